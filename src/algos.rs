@@ -1,22 +1,62 @@
 #[cfg(test)]
 mod test_merkle_tree_generic;
 
+#[cfg(any(feature = "blake2b_256_hash", test, bench))]
+mod blake2b_256;
+#[cfg(any(feature = "blake2s_256_hash", test, bench))]
+mod blake2s_256;
 #[cfg(any(feature = "blake3_hash", test, bench))]
 mod blake3;
 #[cfg(any(feature = "keccak256_hash", test, bench))]
 mod keccak256;
 #[cfg(any(feature = "md5_hash", test, bench))]
 mod md5;
+#[cfg(any(feature = "poseidon_bn254_hash", test, bench))]
+mod poseidon_bn254;
 #[cfg(any(feature = "sha256_hash", test, bench))]
 mod sha256;
+#[cfg(any(feature = "sha3_256_hash", test, bench))]
+mod sha3_256;
+#[cfg(any(feature = "sha512_256_hash", test, bench))]
+mod sha512_256;
+#[cfg(any(feature = "xxh3_128_hash", test, bench))]
+mod xxh3_128;
 
+#[cfg(any(feature = "blake2b_256_hash", test, bench))]
+pub use self::blake2b_256::blake2b_256_merkle_tree_hasher::{
+    Blake2b256MerkleTree, Blake2b256MerkleTreeHasher, Blake2bVarMerkleTree,
+};
+#[cfg(any(feature = "blake2s_256_hash", test, bench))]
+pub use self::blake2s_256::blake2s_256_merkle_tree_hasher::{
+    Blake2s256MerkleTree, Blake2s256MerkleTreeHasher,
+};
 #[cfg(any(feature = "blake3_hash", test, bench))]
-pub use self::blake3::{blake3_hash_leaf_values, Blake3MerkleTreeHasher, BlakeMerkleTree};
+pub use self::blake3::{
+    blake3_hash_leaf_values, Blake3MerkleTreeHasher, Blake3XofMerkleTree, BlakeMerkleTree,
+};
 #[cfg(any(feature = "keccak256_hash", test, bench))]
 pub use self::keccak256::keccak256_merkle_tree_hasher::{
     Keccak256MerkleTree, Keccak256MerkleTreeHasher,
 };
 #[cfg(any(feature = "md5_hash", test, bench))]
 pub use self::md5::md5_merkle_tree_hasher::{Md5MerkleTree, Md5MerkleTreeHasher};
+#[cfg(any(feature = "poseidon_bn254_hash", test, bench))]
+pub use self::poseidon_bn254::poseidon_bn254_merkle_tree_hasher::{
+    PoseidonBn254MerkleTree, PoseidonBn254MerkleTreeHasher,
+};
 #[cfg(any(feature = "sha256_hash", test, bench))]
-pub use self::sha256::sha256_merkle_tree_hasher::{Sha256MerkleTree, Sha256MerkleTreeHasher};
+pub use self::sha256::sha256_merkle_tree_hasher::{
+    Sha256MerkleTree, Sha256MerkleTreeHasher, TruncatedSha256MerkleTree,
+};
+#[cfg(any(feature = "sha3_256_hash", test, bench))]
+pub use self::sha3_256::sha3_256_merkle_tree_hasher::{
+    Sha3_256MerkleTree, Sha3_256MerkleTreeHasher,
+};
+#[cfg(any(feature = "sha512_256_hash", test, bench))]
+pub use self::sha512_256::sha512_256_merkle_tree_hasher::{
+    Sha512_256MerkleTree, Sha512_256MerkleTreeHasher,
+};
+#[cfg(any(feature = "xxh3_128_hash", test, bench))]
+pub use self::xxh3_128::xxh3_128_merkle_tree_hasher::{
+    Xxh3_128MerkleTree, Xxh3_128MerkleTreeHasher,
+};
@@ -0,0 +1,4 @@
+#[cfg(any(feature = "blake2b_256_hash", test, bench))]
+pub mod blake2b_256_merkle_tree_hasher;
+#[cfg(any(feature = "blake2b_256_hash", test, bench))]
+mod test_blake2b_256_merkle_tree;
@@ -0,0 +1,129 @@
+#[cfg(any(feature = "blake2b_256_hash", test))]
+use blake2::digest::consts::U32;
+#[cfg(any(feature = "blake2b_256_hash", test))]
+use blake2::{Blake2b, Blake2bVar, Digest};
+
+#[cfg(any(feature = "blake2b_256_hash", test))]
+use crate::merkle_tree::MerkleTree;
+#[cfg(any(feature = "blake2b_256_hash", test))]
+use crate::merkle_tree_hasher::LEAF_PREFIX;
+
+#[cfg(any(feature = "blake2b_256_hash", test))]
+use crate::merkle_tree_hasher::{
+    impl_cryptographic_digests, CryptographicHasher, MerkleTreeHasher,
+};
+
+#[cfg(any(feature = "blake2b_256_hash", test))]
+use crate::HashAlgorithm;
+
+#[cfg(any(test))]
+use crate::utils::hash_values;
+
+/// Create a [MerkleTree] using [Blake2b256MerkleTreeHasher]. Enabled using the 'blake2b_256_hash' feature.
+#[cfg(any(feature = "blake2b_256_hash", test))]
+pub type Blake2b256MerkleTree = MerkleTree<[u8; 32], Blake2b256MerkleTreeHasher>;
+
+/// Create a [MerkleTree] using BLAKE2b with an `N` byte output, from 1 to 64 bytes.
+///
+/// Enabled using the 'blake2b_256_hash' feature. `Blake2bVarMerkleTree<32>` has the same root as [Blake2b256MerkleTree].
+/// Only trees of at least 16 bytes are [CryptographicHasher] trees, whose proofs can be verified.
+#[cfg(any(feature = "blake2b_256_hash", test))]
+pub type Blake2bVarMerkleTree<const N: usize> =
+    MerkleTree<crate::Digest<N>, Blake2b256MerkleTreeHasher>;
+
+/// Hasher for a Merkle Tree using BLAKE2b-256 Hashing. Enabled using the 'blake2b_256_hash' feature.
+///
+/// BLAKE2b with a 32 byte output.
+///
+/// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
+#[cfg(any(feature = "blake2b_256_hash", test))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Blake2b256MerkleTreeHasher {}
+
+#[cfg(any(feature = "blake2b_256_hash", test))]
+impl CryptographicHasher<[u8; 32]> for Blake2b256MerkleTreeHasher {}
+
+#[cfg(any(feature = "blake2b_256_hash", test))]
+impl MerkleTreeHasher<[u8; 32]> for Blake2b256MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Blake2b256.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Blake2b256)
+    }
+    fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Blake2b::<U32>::new();
+        hasher.update(LEAF_PREFIX);
+        hasher.update(leaf);
+        hasher.finalize().into()
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 32], rhs: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Blake2b::<U32>::new();
+        hasher.update(prefix);
+        hasher.update(lhs);
+        hasher.update(rhs);
+        hasher.finalize().into()
+    }
+}
+
+#[cfg(any(feature = "blake2b_256_hash", test))]
+impl_cryptographic_digests!(Blake2b256MerkleTreeHasher, 64);
+
+/// Hashes with BLAKE2b set to an `N` byte output, rather than truncating a longer output.
+#[cfg(any(feature = "blake2b_256_hash", test))]
+impl<const N: usize> MerkleTreeHasher<crate::Digest<N>> for Blake2b256MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Blake2b256.name_for_len(N, 32)
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Blake2b256)
+    }
+    fn hash_leaf(leaf: &crate::Digest<N>) -> crate::Digest<N> {
+        blake2b_var_hash(&[&LEAF_PREFIX, leaf.as_ref()])
+    }
+    fn hash_non_leaf_node(
+        prefix: &[u8; 1],
+        lhs: &crate::Digest<N>,
+        rhs: &crate::Digest<N>,
+    ) -> crate::Digest<N> {
+        blake2b_var_hash(&[prefix, lhs.as_ref(), rhs.as_ref()])
+    }
+}
+
+#[cfg(any(feature = "blake2b_256_hash", test))]
+#[inline(always)]
+fn blake2b_var_hash<const N: usize>(parts: &[&[u8]]) -> crate::Digest<N> {
+    use blake2::digest::{Update, VariableOutput};
+
+    const { assert!(N > 0 && N <= 64, "a BLAKE2b digest is 1 to 64 bytes long") };
+    // Creating a new Hasher each call allows for multi-threading later.
+    let mut hasher = Blake2bVar::new(N).expect("the output length was checked above");
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut digest = crate::Digest::<N>::default();
+    hasher
+        .finalize_variable(&mut digest.0)
+        .expect("the digest has the output length");
+    digest
+}
+
+/// Convenience function used for testing to create BLAKE2b-256 hashes from strs.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn blake2b_256_hash_leaf_values(values: &[&str]) -> Vec<[u8; 32]> {
+    hash_values(values, blake2b_256_hash_into_bytes)
+}
+
+/// Convenience function used for testing to create BLAKE2b-256 hashes.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn blake2b_256_hash_into_bytes(value: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(value);
+    hasher.finalize().into()
+}
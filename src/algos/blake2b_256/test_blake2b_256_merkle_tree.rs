@@ -0,0 +1,136 @@
+#[cfg(test)]
+mod tests {
+    use blake2::digest::{Update, VariableOutput};
+    use blake2::Blake2bVar;
+
+    use crate::algos::blake2b_256::blake2b_256_merkle_tree_hasher::{
+        blake2b_256_hash_leaf_values, Blake2b256MerkleTree, Blake2b256MerkleTreeHasher,
+        Blake2bVarMerkleTree,
+    };
+    use crate::algos::test_merkle_tree_generic::{
+        merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        thwart_second_image_attack_using_duplicate_odd_node,
+        thwart_second_image_attack_using_interior_nodes,
+        thwart_second_image_attack_using_root_node,
+    };
+    use crate::utils::hash_values;
+    use crate::Digest;
+
+    type ValueType = [u8; 32];
+    type TestMerkleTreeHasher = Blake2b256MerkleTreeHasher;
+    const HASH_VALUES_FN: fn(&[&str]) -> Vec<ValueType> = blake2b_256_hash_leaf_values;
+
+    #[test]
+    fn blake2b_256_merkle_size_test() {
+        merkle_size_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2b_256_merkle_tree_serde_test() {
+        merkle_tree_serde_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2b_256_merkle_tree_serde_failed_test() {
+        merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2b_256_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    /// Added to check backwards compatibility of Merkle Root value.
+    ///
+    /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
+    #[test]
+    fn blake2b_256_merkle_root_calculation_test() {
+        merkle_root_calculation_test::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+            "358e698757acf78d494a88a76fcbcdc9baeec9239462607035ba214cc7d4ed88",
+        );
+    }
+
+    #[test]
+    fn blake2b_256_merkle_test_repeatable_values() {
+        merkle_test_repeatable_values::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2b_256_thwart_second_image_attack_using_interior_nodes() {
+        thwart_second_image_attack_using_interior_nodes::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn blake2b_256_thwart_second_image_attack_using_root_node() {
+        thwart_second_image_attack_using_root_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn blake2b_256_thwart_second_image_attack_using_duplicate_odd_node() {
+        thwart_second_image_attack_using_duplicate_odd_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    type VarValueType = Digest<20>;
+
+    fn blake2b_var_hash_leaf_values(values: &[&str]) -> Vec<VarValueType> {
+        hash_values(values, |value| {
+            let mut hasher = Blake2bVar::new(20).unwrap();
+            hasher.update(value);
+            let mut digest = VarValueType::default();
+            hasher.finalize_variable(&mut digest.0).unwrap();
+            digest
+        })
+    }
+
+    #[test]
+    fn blake2b_var_merkle_size_test() {
+        merkle_size_test::<VarValueType, TestMerkleTreeHasher>(&blake2b_var_hash_leaf_values);
+    }
+
+    #[test]
+    fn blake2b_var_merkle_tree_serde_test() {
+        merkle_tree_serde_test::<VarValueType, TestMerkleTreeHasher>(&blake2b_var_hash_leaf_values);
+    }
+
+    #[test]
+    fn blake2b_var_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<VarValueType, TestMerkleTreeHasher>(
+            &blake2b_var_hash_leaf_values,
+        );
+    }
+
+    /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
+    #[test]
+    fn blake2b_var_merkle_root_calculation_test() {
+        merkle_root_calculation_test::<VarValueType, TestMerkleTreeHasher>(
+            &blake2b_var_hash_leaf_values,
+            "190499bbad664dd9baa1e253b735ff8942435a92",
+        );
+    }
+
+    #[test]
+    fn blake2b_var_thwart_second_image_attack_using_duplicate_odd_node() {
+        thwart_second_image_attack_using_duplicate_odd_node::<VarValueType, TestMerkleTreeHasher>(
+            &blake2b_var_hash_leaf_values,
+        );
+    }
+
+    /// A 32 byte output builds the same tree as [Blake2b256MerkleTree].
+    #[test]
+    fn blake2b_var_default_length_test() {
+        let leaves = HASH_VALUES_FN(&["a", "b", "c", "d", "e"]);
+        let digests: Vec<Digest<32>> = leaves.iter().copied().map(Digest::from).collect();
+        let tree = Blake2b256MerkleTree::new(&leaves).unwrap();
+        let var_tree = Blake2bVarMerkleTree::<32>::new(&digests).unwrap();
+        assert_eq!(tree.root(), var_tree.root().into_bytes());
+        assert_eq!(tree.to_bytes().unwrap(), var_tree.to_bytes().unwrap());
+    }
+}
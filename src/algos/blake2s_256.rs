@@ -0,0 +1,4 @@
+#[cfg(any(feature = "blake2s_256_hash", test, bench))]
+pub mod blake2s_256_merkle_tree_hasher;
+#[cfg(any(feature = "blake2s_256_hash", test, bench))]
+mod test_blake2s_256_merkle_tree;
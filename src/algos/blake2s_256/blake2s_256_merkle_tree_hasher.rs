@@ -0,0 +1,75 @@
+#[cfg(any(feature = "blake2s_256_hash", test))]
+use blake2::{Blake2s256, Digest};
+
+#[cfg(any(feature = "blake2s_256_hash", test))]
+use crate::merkle_tree::MerkleTree;
+#[cfg(any(feature = "blake2s_256_hash", test))]
+use crate::merkle_tree_hasher::LEAF_PREFIX;
+
+#[cfg(any(feature = "blake2s_256_hash", test))]
+use crate::merkle_tree_hasher::{CryptographicHasher, MerkleTreeHasher};
+
+#[cfg(any(feature = "blake2s_256_hash", test))]
+use crate::HashAlgorithm;
+
+#[cfg(any(test))]
+use crate::utils::hash_values;
+
+/// Create a [MerkleTree] using [Blake2s256MerkleTreeHasher]. Enabled using the 'blake2s_256_hash' feature.
+#[cfg(any(feature = "blake2s_256_hash", test))]
+pub type Blake2s256MerkleTree = MerkleTree<[u8; 32], Blake2s256MerkleTreeHasher>;
+
+/// Hasher for a Merkle Tree using BLAKE2s-256 Hashing. Enabled using the 'blake2s_256_hash' feature.
+///
+/// BLAKE2s with a 32 byte output, suited to 32 bit CPUs.
+///
+/// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
+#[cfg(any(feature = "blake2s_256_hash", test))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Blake2s256MerkleTreeHasher {}
+
+#[cfg(any(feature = "blake2s_256_hash", test))]
+impl CryptographicHasher<[u8; 32]> for Blake2s256MerkleTreeHasher {}
+
+#[cfg(any(feature = "blake2s_256_hash", test))]
+impl MerkleTreeHasher<[u8; 32]> for Blake2s256MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Blake2s256.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Blake2s256)
+    }
+    fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Blake2s256::new();
+        hasher.update(LEAF_PREFIX);
+        hasher.update(leaf);
+        hasher.finalize().into()
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 32], rhs: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Blake2s256::new();
+        hasher.update(prefix);
+        hasher.update(lhs);
+        hasher.update(rhs);
+        hasher.finalize().into()
+    }
+}
+
+/// Convenience function used for testing to create BLAKE2s-256 hashes from strs.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn blake2s_256_hash_leaf_values(values: &[&str]) -> Vec<[u8; 32]> {
+    hash_values(values, blake2s_256_hash_into_bytes)
+}
+
+/// Convenience function used for testing to create BLAKE2s-256 hashes.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn blake2s_256_hash_into_bytes(value: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2s256::new();
+    hasher.update(value);
+    hasher.finalize().into()
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::blake2s_256::blake2s_256_merkle_tree_hasher::{
+        blake2s_256_hash_leaf_values, Blake2s256MerkleTreeHasher,
+    };
+    use crate::algos::test_merkle_tree_generic::{
+        merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        thwart_second_image_attack_using_duplicate_odd_node,
+        thwart_second_image_attack_using_interior_nodes,
+        thwart_second_image_attack_using_root_node,
+    };
+
+    type ValueType = [u8; 32];
+    type TestMerkleTreeHasher = Blake2s256MerkleTreeHasher;
+    const HASH_VALUES_FN: fn(&[&str]) -> Vec<ValueType> = blake2s_256_hash_leaf_values;
+
+    #[test]
+    fn blake2s_256_merkle_size_test() {
+        merkle_size_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2s_256_merkle_tree_serde_test() {
+        merkle_tree_serde_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2s_256_merkle_tree_serde_failed_test() {
+        merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2s_256_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    /// Added to check backwards compatibility of Merkle Root value.
+    ///
+    /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
+    #[test]
+    fn blake2s_256_merkle_root_calculation_test() {
+        merkle_root_calculation_test::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+            "fc16e8445b3fcf4c89cae54cff4be982b33afce3498467a67e8fd860e98beb01",
+        );
+    }
+
+    #[test]
+    fn blake2s_256_merkle_test_repeatable_values() {
+        merkle_test_repeatable_values::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn blake2s_256_thwart_second_image_attack_using_interior_nodes() {
+        thwart_second_image_attack_using_interior_nodes::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn blake2s_256_thwart_second_image_attack_using_root_node() {
+        thwart_second_image_attack_using_root_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn blake2s_256_thwart_second_image_attack_using_duplicate_odd_node() {
+        thwart_second_image_attack_using_duplicate_odd_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+}
@@ -1,11 +1,19 @@
 #[cfg(any(feature = "blake3_hash", test))]
-use crate::merkle_tree_hasher::LEAF_PREFIX;
+use crate::merkle_tree_hasher::{
+    CHILD_ROOT_TAG, CONTENT_CHUNK_TAG, DIRECTORY_ENTRY_TAG, LEAF_PREFIX,
+};
 
 #[cfg(any(feature = "blake3_hash", test))]
 use crate::merkle_tree::MerkleTree;
 
 #[cfg(any(feature = "blake3_hash", test))]
-use crate::merkle_tree_hasher::MerkleTreeHasher;
+use crate::merkle_tree_hasher::{
+    impl_cryptographic_digests, AnnotatedMerkleTreeHasher, CryptographicHasher,
+    DirectoryMerkleTreeHasher, MerkleTreeHasher, NestedMerkleTreeHasher,
+};
+
+#[cfg(any(feature = "blake3_hash", test))]
+use crate::{Digest, HashAlgorithm};
 
 #[cfg(any(feature = "blake3_hash", test))]
 use crate::utils::hash_values;
@@ -16,6 +24,13 @@ use crate::utils::hash_values;
 #[cfg(any(feature = "blake3_hash", test))]
 pub type BlakeMerkleTree = MerkleTree<[u8; 32], Blake3MerkleTreeHasher>;
 
+/// Create a [MerkleTree] using the Blake3 extendable output of [Blake3MerkleTreeHasher] with `N` byte hashes.
+///
+/// Enabled using the 'blake3_hash' feature. `Blake3XofMerkleTree<32>` has the same root as [BlakeMerkleTree].
+/// Only trees of 16 to 64 bytes are [CryptographicHasher] trees, whose proofs can be verified.
+#[cfg(any(feature = "blake3_hash", test))]
+pub type Blake3XofMerkleTree<const N: usize> = MerkleTree<Digest<N>, Blake3MerkleTreeHasher>;
+
 /// Hash using [`blake3::Hasher`](blake3::Hasher). Enabled using the 'blake3_hash' feature.
 ///
 /// Blake3 was chosen as an example vs. Kangaroo12 to allow fast hashing on commodity hardware.
@@ -27,10 +42,16 @@ pub type BlakeMerkleTree = MerkleTree<[u8; 32], Blake3MerkleTreeHasher>;
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Blake3MerkleTreeHasher {}
 
+#[cfg(any(feature = "blake3_hash", test))]
+impl CryptographicHasher<[u8; 32]> for Blake3MerkleTreeHasher {}
+
 #[cfg(any(feature = "blake3_hash", test))]
 impl MerkleTreeHasher<[u8; 32]> for Blake3MerkleTreeHasher {
     fn name(&self) -> String {
-        "Blake3".to_string()
+        HashAlgorithm::Blake3.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Blake3)
     }
     fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
         // Creating a new Hasher each call allows for multi-threading later.
@@ -49,6 +70,95 @@ impl MerkleTreeHasher<[u8; 32]> for Blake3MerkleTreeHasher {
     }
 }
 
+#[cfg(any(feature = "blake3_hash", test))]
+impl AnnotatedMerkleTreeHasher<[u8; 32]> for Blake3MerkleTreeHasher {
+    fn hash_annotated_leaf(leaf: &[u8; 32], annotation: &[u8]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&LEAF_PREFIX);
+        hasher.update(leaf);
+        hasher.update(annotation);
+        hasher.finalize().as_bytes().to_owned()
+    }
+    fn hash_annotated_node(
+        prefix: &[u8; 1],
+        lhs: &[u8; 32],
+        rhs: &[u8; 32],
+        annotation: &[u8],
+    ) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(prefix);
+        hasher.update(lhs);
+        hasher.update(rhs);
+        hasher.update(annotation);
+        hasher.finalize().as_bytes().to_owned()
+    }
+}
+
+#[cfg(any(feature = "blake3_hash", test))]
+impl NestedMerkleTreeHasher<[u8; 32]> for Blake3MerkleTreeHasher {
+    fn hash_child_root(root: &[u8; 32], height: u8) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&LEAF_PREFIX);
+        hasher.update(CHILD_ROOT_TAG);
+        hasher.update(&[height]);
+        hasher.update(root);
+        hasher.finalize().as_bytes().to_owned()
+    }
+}
+
+#[cfg(any(feature = "blake3_hash", test))]
+impl DirectoryMerkleTreeHasher<[u8; 32]> for Blake3MerkleTreeHasher {
+    fn hash_content_chunk(chunk: &[u8]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(CONTENT_CHUNK_TAG);
+        hasher.update(chunk);
+        hasher.finalize().as_bytes().to_owned()
+    }
+    fn hash_directory_entry(entry: &[u8], root: &[u8; 32]) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(DIRECTORY_ENTRY_TAG);
+        hasher.update(root);
+        hasher.update(entry);
+        hasher.finalize().as_bytes().to_owned()
+    }
+}
+
+#[cfg(any(feature = "blake3_hash", test))]
+impl_cryptographic_digests!(Blake3MerkleTreeHasher, 64);
+
+/// Reads `N` bytes of the Blake3 extendable output for every hash.
+#[cfg(any(feature = "blake3_hash", test))]
+impl<const N: usize> MerkleTreeHasher<Digest<N>> for Blake3MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Blake3.name_for_len(N, 32)
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Blake3)
+    }
+    fn hash_leaf(leaf: &Digest<N>) -> Digest<N> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&LEAF_PREFIX);
+        hasher.update(leaf.as_ref());
+        finalize_xof(&hasher)
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &Digest<N>, rhs: &Digest<N>) -> Digest<N> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(prefix);
+        hasher.update(lhs.as_ref());
+        hasher.update(rhs.as_ref());
+        finalize_xof(&hasher)
+    }
+}
+
+#[cfg(any(feature = "blake3_hash", test))]
+#[inline(always)]
+fn finalize_xof<const N: usize>(hasher: &blake3::Hasher) -> Digest<N> {
+    const { assert!(N > 0, "a Blake3 digest needs at least one byte") };
+    let mut digest = Digest::<N>::default();
+    hasher.finalize_xof().fill(&mut digest.0);
+    digest
+}
+
 /// Convenience function used for testing to create Blake3 hashes from strs.
 #[cfg(any(feature = "blake3_hash", test))]
 #[doc(hidden)]
@@ -2,12 +2,14 @@
 mod tests {
     use crate::algos::test_merkle_tree_generic::{
         merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
-        merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
         thwart_second_image_attack_using_duplicate_odd_node,
         thwart_second_image_attack_using_interior_nodes,
         thwart_second_image_attack_using_root_node,
     };
     use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::utils::hash_values;
+    use crate::{Blake3XofMerkleTree, BlakeMerkleTree, Digest};
 
     type ValueType = [u8; 32];
     type TestMerkleTreeHasher = Blake3MerkleTreeHasher;
@@ -28,6 +30,11 @@ mod tests {
         merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
     }
 
+    #[test]
+    fn blake3_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
     /// Added to check backwards compatibility of Merkle Root value.
     ///
     /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
@@ -64,4 +71,61 @@ mod tests {
             &HASH_VALUES_FN,
         );
     }
+
+    type XofValueType = Digest<64>;
+
+    fn blake3_xof_hash_leaf_values(values: &[&str]) -> Vec<XofValueType> {
+        hash_values(values, |value| {
+            let mut digest = XofValueType::default();
+            blake3::Hasher::new()
+                .update(value)
+                .finalize_xof()
+                .fill(&mut digest.0);
+            digest
+        })
+    }
+
+    #[test]
+    fn blake3_xof_merkle_size_test() {
+        merkle_size_test::<XofValueType, TestMerkleTreeHasher>(&blake3_xof_hash_leaf_values);
+    }
+
+    #[test]
+    fn blake3_xof_merkle_tree_serde_test() {
+        merkle_tree_serde_test::<XofValueType, TestMerkleTreeHasher>(&blake3_xof_hash_leaf_values);
+    }
+
+    #[test]
+    fn blake3_xof_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<XofValueType, TestMerkleTreeHasher>(
+            &blake3_xof_hash_leaf_values,
+        );
+    }
+
+    /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
+    #[test]
+    fn blake3_xof_merkle_root_calculation_test() {
+        merkle_root_calculation_test::<XofValueType, TestMerkleTreeHasher>(
+            &blake3_xof_hash_leaf_values,
+            "81ccbcaed8a1859ecb649a713c0ee4ea708acedc3fec8efd9f2778e8cafb95e7007f18b614f05ef436be830f4d11fd3067916a6f400e9df2eabf915f3249b9a7",
+        );
+    }
+
+    #[test]
+    fn blake3_xof_thwart_second_image_attack_using_interior_nodes() {
+        thwart_second_image_attack_using_interior_nodes::<XofValueType, TestMerkleTreeHasher>(
+            &blake3_xof_hash_leaf_values,
+        );
+    }
+
+    /// The first 32 bytes of the extendable output are the regular Blake3 hash.
+    #[test]
+    fn blake3_xof_default_length_test() {
+        let leaves = HASH_VALUES_FN(&["a", "b", "c", "d", "e"]);
+        let digests: Vec<Digest<32>> = leaves.iter().copied().map(Digest::from).collect();
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        let xof_tree = Blake3XofMerkleTree::<32>::new(&digests).unwrap();
+        assert_eq!(tree.root(), xof_tree.root().into_bytes());
+        assert_eq!(tree.to_bytes().unwrap(), xof_tree.to_bytes().unwrap());
+    }
 }
@@ -1,5 +1,5 @@
 #[cfg(any(feature = "keccak256_hash", test))]
-use ring::digest::{Context, SHA256};
+use sha3::{Digest, Keccak256};
 
 #[cfg(any(feature = "keccak256_hash", test))]
 use crate::merkle_tree_hasher::LEAF_PREFIX;
@@ -8,7 +8,10 @@ use crate::merkle_tree_hasher::LEAF_PREFIX;
 use crate::merkle_tree::MerkleTree;
 
 #[cfg(any(feature = "keccak256_hash", test))]
-use crate::merkle_tree_hasher::MerkleTreeHasher;
+use crate::merkle_tree_hasher::{CryptographicHasher, MerkleTreeHasher};
+
+#[cfg(any(feature = "keccak256_hash", test))]
+use crate::HashAlgorithm;
 
 #[cfg(any(test))]
 use crate::utils::hash_values;
@@ -19,32 +22,38 @@ pub type Keccak256MerkleTree = MerkleTree<[u8; 32], Keccak256MerkleTreeHasher>;
 
 /// Hasher for a Merkle Tree using Keccak-256 Hashing. Enabled using the 'keccak256_hash' feature.
 ///
+/// This is the original Keccak padding used by Ethereum, not the standardized SHA3-256.
+///
 /// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
 #[cfg(any(feature = "keccak256_hash", test))]
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Keccak256MerkleTreeHasher {}
 
+#[cfg(any(feature = "keccak256_hash", test))]
+impl CryptographicHasher<[u8; 32]> for Keccak256MerkleTreeHasher {}
+
 #[cfg(any(feature = "keccak256_hash", test))]
 impl MerkleTreeHasher<[u8; 32]> for Keccak256MerkleTreeHasher {
     fn name(&self) -> String {
-        "SHA-256".to_string()
+        HashAlgorithm::Keccak256.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Keccak256)
     }
     fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
-        // Creating a new Context each call allows for multi-threading later.
-        let mut context = Context::new(&SHA256);
-        context.update(&LEAF_PREFIX);
-        context.update(leaf);
-        let digest = context.finish();
-        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Keccak256::new();
+        hasher.update(LEAF_PREFIX);
+        hasher.update(leaf);
+        hasher.finalize().into()
     }
     fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 32], rhs: &[u8; 32]) -> [u8; 32] {
-        // Creating a new Context each call allows for multi-threading later.
-        let mut context = Context::new(&SHA256);
-        context.update(prefix);
-        context.update(lhs);
-        context.update(rhs);
-        let digest = context.finish();
-        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Keccak256::new();
+        hasher.update(prefix);
+        hasher.update(lhs);
+        hasher.update(rhs);
+        hasher.finalize().into()
     }
 }
 
@@ -61,8 +70,7 @@ pub(crate) fn keccak256_hash_leaf_values(values: &[&str]) -> Vec<[u8; 32]> {
 #[doc(hidden)]
 #[inline(always)]
 pub(crate) fn keccak256_hash_into_bytes(value: &[u8]) -> [u8; 32] {
-    let mut context = ring::digest::Context::new(&SHA256);
-    context.update(value);
-    let digest = context.finish();
-    <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    let mut hasher = Keccak256::new();
+    hasher.update(value);
+    hasher.finalize().into()
 }
@@ -6,7 +6,7 @@ mod tests {
     };
     use crate::algos::test_merkle_tree_generic::{
         merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
-        merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
         thwart_second_image_attack_using_duplicate_odd_node,
         thwart_second_image_attack_using_interior_nodes,
         thwart_second_image_attack_using_root_node,
@@ -31,6 +31,11 @@ mod tests {
         merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
     }
 
+    #[test]
+    fn keccak256_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
     /// Added to check backwards compatibility of Merkle Root value.
     ///
     /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
@@ -38,7 +43,7 @@ mod tests {
     fn keccak256_merkle_root_calculation_test() {
         merkle_root_calculation_test::<ValueType, TestMerkleTreeHasher>(
             &HASH_VALUES_FN,
-            "52a195c0c851e24cf9e99cf7f61552bd24d25a1fb784a5657b3b9d4634aec633",
+            "a9c3ff3aaafa9bf4ae4d6a2edc57123b126ae80fc9fe60f6992e6579b7604ce8",
         );
     }
 
@@ -1,11 +1,16 @@
 #[cfg(any(feature = "md5_hash", test))]
 use crate::merkle_tree_hasher::LEAF_PREFIX;
 
+#[cfg(any(feature = "md5_hash", test))]
+use crate::merkle_proof::MerkleProof;
 #[cfg(any(feature = "md5_hash", test))]
 use crate::merkle_tree::MerkleTree;
 
 #[cfg(any(feature = "md5_hash", test))]
-use crate::merkle_tree_hasher::MerkleTreeHasher;
+use crate::merkle_tree_hasher::{MerkleTreeHasher, NonCryptographic};
+
+#[cfg(any(feature = "md5_hash", test))]
+use crate::HashAlgorithm;
 
 #[cfg(any(test))]
 use crate::utils::hash_values;
@@ -18,15 +23,39 @@ pub type Md5MerkleTree = MerkleTree<[u8; 16], Md5MerkleTreeHasher>;
 
 /// Hash using MD5. Enabled using the 'md5_hash' feature.
 ///
+/// MD5 collisions are cheap to compute, so this hasher is marked [NonCryptographic].
+///
 /// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
 #[cfg(any(feature = "md5_hash", test))]
 #[derive(Debug, Default, Copy, Clone)]
 pub struct Md5MerkleTreeHasher {}
 
+#[cfg(any(feature = "md5_hash", test))]
+impl NonCryptographic for Md5MerkleTreeHasher {}
+
+/// Keeps the proof checks MD5 trees had before MD5 was marked [NonCryptographic].
+#[cfg(any(feature = "md5_hash", test))]
+impl MerkleProof<[u8; 16], Md5MerkleTreeHasher> {
+    /// Determine whether or not the specified leaf is valid for this Merkle Proof.
+    #[deprecated(note = "MD5 proofs can be forged, only use them to detect accidental changes")]
+    pub fn validate_proof(&self, leaf: &[u8; 16]) -> bool {
+        self.leads_to_root(leaf)
+    }
+
+    /// Determine whether or not `leaf` belongs to the tree with the root `root`.
+    #[deprecated(note = "MD5 proofs can be forged, only use them to detect accidental changes")]
+    pub fn verify(&self, leaf: &[u8; 16], root: &[u8; 16]) -> bool {
+        self.sibling_hashes.last() == Some(root) && self.leads_to_root(leaf)
+    }
+}
+
 #[cfg(any(feature = "md5_hash", test))]
 impl MerkleTreeHasher<[u8; 16]> for Md5MerkleTreeHasher {
     fn name(&self) -> String {
-        "MD5".to_string()
+        HashAlgorithm::Md5.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Md5)
     }
     fn hash_leaf(leaf: &[u8; 16]) -> [u8; 16] {
         let mut context = md5::Context::new();
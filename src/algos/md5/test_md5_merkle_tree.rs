@@ -1,10 +1,12 @@
 #[cfg(test)]
 mod tests {
 
-    use crate::algos::md5::md5_merkle_tree_hasher::{md5_hash_leaf_values, Md5MerkleTreeHasher};
+    use crate::algos::md5::md5_merkle_tree_hasher::{
+        md5_hash_leaf_values, Md5MerkleTree, Md5MerkleTreeHasher,
+    };
     use crate::algos::test_merkle_tree_generic::{
         merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
-        merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
         thwart_second_image_attack_using_duplicate_odd_node,
         thwart_second_image_attack_using_interior_nodes,
         thwart_second_image_attack_using_root_node,
@@ -29,6 +31,11 @@ mod tests {
         merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
     }
 
+    #[test]
+    fn md5_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
     /// Added to check backwards compatibility of Merkle Root value.
     ///
     /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
@@ -65,4 +72,17 @@ mod tests {
             &HASH_VALUES_FN,
         );
     }
+
+    #[test]
+    #[allow(deprecated)]
+    fn md5_deprecated_proof_checks_test() {
+        let leaves = HASH_VALUES_FN(&["a", "b", "c", "d", "e"]);
+        let tree = Md5MerkleTree::new(&leaves).unwrap();
+        for (idx, leaf) in leaves.iter().enumerate() {
+            let proof = tree.build_proof(idx).unwrap();
+            assert!(proof.validate_proof(leaf));
+            assert!(proof.verify(leaf, &tree.root()));
+            assert!(!proof.verify(leaf, leaf));
+        }
+    }
 }
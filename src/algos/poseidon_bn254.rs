@@ -0,0 +1,4 @@
+#[cfg(any(feature = "poseidon_bn254_hash", test, bench))]
+pub mod poseidon_bn254_merkle_tree_hasher;
+#[cfg(any(feature = "poseidon_bn254_hash", test, bench))]
+mod test_poseidon_bn254_merkle_tree;
@@ -0,0 +1,107 @@
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+use ark_bn254::Fr;
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+use ark_ff::{BigInteger, PrimeField};
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+use light_poseidon::{Poseidon, PoseidonHasher};
+
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+use crate::merkle_tree::MerkleTree;
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+use crate::merkle_tree_hasher::LEAF_PREFIX;
+
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+use crate::merkle_tree_hasher::{CryptographicHasher, MerkleTreeHasher};
+
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+use crate::HashAlgorithm;
+
+#[cfg(any(test))]
+use crate::utils::hash_values;
+
+/// Create a [MerkleTree] using [PoseidonBn254MerkleTreeHasher]. Enabled using the 'poseidon_bn254_hash' feature.
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+pub type PoseidonBn254MerkleTree = MerkleTree<[u8; 32], PoseidonBn254MerkleTreeHasher>;
+
+/// Hasher for a Merkle Tree using the circomlib Poseidon hash over the BN254 scalar field.
+/// Enabled using the 'poseidon_bn254_hash' feature.
+///
+/// Hashes are field elements encoded as 32 big-endian bytes. The prefixes are hashed as
+/// field elements too, so a circuit rebuilds the same root with circomlib's `Poseidon` template:
+///
+/// - a leaf is `Poseidon(2)([0, leaf])`,
+/// - a non-leaf node is `Poseidon(3)([prefix, lhs, rhs])`, using the same prefixes as every
+///   other [MerkleTreeHasher].
+///
+/// Leaves not below the field modulus are reduced modulo it, so distinct byte arrays may hash
+/// alike. Use [`is_field_element`](PoseidonBn254MerkleTreeHasher::is_field_element) to reject them.
+///
+/// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PoseidonBn254MerkleTreeHasher {}
+
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+impl PoseidonBn254MerkleTreeHasher {
+    /// True if `value` is the canonical encoding of a BN254 scalar field element.
+    pub fn is_field_element(value: &[u8; 32]) -> bool {
+        value.as_slice() < Fr::MODULUS.to_bytes_be().as_slice()
+    }
+}
+
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+impl CryptographicHasher<[u8; 32]> for PoseidonBn254MerkleTreeHasher {}
+
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+impl MerkleTreeHasher<[u8; 32]> for PoseidonBn254MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::PoseidonBn254.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::PoseidonBn254)
+    }
+    fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
+        poseidon(&[Fr::from(LEAF_PREFIX[0]), to_field_element(leaf)])
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 32], rhs: &[u8; 32]) -> [u8; 32] {
+        poseidon(&[
+            Fr::from(prefix[0]),
+            to_field_element(lhs),
+            to_field_element(rhs),
+        ])
+    }
+}
+
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+#[inline(always)]
+fn to_field_element(value: &[u8; 32]) -> Fr {
+    Fr::from_be_bytes_mod_order(value)
+}
+
+/// Hashes `inputs` with the circomlib parameters for their number.
+#[cfg(any(feature = "poseidon_bn254_hash", test))]
+pub(crate) fn poseidon(inputs: &[Fr]) -> [u8; 32] {
+    // Creating a new Poseidon each call allows for multi-threading later.
+    // Both calls only fail for more than 12 inputs.
+    let mut hasher = Poseidon::<Fr>::new_circom(inputs.len()).unwrap();
+    let hash = hasher.hash(inputs).unwrap();
+    <[u8; 32]>::try_from(hash.into_bigint().to_bytes_be()).unwrap()
+}
+
+/// Convenience function used for testing to create Poseidon hashes from strs.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn poseidon_bn254_hash_leaf_values(values: &[&str]) -> Vec<[u8; 32]> {
+    hash_values(values, poseidon_bn254_hash_into_bytes)
+}
+
+/// Convenience function used for testing to create Poseidon hashes.
+///
+/// `value` is read as one big-endian field element.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn poseidon_bn254_hash_into_bytes(value: &[u8]) -> [u8; 32] {
+    poseidon(&[Fr::from_be_bytes_mod_order(value)])
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use rustc_serialize::hex::ToHex;
+
+    use crate::algos::poseidon_bn254::poseidon_bn254_merkle_tree_hasher::{
+        poseidon, poseidon_bn254_hash_leaf_values, PoseidonBn254MerkleTreeHasher,
+    };
+    use crate::algos::test_merkle_tree_generic::{
+        merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        thwart_second_image_attack_using_duplicate_odd_node,
+        thwart_second_image_attack_using_interior_nodes,
+        thwart_second_image_attack_using_root_node,
+    };
+    use crate::merkle_tree_hasher::MerkleTreeHasher;
+
+    type ValueType = [u8; 32];
+    type TestMerkleTreeHasher = PoseidonBn254MerkleTreeHasher;
+    const HASH_VALUES_FN: fn(&[&str]) -> Vec<ValueType> = poseidon_bn254_hash_leaf_values;
+
+    #[test]
+    fn poseidon_bn254_merkle_size_test() {
+        merkle_size_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn poseidon_bn254_merkle_tree_serde_test() {
+        merkle_tree_serde_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn poseidon_bn254_merkle_tree_serde_failed_test() {
+        merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn poseidon_bn254_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    /// Added to check backwards compatibility of Merkle Root value.
+    ///
+    /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
+    #[test]
+    fn poseidon_bn254_merkle_root_calculation_test() {
+        merkle_root_calculation_test::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+            "0f132f88e4042f8fde69641f21e20c95d30b195953937eb3049bbf13e28e2601",
+        );
+    }
+
+    #[test]
+    fn poseidon_bn254_merkle_test_repeatable_values() {
+        merkle_test_repeatable_values::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn poseidon_bn254_thwart_second_image_attack_using_interior_nodes() {
+        thwart_second_image_attack_using_interior_nodes::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn poseidon_bn254_thwart_second_image_attack_using_root_node() {
+        thwart_second_image_attack_using_root_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn poseidon_bn254_thwart_second_image_attack_using_duplicate_odd_node() {
+        thwart_second_image_attack_using_duplicate_odd_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    /// Test vectors from the circomlib Poseidon reference implementation.
+    #[test]
+    fn poseidon_bn254_circomlib_vectors_test() {
+        assert_eq!(
+            "29176100eaa962bdc1fe6c654d6a3c130e96a4d1168b33848b897dc502820133",
+            poseidon(&[Fr::from(1_u8)]).to_hex()
+        );
+        assert_eq!(
+            "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+            poseidon(&[Fr::from(1_u8), Fr::from(2_u8)]).to_hex()
+        );
+    }
+
+    /// Leaves and nodes are hashed the way a circomlib circuit would hash them.
+    #[test]
+    fn poseidon_bn254_circuit_layout_test() {
+        let mut one = [0_u8; 32];
+        one[31] = 1;
+        let mut two = [0_u8; 32];
+        two[31] = 2;
+
+        assert_eq!(
+            poseidon(&[Fr::from(0_u8), Fr::from(1_u8)]),
+            TestMerkleTreeHasher::hash_leaf(&one)
+        );
+        assert_eq!(
+            poseidon(&[Fr::from(3_u8), Fr::from(1_u8), Fr::from(2_u8)]),
+            TestMerkleTreeHasher::hash_non_leaf_node(&[3], &one, &two)
+        );
+        assert_ne!(
+            TestMerkleTreeHasher::hash_leaf(&one),
+            TestMerkleTreeHasher::hash_non_leaf_node(&[0], &one, &one)
+        );
+    }
+
+    #[test]
+    fn poseidon_bn254_field_element_test() {
+        let hash = HASH_VALUES_FN(&["a"])[0];
+        assert!(TestMerkleTreeHasher::is_field_element(&hash));
+        assert!(TestMerkleTreeHasher::is_field_element(&[0; 32]));
+        assert!(!TestMerkleTreeHasher::is_field_element(&[0xff; 32]));
+    }
+}
@@ -4,10 +4,18 @@ use ring::digest::{Context, SHA256};
 #[cfg(any(feature = "sha256_hash", test))]
 use crate::merkle_tree::MerkleTree;
 #[cfg(any(feature = "sha256_hash", test))]
-use crate::merkle_tree_hasher::LEAF_PREFIX;
+use crate::merkle_tree_hasher::{
+    CHILD_ROOT_TAG, CONTENT_CHUNK_TAG, DIRECTORY_ENTRY_TAG, LEAF_PREFIX,
+};
 
 #[cfg(any(feature = "sha256_hash", test))]
-use crate::merkle_tree_hasher::MerkleTreeHasher;
+use crate::merkle_tree_hasher::{
+    impl_cryptographic_digests, AnnotatedMerkleTreeHasher, CryptographicHasher,
+    DirectoryMerkleTreeHasher, MerkleTreeHasher, NestedMerkleTreeHasher,
+};
+
+#[cfg(any(feature = "sha256_hash", test))]
+use crate::{Digest, HashAlgorithm};
 
 #[cfg(any(test))]
 use crate::utils::hash_values;
@@ -16,6 +24,14 @@ use crate::utils::hash_values;
 #[cfg(any(feature = "sha256_hash", test))]
 pub type Sha256MerkleTree = MerkleTree<[u8; 32], Sha256MerkleTreeHasher>;
 
+/// Create a [MerkleTree] using [Sha256MerkleTreeHasher] truncated to `N` bytes.
+/// Enabled using the 'sha256_hash' feature.
+///
+/// `N` must be between 1 and 32. `TruncatedSha256MerkleTree<32>` has the same root as [Sha256MerkleTree].
+/// Only trees of at least 16 bytes are [CryptographicHasher] trees, whose proofs can be verified.
+#[cfg(any(feature = "sha256_hash", test))]
+pub type TruncatedSha256MerkleTree<const N: usize> = MerkleTree<Digest<N>, Sha256MerkleTreeHasher>;
+
 /// Hasher for a Merkle Tree using SHA-256 Hashing. Enabled using the 'sha256_hash' feature.
 ///
 /// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
@@ -23,10 +39,16 @@ pub type Sha256MerkleTree = MerkleTree<[u8; 32], Sha256MerkleTreeHasher>;
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Sha256MerkleTreeHasher {}
 
+#[cfg(any(feature = "sha256_hash", test))]
+impl CryptographicHasher<[u8; 32]> for Sha256MerkleTreeHasher {}
+
 #[cfg(any(feature = "sha256_hash", test))]
 impl MerkleTreeHasher<[u8; 32]> for Sha256MerkleTreeHasher {
     fn name(&self) -> String {
-        "SHA-256".to_string()
+        HashAlgorithm::Sha256.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Sha256)
     }
     fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
         // Creating a new Context each call allows for multi-threading later.
@@ -47,6 +69,105 @@ impl MerkleTreeHasher<[u8; 32]> for Sha256MerkleTreeHasher {
     }
 }
 
+#[cfg(any(feature = "sha256_hash", test))]
+impl AnnotatedMerkleTreeHasher<[u8; 32]> for Sha256MerkleTreeHasher {
+    fn hash_annotated_leaf(leaf: &[u8; 32], annotation: &[u8]) -> [u8; 32] {
+        let mut context = Context::new(&SHA256);
+        context.update(&LEAF_PREFIX);
+        context.update(leaf);
+        context.update(annotation);
+        let digest = context.finish();
+        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    }
+    fn hash_annotated_node(
+        prefix: &[u8; 1],
+        lhs: &[u8; 32],
+        rhs: &[u8; 32],
+        annotation: &[u8],
+    ) -> [u8; 32] {
+        let mut context = Context::new(&SHA256);
+        context.update(prefix);
+        context.update(lhs);
+        context.update(rhs);
+        context.update(annotation);
+        let digest = context.finish();
+        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    }
+}
+
+#[cfg(any(feature = "sha256_hash", test))]
+impl NestedMerkleTreeHasher<[u8; 32]> for Sha256MerkleTreeHasher {
+    fn hash_child_root(root: &[u8; 32], height: u8) -> [u8; 32] {
+        let mut context = Context::new(&SHA256);
+        context.update(&LEAF_PREFIX);
+        context.update(CHILD_ROOT_TAG);
+        context.update(&[height]);
+        context.update(root);
+        let digest = context.finish();
+        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    }
+}
+
+#[cfg(any(feature = "sha256_hash", test))]
+impl DirectoryMerkleTreeHasher<[u8; 32]> for Sha256MerkleTreeHasher {
+    fn hash_content_chunk(chunk: &[u8]) -> [u8; 32] {
+        let mut context = Context::new(&SHA256);
+        context.update(CONTENT_CHUNK_TAG);
+        context.update(chunk);
+        let digest = context.finish();
+        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    }
+    fn hash_directory_entry(entry: &[u8], root: &[u8; 32]) -> [u8; 32] {
+        let mut context = Context::new(&SHA256);
+        context.update(DIRECTORY_ENTRY_TAG);
+        context.update(root);
+        context.update(entry);
+        let digest = context.finish();
+        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    }
+}
+
+#[cfg(any(feature = "sha256_hash", test))]
+impl_cryptographic_digests!(Sha256MerkleTreeHasher, 32);
+
+/// Truncates every hash to `N` bytes, including the hashes of child nodes.
+#[cfg(any(feature = "sha256_hash", test))]
+impl<const N: usize> MerkleTreeHasher<Digest<N>> for Sha256MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Sha256.name_for_len(N, 32)
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Sha256)
+    }
+    fn hash_leaf(leaf: &Digest<N>) -> Digest<N> {
+        let mut context = Context::new(&SHA256);
+        context.update(&LEAF_PREFIX);
+        context.update(leaf.as_ref());
+        truncate(context.finish().as_ref())
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &Digest<N>, rhs: &Digest<N>) -> Digest<N> {
+        let mut context = Context::new(&SHA256);
+        context.update(prefix);
+        context.update(lhs.as_ref());
+        context.update(rhs.as_ref());
+        truncate(context.finish().as_ref())
+    }
+}
+
+#[cfg(any(feature = "sha256_hash", test))]
+#[inline(always)]
+fn truncate<const N: usize>(digest: &[u8]) -> Digest<N> {
+    const {
+        assert!(
+            N > 0 && N <= 32,
+            "SHA-256 can be truncated to 1 to 32 bytes"
+        )
+    };
+    let mut truncated = Digest::<N>::default();
+    truncated.0.copy_from_slice(&digest[..N]);
+    truncated
+}
+
 /// Convenience function used for testing to create SHA-256 hashes from strs.
 #[cfg(any(test))]
 #[doc(hidden)]
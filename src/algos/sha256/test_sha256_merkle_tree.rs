@@ -1,16 +1,19 @@
 #[cfg(test)]
 mod tests {
     use crate::algos::sha256::sha256_merkle_tree_hasher::{
-        sha256_hash_leaf_values, Sha256MerkleTreeHasher,
+        sha256_hash_into_bytes, sha256_hash_leaf_values, Sha256MerkleTreeHasher,
     };
     use crate::algos::test_merkle_tree_generic::{
         merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
-        merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
         thwart_second_image_attack_using_duplicate_odd_node,
         thwart_second_image_attack_using_interior_nodes,
         thwart_second_image_attack_using_root_node,
     };
 
+    use crate::utils::hash_values;
+    use crate::{Digest, Sha256MerkleTree, TruncatedSha256MerkleTree};
+
     type ValueType = [u8; 32];
     type TestMerkleTreeHasher = Sha256MerkleTreeHasher;
     const HASH_VALUES_FN: fn(&[&str]) -> Vec<ValueType> = sha256_hash_leaf_values;
@@ -30,6 +33,11 @@ mod tests {
         merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
     }
 
+    #[test]
+    fn sha256_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
     /// Added to check backwards compatibility of Merkle Root value.
     ///
     /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
@@ -66,4 +74,61 @@ mod tests {
             &HASH_VALUES_FN,
         );
     }
+
+    type TruncatedValueType = Digest<20>;
+
+    fn truncated_sha256_hash_leaf_values(values: &[&str]) -> Vec<TruncatedValueType> {
+        hash_values(values, |value| {
+            Digest::try_from(&sha256_hash_into_bytes(value)[..20]).unwrap()
+        })
+    }
+
+    #[test]
+    fn truncated_sha256_merkle_size_test() {
+        merkle_size_test::<TruncatedValueType, TestMerkleTreeHasher>(
+            &truncated_sha256_hash_leaf_values,
+        );
+    }
+
+    #[test]
+    fn truncated_sha256_merkle_tree_serde_test() {
+        merkle_tree_serde_test::<TruncatedValueType, TestMerkleTreeHasher>(
+            &truncated_sha256_hash_leaf_values,
+        );
+    }
+
+    #[test]
+    fn truncated_sha256_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<TruncatedValueType, TestMerkleTreeHasher>(
+            &truncated_sha256_hash_leaf_values,
+        );
+    }
+
+    /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
+    #[test]
+    fn truncated_sha256_merkle_root_calculation_test() {
+        merkle_root_calculation_test::<TruncatedValueType, TestMerkleTreeHasher>(
+            &truncated_sha256_hash_leaf_values,
+            "1a88afb95cbe657ef50aec41c8c7be43aed36708",
+        );
+    }
+
+    #[test]
+    fn truncated_sha256_thwart_second_image_attack_using_duplicate_odd_node() {
+        thwart_second_image_attack_using_duplicate_odd_node::<
+            TruncatedValueType,
+            TestMerkleTreeHasher,
+        >(&truncated_sha256_hash_leaf_values);
+    }
+
+    /// Truncating to the full 32 bytes builds the same tree as [Sha256MerkleTree].
+    #[test]
+    fn truncated_sha256_full_length_test() {
+        let leaves = HASH_VALUES_FN(&["a", "b", "c", "d", "e"]);
+        let digests: Vec<Digest<32>> = leaves.iter().copied().map(Digest::from).collect();
+        let tree = Sha256MerkleTree::new(&leaves).unwrap();
+        let truncated_tree = TruncatedSha256MerkleTree::<32>::new(&digests).unwrap();
+        assert_eq!(tree.root(), truncated_tree.root().into_bytes());
+        assert_eq!(tree.to_bytes().unwrap(), truncated_tree.to_bytes().unwrap());
+    }
 }
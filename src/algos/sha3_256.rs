@@ -0,0 +1,4 @@
+#[cfg(any(feature = "sha3_256_hash", test, bench))]
+pub mod sha3_256_merkle_tree_hasher;
+#[cfg(any(feature = "sha3_256_hash", test, bench))]
+mod test_sha3_256_merkle_tree;
@@ -0,0 +1,75 @@
+#[cfg(any(feature = "sha3_256_hash", test))]
+use sha3::{Digest, Sha3_256};
+
+#[cfg(any(feature = "sha3_256_hash", test))]
+use crate::merkle_tree::MerkleTree;
+#[cfg(any(feature = "sha3_256_hash", test))]
+use crate::merkle_tree_hasher::LEAF_PREFIX;
+
+#[cfg(any(feature = "sha3_256_hash", test))]
+use crate::merkle_tree_hasher::{CryptographicHasher, MerkleTreeHasher};
+
+#[cfg(any(feature = "sha3_256_hash", test))]
+use crate::HashAlgorithm;
+
+#[cfg(any(test))]
+use crate::utils::hash_values;
+
+/// Create a [MerkleTree] using [Sha3_256MerkleTreeHasher]. Enabled using the 'sha3_256_hash' feature.
+#[cfg(any(feature = "sha3_256_hash", test))]
+pub type Sha3_256MerkleTree = MerkleTree<[u8; 32], Sha3_256MerkleTreeHasher>;
+
+/// Hasher for a Merkle Tree using SHA3-256 Hashing. Enabled using the 'sha3_256_hash' feature.
+///
+/// This is the standardized SHA3-256, not the original Keccak-256 used by Ethereum.
+///
+/// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
+#[cfg(any(feature = "sha3_256_hash", test))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha3_256MerkleTreeHasher {}
+
+#[cfg(any(feature = "sha3_256_hash", test))]
+impl CryptographicHasher<[u8; 32]> for Sha3_256MerkleTreeHasher {}
+
+#[cfg(any(feature = "sha3_256_hash", test))]
+impl MerkleTreeHasher<[u8; 32]> for Sha3_256MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Sha3_256.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Sha3_256)
+    }
+    fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Sha3_256::new();
+        hasher.update(LEAF_PREFIX);
+        hasher.update(leaf);
+        hasher.finalize().into()
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 32], rhs: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Hasher each call allows for multi-threading later.
+        let mut hasher = Sha3_256::new();
+        hasher.update(prefix);
+        hasher.update(lhs);
+        hasher.update(rhs);
+        hasher.finalize().into()
+    }
+}
+
+/// Convenience function used for testing to create SHA3-256 hashes from strs.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn sha3_256_hash_leaf_values(values: &[&str]) -> Vec<[u8; 32]> {
+    hash_values(values, sha3_256_hash_into_bytes)
+}
+
+/// Convenience function used for testing to create SHA3-256 hashes.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn sha3_256_hash_into_bytes(value: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(value);
+    hasher.finalize().into()
+}
@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::sha3_256::sha3_256_merkle_tree_hasher::{
+        sha3_256_hash_leaf_values, Sha3_256MerkleTreeHasher,
+    };
+    use crate::algos::test_merkle_tree_generic::{
+        merkle_root_calculation_test, merkle_size_test, merkle_test_repeatable_values,
+        merkle_tree_integrity_test, merkle_tree_serde_failed_test, merkle_tree_serde_test,
+        thwart_second_image_attack_using_duplicate_odd_node,
+        thwart_second_image_attack_using_interior_nodes,
+        thwart_second_image_attack_using_root_node,
+    };
+
+    type ValueType = [u8; 32];
+    type TestMerkleTreeHasher = Sha3_256MerkleTreeHasher;
+    const HASH_VALUES_FN: fn(&[&str]) -> Vec<ValueType> = sha3_256_hash_leaf_values;
+
+    #[test]
+    fn sha3_256_merkle_size_test() {
+        merkle_size_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn sha3_256_merkle_tree_serde_test() {
+        merkle_tree_serde_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn sha3_256_merkle_tree_serde_failed_test() {
+        merkle_tree_serde_failed_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn sha3_256_merkle_tree_integrity_test() {
+        merkle_tree_integrity_test::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    /// Added to check backwards compatibility of Merkle Root value.
+    ///
+    /// <b>WARNING</b>: The hash value needs to be changed if the code changes!
+    #[test]
+    fn sha3_256_merkle_root_calculation_test() {
+        merkle_root_calculation_test::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+            "e6440f7a1e08808b769519986049f3b48d36d7d0285063b8279efd3a9b3b183a",
+        );
+    }
+
+    #[test]
+    fn sha3_256_merkle_test_repeatable_values() {
+        merkle_test_repeatable_values::<ValueType, TestMerkleTreeHasher>(&HASH_VALUES_FN);
+    }
+
+    #[test]
+    fn sha3_256_thwart_second_image_attack_using_interior_nodes() {
+        thwart_second_image_attack_using_interior_nodes::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn sha3_256_thwart_second_image_attack_using_root_node() {
+        thwart_second_image_attack_using_root_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+
+    #[test]
+    fn sha3_256_thwart_second_image_attack_using_duplicate_odd_node() {
+        thwart_second_image_attack_using_duplicate_odd_node::<ValueType, TestMerkleTreeHasher>(
+            &HASH_VALUES_FN,
+        );
+    }
+}
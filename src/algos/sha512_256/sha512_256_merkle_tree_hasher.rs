@@ -0,0 +1,78 @@
+#[cfg(any(feature = "sha512_256_hash", test))]
+use ring::digest::{Context, SHA512_256};
+
+#[cfg(any(feature = "sha512_256_hash", test))]
+use crate::merkle_tree::MerkleTree;
+#[cfg(any(feature = "sha512_256_hash", test))]
+use crate::merkle_tree_hasher::LEAF_PREFIX;
+
+#[cfg(any(feature = "sha512_256_hash", test))]
+use crate::merkle_tree_hasher::{CryptographicHasher, MerkleTreeHasher};
+
+#[cfg(any(feature = "sha512_256_hash", test))]
+use crate::HashAlgorithm;
+
+#[cfg(any(test))]
+use crate::utils::hash_values;
+
+/// Create a [MerkleTree] using [Sha512_256MerkleTreeHasher]. Enabled using the 'sha512_256_hash' feature.
+#[cfg(any(feature = "sha512_256_hash", test))]
+pub type Sha512_256MerkleTree = MerkleTree<[u8; 32], Sha512_256MerkleTreeHasher>;
+
+/// Hasher for a Merkle Tree using SHA-512/256 Hashing. Enabled using the 'sha512_256_hash' feature.
+///
+/// SHA-512 truncated to 256 bits, which is faster than SHA-256 on 64 bit CPUs.
+///
+/// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
+#[cfg(any(feature = "sha512_256_hash", test))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sha512_256MerkleTreeHasher {}
+
+#[cfg(any(feature = "sha512_256_hash", test))]
+impl CryptographicHasher<[u8; 32]> for Sha512_256MerkleTreeHasher {}
+
+#[cfg(any(feature = "sha512_256_hash", test))]
+impl MerkleTreeHasher<[u8; 32]> for Sha512_256MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Sha512_256.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Sha512_256)
+    }
+    fn hash_leaf(leaf: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Context each call allows for multi-threading later.
+        let mut context = Context::new(&SHA512_256);
+        context.update(&LEAF_PREFIX);
+        context.update(leaf);
+        let digest = context.finish();
+        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 32], rhs: &[u8; 32]) -> [u8; 32] {
+        // Creating a new Context each call allows for multi-threading later.
+        let mut context = Context::new(&SHA512_256);
+        context.update(prefix);
+        context.update(lhs);
+        context.update(rhs);
+        let digest = context.finish();
+        <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+    }
+}
+
+/// Convenience function used for testing to create SHA-512/256 hashes from strs.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn sha512_256_hash_leaf_values(values: &[&str]) -> Vec<[u8; 32]> {
+    hash_values(values, sha512_256_hash_into_bytes)
+}
+
+/// Convenience function used for testing to create SHA-512/256 hashes.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn sha512_256_hash_into_bytes(value: &[u8]) -> [u8; 32] {
+    let mut context = Context::new(&SHA512_256);
+    context.update(value);
+    let digest = context.finish();
+    <[u8; 32]>::try_from(digest.as_ref()).unwrap()
+}
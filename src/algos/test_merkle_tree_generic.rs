@@ -151,3 +151,48 @@ pub(crate) fn thwart_second_image_attack_using_duplicate_odd_node<T, H>(
     assert_ne!(valid_tree.root(), attack_tree.root());
     assert_ne!(valid_tree, attack_tree);
 }
+
+#[cfg(any(test))]
+#[doc(hidden)]
+pub(crate) fn merkle_tree_integrity_test<T, H>(hash: &dyn Fn(&[&str]) -> Vec<T>)
+where
+    T: AsRef<[u8]>
+        + Copy
+        + Debug
+        + DeserializeOwned
+        + PartialEq
+        + Serialize
+        + Sized
+        + Ord
+        + Send
+        + Sync,
+    H: Clone + Debug + Default + MerkleTreeHasher<T>,
+{
+    let tree = MerkleTree::<T, H>::new(&hash(&SINGLE_CHAR_VALUES)).unwrap();
+    assert!(tree.verify_integrity().is_ok());
+
+    let json = serde_json::to_string(&tree).unwrap();
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    let tree_de = MerkleTree::<T, H>::deserialize_checked(&mut deserializer).unwrap();
+    assert_eq!(tree, tree_de);
+
+    let mut wrong_num_leaves = tree.clone();
+    wrong_num_leaves.num_leaves += 1;
+    assert!(wrong_num_leaves.verify_integrity().is_err());
+    let json = serde_json::to_string(&wrong_num_leaves).unwrap();
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    assert!(MerkleTree::<T, H>::deserialize_checked(&mut deserializer).is_err());
+
+    let mut wrong_length = tree.clone();
+    wrong_length.tree.pop();
+    assert!(wrong_length.verify_integrity().is_err());
+
+    let mut tampered = tree.clone();
+    let interior_idx = tampered.num_leaves + 1;
+    tampered.tree[interior_idx] = tampered.tree[0];
+    assert!(tampered.verify_integrity().is_err());
+
+    let mut wrong_hash_name = tree;
+    wrong_hash_name.hash_name = "unknown".to_string();
+    assert!(wrong_hash_name.verify_integrity().is_err());
+}
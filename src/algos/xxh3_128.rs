@@ -0,0 +1,4 @@
+#[cfg(any(feature = "xxh3_128_hash", test, bench))]
+mod test_xxh3_128_merkle_tree;
+#[cfg(any(feature = "xxh3_128_hash", test, bench))]
+pub mod xxh3_128_merkle_tree_hasher;
@@ -0,0 +1,77 @@
+#[cfg(any(feature = "xxh3_128_hash", test))]
+use xxhash_rust::xxh3::xxh3_128;
+
+#[cfg(any(feature = "xxh3_128_hash", test))]
+use crate::merkle_tree::MerkleTree;
+#[cfg(any(feature = "xxh3_128_hash", test))]
+use crate::merkle_tree_hasher::LEAF_PREFIX;
+
+#[cfg(any(feature = "xxh3_128_hash", test))]
+use crate::merkle_tree_hasher::{MerkleTreeHasher, NonCryptographic};
+
+#[cfg(any(feature = "xxh3_128_hash", test))]
+use crate::HashAlgorithm;
+
+#[cfg(any(test))]
+use crate::utils::hash_values;
+
+/// Create a [MerkleTree] using [Xxh3_128MerkleTreeHasher]. Enabled using the 'xxh3_128_hash' feature.
+#[cfg(any(feature = "xxh3_128_hash", test))]
+pub type Xxh3_128MerkleTree = MerkleTree<[u8; 16], Xxh3_128MerkleTreeHasher>;
+
+/// Hasher for a Merkle Tree using XXH3-128 Hashing. Enabled using the 'xxh3_128_hash' feature.
+///
+/// XXH3 is much faster than any cryptographic hash but anyone can craft collisions,
+/// so it is only suited to change detection and deduplication of trusted data.
+/// It is marked [NonCryptographic] and can't be used with
+/// [`MerkleProof::verify`](crate::MerkleProof::verify).
+///
+/// Hashes are the canonical big-endian encoding of the 128 bit XXH3 value.
+///
+/// This [MerkleTreeHasher] will behave as expected in a multi-threaded environment.
+#[cfg(any(feature = "xxh3_128_hash", test))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Xxh3_128MerkleTreeHasher {}
+
+#[cfg(any(feature = "xxh3_128_hash", test))]
+impl NonCryptographic for Xxh3_128MerkleTreeHasher {}
+
+#[cfg(any(feature = "xxh3_128_hash", test))]
+impl MerkleTreeHasher<[u8; 16]> for Xxh3_128MerkleTreeHasher {
+    fn name(&self) -> String {
+        HashAlgorithm::Xxh3_128.name().to_string()
+    }
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        Some(HashAlgorithm::Xxh3_128)
+    }
+    fn hash_leaf(leaf: &[u8; 16]) -> [u8; 16] {
+        // The input is small enough to hash in one call instead of streaming it.
+        let mut input = [0_u8; 17];
+        input[..1].copy_from_slice(&LEAF_PREFIX);
+        input[1..].copy_from_slice(leaf);
+        xxh3_128(&input).to_be_bytes()
+    }
+    fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 16], rhs: &[u8; 16]) -> [u8; 16] {
+        let mut input = [0_u8; 33];
+        input[..1].copy_from_slice(prefix);
+        input[1..17].copy_from_slice(lhs);
+        input[17..].copy_from_slice(rhs);
+        xxh3_128(&input).to_be_bytes()
+    }
+}
+
+/// Convenience function used for testing to create XXH3-128 hashes from strs.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn xxh3_128_hash_leaf_values(values: &[&str]) -> Vec<[u8; 16]> {
+    hash_values(values, xxh3_128_hash_into_bytes)
+}
+
+/// Convenience function used for testing to create XXH3-128 hashes.
+#[cfg(any(test))]
+#[doc(hidden)]
+#[inline(always)]
+pub(crate) fn xxh3_128_hash_into_bytes(value: &[u8]) -> [u8; 16] {
+    xxh3_128(value).to_be_bytes()
+}
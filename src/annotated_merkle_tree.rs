@@ -0,0 +1,302 @@
+use std::marker::PhantomData;
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::merkle_tree_hasher::{AnnotatedMerkleTreeHasher, CryptographicHasher};
+use crate::utils::{for_each_parent, next_level_prefix, node_prefix};
+use crate::{add_1_if_odd, count_tree_nodes, is_odd, Annotation};
+
+/// A Merkle Tree whose nodes carry an [Annotation] combined from the annotations below them.
+///
+/// Uses the same level-ordered layout and level prefixes as [MerkleTree](crate::MerkleTree), with
+/// every annotation hashed into its node. The last node of an odd level is hashed with itself and
+/// combined with [`Annotation::identity`], so it is not counted twice.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize, A: Serialize",
+    deserialize = "T: Deserialize<'de>, A: Deserialize<'de>"
+))]
+pub struct AnnotatedMerkleTree<T: Copy + Sized, H: AnnotatedMerkleTreeHasher<T>, A: Annotation> {
+    pub(crate) num_leaves: usize,
+    pub(crate) tree: Vec<T>,
+    pub(crate) annotations: Vec<A>,
+    pub(crate) hash_name: String,
+    pub(crate) _dummy: PhantomData<H>,
+}
+
+impl<T, H, A> AnnotatedMerkleTree<T, H, A>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: Default + AnnotatedMerkleTreeHasher<T>,
+    A: Annotation,
+{
+    /// Builds an AnnotatedMerkleTree from leaves and their annotations.
+    ///
+    /// Fails if there are no leaves or annotations fail to combine.
+    pub fn new(leaves: &[(T, A)]) -> Result<AnnotatedMerkleTree<T, H, A>> {
+        ensure!(!leaves.is_empty(), "a Merkle Tree needs at least one leaf");
+        let num_leaves = leaves.len();
+        let mut tree = Vec::with_capacity(count_tree_nodes(num_leaves));
+        let mut annotations = Vec::with_capacity(count_tree_nodes(num_leaves));
+        for (leaf, annotation) in leaves {
+            tree.push(H::hash_annotated_leaf(leaf, &annotation.to_bytes()));
+            annotations.push(annotation.clone());
+        }
+
+        for_each_parent(
+            num_leaves,
+            H::non_leaf_node_starting_prefix()[0],
+            H::wrap_to_value(),
+            |node| {
+                let (hash, annotation) = hash_annotated_node::<T, H, A>(
+                    &node.prefix,
+                    (&tree[node.lhs()], &annotations[node.lhs()]),
+                    (&tree[node.rhs()], &annotations[node.rhs()]),
+                    node.is_odd_last(),
+                )?;
+                tree.push(hash);
+                annotations.push(annotation);
+                Ok::<(), anyhow::Error>(())
+            },
+        )?;
+
+        Ok(AnnotatedMerkleTree {
+            num_leaves,
+            tree,
+            annotations,
+            hash_name: H::default().name(),
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Builds the [AnnotatedMerkleProof] for the leaf at `leaf_index`.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<AnnotatedMerkleProof<T, H, A>> {
+        ensure!(
+            leaf_index < self.num_leaves,
+            "leaf index {} is out of range for {} leaves",
+            leaf_index,
+            self.num_leaves
+        );
+
+        let mut sibling_nodes = Vec::new();
+        let mut level_start = 0;
+        let mut level_count = self.num_leaves;
+        let mut current_idx = leaf_index;
+
+        while level_count > 1 {
+            let sibling_idx = if is_odd(current_idx) {
+                current_idx - 1
+            } else {
+                (current_idx + 1).min(level_count - 1)
+            };
+            sibling_nodes.push((
+                self.tree[level_start + sibling_idx],
+                self.annotations[level_start + sibling_idx].clone(),
+            ));
+
+            current_idx /= 2;
+            level_start += level_count;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        Ok(AnnotatedMerkleProof {
+            leaf_index,
+            num_leaves: self.num_leaves,
+            annotation: self.annotations[leaf_index].clone(),
+            sibling_nodes,
+            root: self.root(),
+            root_annotation: self.root_annotation().clone(),
+            hash_name: self.hash_name.clone(),
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Returns the annotation of the node at `idx` of the level-ordered tree.
+    pub fn annotation(&self, idx: usize) -> Option<&A> {
+        self.annotations.get(idx)
+    }
+
+    /// Returns the number of leaves used to create this Merkle Tree.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the Merkle Tree root.
+    pub fn root(&self) -> T {
+        self.tree[self.tree.len() - 1]
+    }
+
+    /// Returns the annotation combined from every leaf, committed to by the root.
+    pub fn root_annotation(&self) -> &A {
+        &self.annotations[self.annotations.len() - 1]
+    }
+}
+
+/// Combines the annotations of two sibling nodes and hashes them into their parent.
+///
+/// `is_odd_last` is set for the last node of an odd level, which is its own sibling.
+fn hash_annotated_node<T: Copy, H: AnnotatedMerkleTreeHasher<T>, A: Annotation>(
+    prefix: &[u8; 1],
+    lhs: (&T, &A),
+    rhs: (&T, &A),
+    is_odd_last: bool,
+) -> Result<(T, A)> {
+    let annotation = if is_odd_last {
+        lhs.1.combine(&A::identity())?
+    } else {
+        lhs.1.combine(rhs.1)?
+    };
+    let hash = H::hash_annotated_node(prefix, lhs.0, rhs.0, &annotation.to_bytes());
+    Ok((hash, annotation))
+}
+
+/// Holds the data needed to prove that a leaf and its annotation are included in an [AnnotatedMerkleTree].
+///
+/// Created by [`AnnotatedMerkleTree.build_proof`](AnnotatedMerkleTree::build_proof). Every sibling
+/// carries its annotation, so a verifier recombines the annotations along the path up to the root.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(bound(
+    serialize = "T: Serialize, A: Serialize",
+    deserialize = "T: Deserialize<'de>, A: Deserialize<'de>"
+))]
+pub struct AnnotatedMerkleProof<
+    T: Copy + Sized,
+    H: AnnotatedMerkleTreeHasher<T> + Default,
+    A: Annotation,
+> {
+    pub(crate) leaf_index: usize,
+    pub(crate) num_leaves: usize,
+    pub(crate) annotation: A,
+    pub(crate) sibling_nodes: Vec<(T, A)>,
+    pub(crate) root: T,
+    pub(crate) root_annotation: A,
+    pub(crate) hash_name: String,
+    pub(crate) _dummy: PhantomData<H>,
+}
+
+impl<T, H, A> AnnotatedMerkleProof<T, H, A>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: AnnotatedMerkleTreeHasher<T> + Default,
+    A: Annotation,
+{
+    /// Determine whether or not the specified leaf and annotation are valid for this proof.
+    pub fn validate_proof(&self, leaf: &T, annotation: &A) -> bool {
+        *annotation == self.annotation
+            && matches!(
+                self.compute_root(leaf),
+                Ok((root, root_annotation)) if root == self.root && root_annotation == self.root_annotation
+            )
+    }
+
+    /// Hashes from the leaf up to the root using the sibling nodes, returning the root and its annotation.
+    ///
+    /// The sibling of the last node of an odd level must be the node itself.
+    fn compute_root(&self, leaf: &T) -> Result<(T, A)> {
+        ensure!(
+            self.leaf_index < self.num_leaves,
+            "leaf index {} is out of range for {} leaves",
+            self.leaf_index,
+            self.num_leaves
+        );
+        let wrap_to_value = H::wrap_to_value();
+        let mut level_prefix = H::non_leaf_node_starting_prefix()[0];
+        let mut level_count = self.num_leaves;
+        let mut current_idx = self.leaf_index;
+        let mut node = (
+            H::hash_annotated_leaf(leaf, &self.annotation.to_bytes()),
+            self.annotation.clone(),
+        );
+
+        for (sibling_hash, sibling_annotation) in &self.sibling_nodes {
+            ensure!(level_count > 1, "the proof has more siblings than levels");
+            let prefix = [node_prefix(
+                level_prefix,
+                level_count,
+                current_idx,
+                wrap_to_value,
+            )];
+            let sibling = (sibling_hash, sibling_annotation);
+            node = if is_odd(current_idx) {
+                hash_annotated_node::<T, H, A>(&prefix, sibling, (&node.0, &node.1), false)?
+            } else if current_idx + 1 < level_count {
+                hash_annotated_node::<T, H, A>(&prefix, (&node.0, &node.1), sibling, false)?
+            } else {
+                ensure!(
+                    *sibling_hash == node.0 && *sibling_annotation == node.1,
+                    "the last node of an odd level must be its own sibling"
+                );
+                hash_annotated_node::<T, H, A>(&prefix, (&node.0, &node.1), sibling, true)?
+            };
+
+            level_prefix = next_level_prefix(level_prefix, level_count, wrap_to_value);
+            current_idx /= 2;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+        ensure!(level_count == 1, "the proof has fewer siblings than levels");
+        Ok(node)
+    }
+
+    /// Returns the index of the proven leaf.
+    pub fn leaf_index(&self) -> usize {
+        self.leaf_index
+    }
+
+    /// Returns the number of leaves of the tree this proof was built from.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the annotation of the proven leaf.
+    pub fn annotation(&self) -> &A {
+        &self.annotation
+    }
+
+    /// Returns the sibling hashes and annotations from the leaf level upwards, without the root.
+    pub fn sibling_nodes(&self) -> &[(T, A)] {
+        &self.sibling_nodes
+    }
+
+    /// Returns the root of the tree this proof was built from.
+    pub fn root(&self) -> T {
+        self.root
+    }
+
+    /// Returns the annotation of the root of the tree this proof was built from.
+    pub fn root_annotation(&self) -> &A {
+        &self.root_annotation
+    }
+}
+
+impl<T, H, A> AnnotatedMerkleProof<T, H, A>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: CryptographicHasher<T> + AnnotatedMerkleTreeHasher<T> + Default,
+    A: Annotation,
+{
+    /// Determine whether or not `leaf` and `annotation` belong to the tree with the trusted root `root`.
+    ///
+    /// Unlike [`validate_proof`](AnnotatedMerkleProof::validate_proof), the root inside this proof is not trusted.
+    /// The root annotation is bound into `root`, so it is trusted once this returns true.
+    pub fn verify(&self, leaf: &T, annotation: &A, root: &T) -> bool {
+        self.root == *root && self.validate_proof(leaf, annotation)
+    }
+}
+
+impl<T, H, A> PartialEq for AnnotatedMerkleProof<T, H, A>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: AnnotatedMerkleTreeHasher<T> + Default,
+    A: Annotation,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.num_leaves == other.num_leaves
+            && self.leaf_index == other.leaf_index
+            && self.hash_name == other.hash_name
+            && self.annotation == other.annotation
+            && self.sibling_nodes == other.sibling_nodes
+            && self.root == other.root
+            && self.root_annotation == other.root_annotation
+    }
+}
@@ -0,0 +1,167 @@
+use std::fmt::Debug;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// An aggregate carried by every node of an [AnnotatedMerkleTree](crate::AnnotatedMerkleTree).
+///
+/// Annotations form a monoid: [`combine`](Annotation::combine) must be associative and
+/// [`identity`](Annotation::identity) must leave any annotation unchanged. The annotation of a
+/// node is the combination of its children's annotations and is bound into its hash using
+/// [`to_bytes`](Annotation::to_bytes).
+pub trait Annotation: Clone + Debug + PartialEq {
+    /// The annotation which changes nothing when combined.
+    fn identity() -> Self;
+
+    /// Combines the annotations of a left-hand and a right-hand node.
+    ///
+    /// Fails if the result cannot be represented, for example when a count overflows.
+    fn combine(&self, rhs: &Self) -> Result<Self>;
+
+    /// The bytes hashed into the node. Different annotations must have different bytes.
+    fn to_bytes(&self) -> Vec<u8>;
+}
+
+/// Counts the leaves below a node. Every leaf is annotated with `Count(1)`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Count(pub u64);
+
+impl Annotation for Count {
+    fn identity() -> Self {
+        Count(0)
+    }
+
+    fn combine(&self, rhs: &Self) -> Result<Self> {
+        self.0
+            .checked_add(rhs.0)
+            .map(Count)
+            .ok_or_else(|| anyhow!("the count of {} and {} overflows", self.0, rhs.0))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+}
+
+/// Sums the balances below a node, for example the liabilities of a proof-of-reserves.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Sum(pub u128);
+
+impl Annotation for Sum {
+    fn identity() -> Self {
+        Sum(0)
+    }
+
+    fn combine(&self, rhs: &Self) -> Result<Self> {
+        self.0
+            .checked_add(rhs.0)
+            .map(Sum)
+            .ok_or_else(|| anyhow!("the sum of {} and {} overflows", self.0, rhs.0))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+}
+
+/// The largest value below a node, for example the latest timestamp.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Max(pub u64);
+
+impl Annotation for Max {
+    fn identity() -> Self {
+        Max(u64::MIN)
+    }
+
+    fn combine(&self, rhs: &Self) -> Result<Self> {
+        Ok(Max(self.0.max(rhs.0)))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+}
+
+/// The smallest value below a node, for example the earliest timestamp.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct Min(pub u64);
+
+impl Annotation for Min {
+    fn identity() -> Self {
+        Min(u64::MAX)
+    }
+
+    fn combine(&self, rhs: &Self) -> Result<Self> {
+        Ok(Min(self.0.min(rhs.0)))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_be_bytes().to_vec()
+    }
+}
+
+/// The smallest and largest key below a node.
+///
+/// A leaf with key `k` is annotated with `KeyRange::new(k)`. The identity is an empty range.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct KeyRange {
+    /// The smallest key, [u64::MAX] for the empty range.
+    pub min: u64,
+    /// The largest key, [u64::MIN] for the empty range.
+    pub max: u64,
+}
+
+impl KeyRange {
+    /// The range holding only `key`.
+    pub fn new(key: u64) -> KeyRange {
+        KeyRange { min: key, max: key }
+    }
+
+    /// True if `key` lies within this range.
+    pub fn contains(&self, key: u64) -> bool {
+        self.min <= key && key <= self.max
+    }
+}
+
+impl Annotation for KeyRange {
+    fn identity() -> Self {
+        KeyRange {
+            min: u64::MAX,
+            max: u64::MIN,
+        }
+    }
+
+    fn combine(&self, rhs: &Self) -> Result<Self> {
+        Ok(KeyRange {
+            min: self.min.min(rhs.min),
+            max: self.max.max(rhs.max),
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.min.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&self.max.to_be_bytes());
+        bytes
+    }
+}
+
+/// Carries two annotations at once, for example `(Count, Max)`.
+///
+/// The bytes of the left-hand annotation are prefixed with their length so both stay distinct.
+impl<A: Annotation, B: Annotation> Annotation for (A, B) {
+    fn identity() -> Self {
+        (A::identity(), B::identity())
+    }
+
+    fn combine(&self, rhs: &Self) -> Result<Self> {
+        Ok((self.0.combine(&rhs.0)?, self.1.combine(&rhs.1)?))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let lhs = self.0.to_bytes();
+        let mut bytes = (lhs.len() as u64).to_be_bytes().to_vec();
+        bytes.extend_from_slice(&lhs);
+        bytes.extend_from_slice(&self.1.to_bytes());
+        bytes
+    }
+}
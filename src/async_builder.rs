@@ -0,0 +1,264 @@
+//! Builds a [MerkleTree] on a dedicated thread pool, returning a future instead of blocking.
+//!
+//! Hashing a large tree keeps a CPU busy for a long time, which would stall an async executor
+//! if done on one of its threads. [AsyncMerkleTreeBuilder] moves the work to a [rayon] thread
+//! pool and completes a [MerkleTreeBuild] future with the tree. The build reports its progress
+//! to an optional callback and stops early once its [CancellationToken] is cancelled.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+
+use anyhow::{anyhow, bail, ensure, Result};
+use futures_channel::oneshot;
+use rayon::ThreadPool;
+
+use crate::build_options::add_leaf_hashes;
+use crate::utils::build_levels;
+use crate::{count_tree_nodes, BuildOptions, MerkleTree, MerkleTreeHasher};
+
+/// Number of nodes hashed between two progress reports and cancellation checks.
+const LEAF_CHUNK_LEN: usize = 1 << 14;
+
+/// A flag shared between the owner of a build and the thread pool running it.
+///
+/// Clones share the same flag, so cancelling any clone cancels the build.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token which is not cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Asks every build holding this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true once [`cancel`](CancellationToken::cancel) was called on any clone.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a build is, in nodes hashed out of all the nodes of the tree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BuildProgress {
+    /// Number of nodes hashed so far, leaves included.
+    pub hashed_nodes: usize,
+    /// Number of nodes of the finished tree, leaves and root included.
+    pub total_nodes: usize,
+}
+
+impl BuildProgress {
+    /// Returns true once every node is hashed.
+    pub fn is_done(&self) -> bool {
+        self.hashed_nodes == self.total_nodes
+    }
+}
+
+type ProgressCallback = Arc<dyn Fn(BuildProgress) + Send + Sync>;
+
+/// Configures and starts building a [MerkleTree] off the calling thread.
+///
+/// Without [`thread_pool`](AsyncMerkleTreeBuilder::thread_pool), builds run on a pool shared by
+/// every builder, with one thread per CPU, rather than on the global [rayon] pool.
+pub struct AsyncMerkleTreeBuilder<T, H> {
+    thread_pool: Option<Arc<ThreadPool>>,
+    on_progress: Option<ProgressCallback>,
+    cancellation_token: CancellationToken,
+    _dummy: PhantomData<(T, H)>,
+}
+
+impl<T, H> Default for AsyncMerkleTreeBuilder<T, H> {
+    fn default() -> Self {
+        AsyncMerkleTreeBuilder {
+            thread_pool: None,
+            on_progress: None,
+            cancellation_token: CancellationToken::default(),
+            _dummy: Default::default(),
+        }
+    }
+}
+
+impl<T, H> AsyncMerkleTreeBuilder<T, H>
+where
+    T: 'static + AsRef<[u8]> + Copy + Send + Sync,
+    H: 'static + Default + MerkleTreeHasher<T> + Send,
+{
+    /// Creates a builder using the shared thread pool, without progress reports.
+    pub fn new() -> AsyncMerkleTreeBuilder<T, H> {
+        AsyncMerkleTreeBuilder::default()
+    }
+
+    /// Runs the build on `thread_pool` instead of the shared one.
+    pub fn thread_pool(mut self, thread_pool: Arc<ThreadPool>) -> Self {
+        self.thread_pool = Some(thread_pool);
+        self
+    }
+
+    /// Calls `on_progress` from the thread pool after each chunk of nodes.
+    ///
+    /// The last call reports every node as hashed, see [`BuildProgress::is_done`].
+    pub fn on_progress<F>(mut self, on_progress: F) -> Self
+    where
+        F: Fn(BuildProgress) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    /// Stops the build once `cancellation_token` is cancelled.
+    ///
+    /// The build checks the token between chunks of nodes, so a chunk already started is
+    /// finished first.
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    /// Starts building the tree from `leaves` and returns the future completing with it.
+    ///
+    /// The build starts right away, whether or not the future is polled. Dropping the future
+    /// before it completes cancels its token.
+    pub fn build(self, leaves: Vec<T>) -> MerkleTreeBuild<T, H> {
+        let (sender, receiver) = oneshot::channel();
+        let cancellation_token = self.cancellation_token.clone();
+        let thread_pool = self.thread_pool.clone().unwrap_or_else(shared_thread_pool);
+
+        let options = BuildOptions {
+            thread_pool: Some(thread_pool.clone()),
+            ..BuildOptions::default()
+        };
+        thread_pool.spawn(move || {
+            // The receiver is gone when the future was dropped, nobody wants the result then.
+            let _ = sender.send(self.run(leaves, &options));
+        });
+
+        MerkleTreeBuild {
+            receiver,
+            cancellation_token,
+            completed: false,
+        }
+    }
+
+    /// Hashes the leaves chunk by chunk, each chunk in parallel on the pool of `options`.
+    fn run(&self, leaves: Vec<T>, options: &BuildOptions) -> Result<MerkleTree<T, H>> {
+        ensure!(!leaves.is_empty(), "a Merkle Tree needs at least one leaf");
+        let total_nodes = count_tree_nodes(leaves.len());
+        let report = |hashed_nodes: usize| {
+            if let Some(on_progress) = &self.on_progress {
+                on_progress(BuildProgress {
+                    hashed_nodes,
+                    total_nodes,
+                });
+            }
+        };
+
+        let mut merkle_tree = MerkleTree {
+            num_leaves: leaves.len(),
+            tree: Vec::with_capacity(total_nodes),
+            hash_name: H::default().name(),
+            _dummy: Default::default(),
+            leaf_positions: OnceLock::new(),
+        };
+
+        for chunk in leaves.chunks(LEAF_CHUNK_LEN) {
+            if self.cancellation_token.is_cancelled() {
+                bail!("the build was cancelled");
+            }
+            add_leaf_hashes(
+                &mut merkle_tree.tree,
+                chunk,
+                options.is_parallel_for(chunk.len()),
+                options,
+                |leaf| Ok(H::hash_leaf(leaf)),
+            )?;
+            report(merkle_tree.tree.len());
+        }
+
+        let num_leaves = merkle_tree.num_leaves;
+        let mut hashed_nodes = num_leaves;
+        build_levels(
+            &mut merkle_tree.tree,
+            num_leaves,
+            H::non_leaf_node_starting_prefix()[0],
+            H::wrap_to_value(),
+            |prefix, lhs, rhs| {
+                if hashed_nodes % LEAF_CHUNK_LEN == 0 && hashed_nodes > num_leaves {
+                    report(hashed_nodes);
+                    if self.cancellation_token.is_cancelled() {
+                        bail!("the build was cancelled");
+                    }
+                }
+                hashed_nodes += 1;
+                Ok(H::hash_non_leaf_node(prefix, lhs, rhs))
+            },
+        )?;
+        report(total_nodes);
+        Ok(merkle_tree)
+    }
+}
+
+/// The pool used by builders without a thread pool of their own.
+fn shared_thread_pool() -> Arc<ThreadPool> {
+    static SHARED_THREAD_POOL: OnceLock<Arc<ThreadPool>> = OnceLock::new();
+    SHARED_THREAD_POOL
+        .get_or_init(|| {
+            Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .thread_name(|idx| format!("merkle-tree-build-{}", idx))
+                    .build()
+                    .expect("failed to start the Merkle Tree build threads"),
+            )
+        })
+        .clone()
+}
+
+/// A [MerkleTree] being built on a thread pool.
+///
+/// Created by [`AsyncMerkleTreeBuilder::build`]. Completes with an error if the build was
+/// cancelled or there were no leaves.
+#[must_use = "dropping the build cancels it"]
+#[derive(Debug)]
+pub struct MerkleTreeBuild<T: Copy, H: MerkleTreeHasher<T>> {
+    receiver: oneshot::Receiver<Result<MerkleTree<T, H>>>,
+    cancellation_token: CancellationToken,
+    completed: bool,
+}
+
+impl<T: Copy, H: MerkleTreeHasher<T>> MerkleTreeBuild<T, H> {
+    /// Returns the token cancelling this build.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+}
+
+impl<T: Copy, H: MerkleTreeHasher<T>> Future for MerkleTreeBuild<T, H> {
+    type Output = Result<MerkleTree<T, H>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let result = Pin::new(&mut self.receiver).poll(cx);
+        if result.is_ready() {
+            self.completed = true;
+        }
+        result.map(|result| {
+            result.unwrap_or_else(|_| Err(anyhow!("the build stopped without a result")))
+        })
+    }
+}
+
+impl<T: Copy, H: MerkleTreeHasher<T>> Drop for MerkleTreeBuild<T, H> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.cancellation_token.cancel();
+        }
+    }
+}
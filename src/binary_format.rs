@@ -0,0 +1,468 @@
+//! Compact, versioned binary encoding for [MerkleTree] and [MerkleProof].
+//!
+//! Both encodings start with a 4 byte magic number, a format version byte, the algorithm id
+//! as a varint (see [HashAlgorithm::code]) and the digest length in bytes. Varints are unsigned LEB128.
+//!
+//! A [MerkleProof] continues with:
+//!
+//! - the leaf index and the number of leaves as varints,
+//! - the leaf hash,
+//! - a bitmap with one bit per tree level, least significant bit first, set when the
+//!   sibling on that level is the left-hand node,
+//! - the sibling hashes from the leaf level upwards,
+//! - the root.
+//!
+//! The result hashes are not encoded. They are recomputed when decoding.
+//!
+//! A [CompactMerkleProof] continues like a [MerkleProof], without the leaf index and the root:
+//!
+//! - the number of leaves as a varint,
+//! - the leaf hash,
+//! - the direction bitmap, from which the leaf index is read,
+//! - the sibling hashes from the leaf level upwards.
+//!
+//! A [MerkleTree] continues with the number of leaves as a varint followed by the leaf hashes.
+//! The non-leaf nodes are recomputed when decoding.
+//!
+//! Decoding fails unless the input has exactly the expected length.
+
+use anyhow::{anyhow, bail, ensure, Result};
+
+use crate::utils::count_tree_levels;
+use crate::{is_odd, CompactMerkleProof, HashAlgorithm, MerkleProof, MerkleTree, MerkleTreeHasher};
+
+/// Magic number starting an encoded [MerkleProof].
+pub const PROOF_MAGIC: [u8; 4] = *b"NDMP";
+
+/// Magic number starting an encoded [CompactMerkleProof].
+pub const COMPACT_PROOF_MAGIC: [u8; 4] = *b"NDMC";
+
+/// Magic number starting an encoded [MerkleTree].
+pub const TREE_MAGIC: [u8; 4] = *b"NDMT";
+
+/// The binary format version written by this crate.
+pub const FORMAT_VERSION: u8 = 1;
+
+/// Longest unsigned LEB128 encoding of a u64.
+const MAX_VARINT_LEN: usize = 10;
+
+/// The [HashAlgorithm] of `H`, needed to encode or decode anything it hashed.
+pub(crate) fn hasher_algorithm<T: Copy, H: Default + MerkleTreeHasher<T>>() -> Result<HashAlgorithm>
+{
+    let hasher = H::default();
+    hasher
+        .algorithm()
+        .ok_or_else(|| anyhow!("hasher '{}' has no algorithm id", hasher.name()))
+}
+
+/// The fields of an encoded [MerkleProof]. The result hashes still need to be recomputed.
+pub(crate) struct ProofParts<T> {
+    pub(crate) algorithm: HashAlgorithm,
+    pub(crate) leaf_index: usize,
+    pub(crate) num_leaves: usize,
+    pub(crate) leaf_hash: T,
+    pub(crate) siblings: Vec<T>,
+    pub(crate) root: T,
+}
+
+/// The fields of an encoded [MerkleTree]. The non-leaf nodes still need to be recomputed.
+pub(crate) struct TreeParts<T> {
+    pub(crate) algorithm: HashAlgorithm,
+    pub(crate) leaf_hashes: Vec<T>,
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn write_header(
+    bytes: &mut Vec<u8>,
+    magic: &[u8; 4],
+    algorithm: HashAlgorithm,
+    digest_len: usize,
+) -> Result<()> {
+    let digest_len = u8::try_from(digest_len).map_err(|_| anyhow!("digest is too long"))?;
+    bytes.extend_from_slice(magic);
+    bytes.push(FORMAT_VERSION);
+    write_varint(bytes, algorithm.code());
+    bytes.push(digest_len);
+    Ok(())
+}
+
+/// Encodes a proof given its leaf hash and its sibling hashes followed by the root.
+pub(crate) fn encode_proof<'a>(
+    algorithm: HashAlgorithm,
+    leaf_index: usize,
+    num_leaves: usize,
+    leaf_hash: &[u8],
+    sibling_hashes: impl ExactSizeIterator<Item = &'a [u8]>,
+) -> Result<Vec<u8>> {
+    let digest_len = leaf_hash.len();
+    let levels = sibling_hashes
+        .len()
+        .checked_sub(1)
+        .ok_or_else(|| anyhow!("the proof has no root"))?;
+    let mut bytes = Vec::with_capacity(32 + digest_len * (levels + 2));
+
+    write_header(&mut bytes, &PROOF_MAGIC, algorithm, digest_len)?;
+    write_varint(&mut bytes, leaf_index as u64);
+    write_varint(&mut bytes, num_leaves as u64);
+    bytes.extend_from_slice(leaf_hash);
+    bytes.extend_from_slice(&direction_bitmap(leaf_index, levels));
+    for sibling_hash in sibling_hashes {
+        ensure!(sibling_hash.len() == digest_len, "hashes differ in length");
+        bytes.extend_from_slice(sibling_hash);
+    }
+    Ok(bytes)
+}
+
+/// Encodes a tree given its leaf hashes.
+pub(crate) fn encode_tree<'a>(
+    algorithm: HashAlgorithm,
+    digest_len: usize,
+    leaf_hashes: impl ExactSizeIterator<Item = &'a [u8]>,
+) -> Result<Vec<u8>> {
+    let num_leaves = leaf_hashes.len();
+    let mut bytes = Vec::with_capacity(16 + digest_len * num_leaves);
+
+    write_header(&mut bytes, &TREE_MAGIC, algorithm, digest_len)?;
+    write_varint(&mut bytes, num_leaves as u64);
+    for leaf_hash in leaf_hashes {
+        ensure!(leaf_hash.len() == digest_len, "hashes differ in length");
+        bytes.extend_from_slice(leaf_hash);
+    }
+    Ok(bytes)
+}
+
+/// Decodes a proof, converting each hash with `read_hash`.
+pub(crate) fn decode_proof<T>(
+    bytes: &[u8],
+    read_hash: impl Fn(&[u8]) -> Result<T>,
+) -> Result<ProofParts<T>> {
+    let mut reader = Reader::new(bytes);
+    let (algorithm, digest_len) = reader.read_header(&PROOF_MAGIC)?;
+    check_digest_len(digest_len, &read_hash)?;
+    let leaf_index = reader.read_usize()?;
+    let num_leaves = reader.read_usize()?;
+    ensure!(
+        leaf_index < num_leaves,
+        "leaf index {} is out of range for {} leaves",
+        leaf_index,
+        num_leaves
+    );
+
+    let levels = count_tree_levels(num_leaves);
+    let leaf_hash = read_hash(reader.read_bytes(digest_len)?)?;
+    let bitmap = reader.read_bytes(levels.div_ceil(8))?;
+    ensure!(
+        bitmap == direction_bitmap(leaf_index, levels).as_slice(),
+        "direction bitmap does not match leaf index {}",
+        leaf_index
+    );
+
+    let mut siblings = Vec::with_capacity(levels + 1);
+    for _ in 0..levels {
+        siblings.push(read_hash(reader.read_bytes(digest_len)?)?);
+    }
+    let root = read_hash(reader.read_bytes(digest_len)?)?;
+    reader.finish()?;
+
+    Ok(ProofParts {
+        algorithm,
+        leaf_index,
+        num_leaves,
+        leaf_hash,
+        siblings,
+        root,
+    })
+}
+
+/// Decodes a tree, converting each leaf hash with `read_hash`.
+pub(crate) fn decode_tree<T>(
+    bytes: &[u8],
+    read_hash: impl Fn(&[u8]) -> Result<T>,
+) -> Result<TreeParts<T>> {
+    let mut reader = Reader::new(bytes);
+    let (algorithm, digest_len) = reader.read_header(&TREE_MAGIC)?;
+    check_digest_len(digest_len, &read_hash)?;
+    let num_leaves = reader.read_usize()?;
+    ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+
+    let expected_len = num_leaves
+        .checked_mul(digest_len)
+        .ok_or_else(|| anyhow!("{} leaves are too many", num_leaves))?;
+    ensure!(
+        reader.remaining() == expected_len,
+        "expected {} bytes of leaf hashes, found {}",
+        expected_len,
+        reader.remaining()
+    );
+
+    let mut leaf_hashes = Vec::with_capacity(num_leaves.min(reader.remaining() / digest_len));
+    for _ in 0..num_leaves {
+        leaf_hashes.push(read_hash(reader.read_bytes(digest_len)?)?);
+    }
+    reader.finish()?;
+
+    Ok(TreeParts {
+        algorithm,
+        leaf_hashes,
+    })
+}
+
+/// Reads the algorithm of an encoded tree, proof or compact proof without decoding the rest.
+pub fn peek_algorithm(bytes: &[u8]) -> Result<HashAlgorithm> {
+    let mut reader = Reader::new(bytes);
+    let magic = reader.read_bytes(4)?;
+    ensure!(
+        magic == PROOF_MAGIC || magic == COMPACT_PROOF_MAGIC || magic == TREE_MAGIC,
+        "bad magic number"
+    );
+    reader.pos = 0;
+    Ok(reader.read_header(magic.try_into()?)?.0)
+}
+
+/// Fails unless `read_hash` accepts digests of `digest_len` bytes.
+///
+/// Called right after the header, so no count is trusted while the digest length is wrong.
+fn check_digest_len<T>(digest_len: usize, read_hash: impl Fn(&[u8]) -> Result<T>) -> Result<()> {
+    read_hash(&[0; u8::MAX as usize][..digest_len]).map(|_| ())
+}
+
+/// Reads the encoded values in order, failing on truncated input.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes, pos: 0 }
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| anyhow!("unexpected end of input at byte {}", self.pos))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_varint(&mut self) -> Result<u64> {
+        let mut value: u64 = 0;
+        for idx in 0..MAX_VARINT_LEN {
+            let byte = self.read_u8()?;
+            let bits = (byte & 0x7f) as u64;
+            ensure!(
+                idx < MAX_VARINT_LEN - 1 || bits <= 1,
+                "varint overflows a u64"
+            );
+            value |= bits << (7 * idx);
+            if byte & 0x80 == 0 {
+                ensure!(idx == 0 || byte != 0, "varint is not minimally encoded");
+                return Ok(value);
+            }
+        }
+        bail!("varint overflows a u64")
+    }
+
+    fn read_usize(&mut self) -> Result<usize> {
+        let value = self.read_varint()?;
+        usize::try_from(value).map_err(|_| anyhow!("{} does not fit in a usize", value))
+    }
+
+    /// Checks the magic number and version, returning the algorithm and the digest length.
+    fn read_header(&mut self, magic: &[u8; 4]) -> Result<(HashAlgorithm, usize)> {
+        ensure!(self.read_bytes(4)? == magic, "bad magic number");
+        let version = self.read_u8()?;
+        ensure!(
+            version == FORMAT_VERSION,
+            "unsupported format version {}",
+            version
+        );
+        let algorithm = HashAlgorithm::from_code(self.read_varint()?);
+        let digest_len = self.read_u8()? as usize;
+        ensure!(digest_len > 0, "the digest length is zero");
+        Ok((algorithm, digest_len))
+    }
+
+    /// Number of bytes left to read.
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn finish(&self) -> Result<()> {
+        ensure!(
+            self.pos == self.bytes.len(),
+            "{} unexpected trailing bytes",
+            self.bytes.len() - self.pos
+        );
+        Ok(())
+    }
+}
+
+/// One bit per level, set when the node on the path is the right-hand node.
+pub(crate) fn direction_bitmap(leaf_index: usize, levels: usize) -> Vec<u8> {
+    let mut bitmap = vec![0_u8; levels.div_ceil(8)];
+    let mut current_idx = leaf_index;
+    for level in 0..levels {
+        if is_odd(current_idx) {
+            bitmap[level / 8] |= 1 << (level % 8);
+        }
+        current_idx /= 2;
+    }
+    bitmap
+}
+
+pub(crate) fn typed_hash<T: for<'a> TryFrom<&'a [u8]>>(bytes: &[u8]) -> Result<T> {
+    T::try_from(bytes).map_err(|_| {
+        anyhow!(
+            "a digest of {} bytes does not fit the hash type",
+            bytes.len()
+        )
+    })
+}
+
+pub(crate) fn ensure_algorithm(found: HashAlgorithm, expected: HashAlgorithm) -> Result<()> {
+    ensure!(
+        found == expected,
+        "encoded with {}, expected {}",
+        found,
+        expected
+    );
+    Ok(())
+}
+
+impl<T, H> MerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]>,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Encodes this [MerkleProof] using the binary format described in [binary_format](crate::binary_format).
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_proof(
+            hasher_algorithm::<T, H>()?,
+            self.leaf_index,
+            self.num_leaves,
+            self.leaf_hash.as_ref(),
+            self.sibling_hashes.iter().map(|hash| hash.as_ref()),
+        )
+    }
+
+    /// Decodes a [MerkleProof] encoded by [`to_bytes`](MerkleProof::to_bytes).
+    ///
+    /// The result hashes are recomputed and must lead to the encoded root.
+    pub fn from_bytes(bytes: &[u8]) -> Result<MerkleProof<T, H>> {
+        let parts = decode_proof(bytes, typed_hash::<T>)?;
+        ensure_algorithm(parts.algorithm, hasher_algorithm::<T, H>()?)?;
+
+        let proof: MerkleProof<T, H> = MerkleProof::from_siblings(
+            parts.leaf_index,
+            parts.num_leaves,
+            parts.leaf_hash,
+            parts.siblings,
+        );
+        ensure!(
+            proof.sibling_hashes[proof.sibling_hashes.len() - 1].as_ref() == parts.root.as_ref(),
+            "the siblings do not lead to the encoded root"
+        );
+        Ok(proof)
+    }
+}
+
+impl<T, H> CompactMerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq + for<'a> TryFrom<&'a [u8]>,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Encodes this [CompactMerkleProof] using the binary format described in [binary_format](crate::binary_format).
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        self.check_shape()?;
+        let digest_len = self.leaf_hash.as_ref().len();
+        let mut bytes = Vec::with_capacity(24 + digest_len * (self.siblings.len() + 1));
+
+        write_header(
+            &mut bytes,
+            &COMPACT_PROOF_MAGIC,
+            hasher_algorithm::<T, H>()?,
+            digest_len,
+        )?;
+        write_varint(&mut bytes, self.num_leaves as u64);
+        bytes.extend_from_slice(self.leaf_hash.as_ref());
+        bytes.extend_from_slice(&self.directions);
+        for sibling in &self.siblings {
+            ensure!(
+                sibling.as_ref().len() == digest_len,
+                "hashes differ in length"
+            );
+            bytes.extend_from_slice(sibling.as_ref());
+        }
+        Ok(bytes)
+    }
+
+    /// Decodes a [CompactMerkleProof] encoded by [`to_bytes`](CompactMerkleProof::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<CompactMerkleProof<T, H>> {
+        let mut reader = Reader::new(bytes);
+        let (algorithm, digest_len) = reader.read_header(&COMPACT_PROOF_MAGIC)?;
+        ensure_algorithm(algorithm, hasher_algorithm::<T, H>()?)?;
+        check_digest_len(digest_len, typed_hash::<T>)?;
+        let num_leaves = reader.read_usize()?;
+        ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+
+        let levels = count_tree_levels(num_leaves);
+        let leaf_hash = typed_hash(reader.read_bytes(digest_len)?)?;
+        let directions = reader.read_bytes(levels.div_ceil(8))?.to_vec();
+        let mut siblings = Vec::with_capacity(levels);
+        for _ in 0..levels {
+            siblings.push(typed_hash(reader.read_bytes(digest_len)?)?);
+        }
+        reader.finish()?;
+
+        let proof = CompactMerkleProof {
+            num_leaves,
+            leaf_hash,
+            directions,
+            siblings,
+            _dummy: Default::default(),
+        };
+        proof.check_shape()?;
+        Ok(proof)
+    }
+}
+
+impl<T, H> MerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync + for<'a> TryFrom<&'a [u8]>,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Encodes this [MerkleTree] using the binary format described in [binary_format](crate::binary_format).
+    ///
+    /// Only the leaf hashes are written.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_tree(
+            hasher_algorithm::<T, H>()?,
+            self.root().as_ref().len(),
+            self.tree[..self.num_leaves]
+                .iter()
+                .map(|hash| hash.as_ref()),
+        )
+    }
+
+    /// Decodes a [MerkleTree] encoded by [`to_bytes`](MerkleTree::to_bytes), recomputing the non-leaf nodes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<MerkleTree<T, H>> {
+        let parts = decode_tree(bytes, typed_hash::<T>)?;
+        ensure_algorithm(parts.algorithm, hasher_algorithm::<T, H>()?)?;
+        Ok(MerkleTree::from_leaf_hashes(&parts.leaf_hashes))
+    }
+}
@@ -0,0 +1,181 @@
+use std::borrow::Borrow;
+use std::sync::{Arc, OnceLock};
+
+use anyhow::{ensure, Result};
+use rayon::prelude::*;
+use rayon::ThreadPool;
+
+use crate::binary_format::typed_hash;
+use crate::{count_tree_nodes, MerkleTree, MerkleTreeHasher};
+
+/// Controls how [`MerkleTree::new_with_options`] and the other constructors of this module
+/// spread leaf hashing over threads.
+///
+/// The defaults hash in parallel on the global rayon pool with rayon's own splitting, like
+/// [MerkleTree::new] does with the `parallel_hashing` feature. `benches/rayon_bench.rs` shows
+/// that parallel hashing only pays off above a few hundred leaves and depends on the chunk
+/// length, so both are worth tuning for the hasher and machine at hand.
+#[derive(Clone, Debug)]
+pub struct BuildOptions {
+    /// Hash on this pool instead of the global rayon pool.
+    pub thread_pool: Option<Arc<ThreadPool>>,
+    /// Never hand fewer leaves than this to a thread, see
+    /// [`with_min_len`](rayon::iter::IndexedParallelIterator::with_min_len). 0 is treated as 1.
+    pub min_chunk_len: usize,
+    /// Hash on the calling thread when there are fewer leaves than this.
+    pub serial_threshold: usize,
+    /// Hash in parallel at all. When false the other options are ignored.
+    pub parallel: bool,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        BuildOptions {
+            thread_pool: None,
+            min_chunk_len: 1,
+            serial_threshold: 0,
+            parallel: true,
+        }
+    }
+}
+
+impl BuildOptions {
+    /// Creates options hashing every leaf on the calling thread.
+    pub fn serial() -> BuildOptions {
+        BuildOptions {
+            parallel: false,
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if `num_leaves` leaves are hashed in parallel with these options.
+    pub fn is_parallel_for(&self, num_leaves: usize) -> bool {
+        self.parallel && num_leaves >= self.serial_threshold
+    }
+}
+
+impl<T, H> MerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Builds a MerkleTree like [MerkleTree::new], hashing the leaves as `options` say.
+    pub fn new_with_options(leaves: &[T], options: &BuildOptions) -> Result<MerkleTree<T, H>> {
+        let mut merkle_tree = MerkleTree::with_leaf_capacity(leaves.len())?;
+        let parallel = options.is_parallel_for(leaves.len());
+        add_leaf_hashes(&mut merkle_tree.tree, leaves, parallel, options, |leaf| {
+            Ok(H::hash_leaf(leaf))
+        })?;
+        MerkleTree::add_interior_nodes(&mut merkle_tree);
+        Ok(merkle_tree)
+    }
+
+    /// Builds a MerkleTree from leaves split across `chunks`, hashing them in place.
+    ///
+    /// The leaves are taken in order, the first leaf of a chunk following the last of the
+    /// previous one. Whether to hash in parallel is decided on the total number of leaves.
+    pub fn from_chunks(chunks: &[&[T]], options: &BuildOptions) -> Result<MerkleTree<T, H>> {
+        let num_leaves = chunks.iter().map(|chunk| chunk.len()).sum();
+        let mut merkle_tree = MerkleTree::with_leaf_capacity(num_leaves)?;
+        let parallel = options.is_parallel_for(num_leaves);
+        for chunk in chunks {
+            add_leaf_hashes(&mut merkle_tree.tree, chunk, parallel, options, |leaf| {
+                Ok(H::hash_leaf(leaf))
+            })?;
+        }
+        MerkleTree::add_interior_nodes(&mut merkle_tree);
+        Ok(merkle_tree)
+    }
+
+    /// Builds a MerkleTree from an [IndexedParallelIterator] of leaves, or of references to them.
+    ///
+    /// The leaf hashes are written straight into the tree. The iterator runs in the current
+    /// rayon pool with its own splitting, so call [ThreadPool::install] and
+    /// [`with_min_len`](IndexedParallelIterator::with_min_len) on it to tune it.
+    pub fn from_par_iter<I>(leaves: I) -> Result<MerkleTree<T, H>>
+    where
+        I: IndexedParallelIterator,
+        I::Item: Borrow<T>,
+    {
+        let mut merkle_tree = MerkleTree::with_leaf_capacity(leaves.len())?;
+        merkle_tree
+            .tree
+            .par_extend(leaves.map(|leaf| H::hash_leaf(leaf.borrow())));
+        MerkleTree::add_interior_nodes(&mut merkle_tree);
+        Ok(merkle_tree)
+    }
+
+    /// Builds a MerkleTree from leaves given as byte slices, such as leaves read from a file.
+    ///
+    /// Fails if a slice does not convert to `T`, typically because its length is not the
+    /// digest length.
+    pub fn from_leaf_bytes(leaves: &[&[u8]], options: &BuildOptions) -> Result<MerkleTree<T, H>>
+    where
+        T: for<'b> TryFrom<&'b [u8]>,
+    {
+        let mut merkle_tree = MerkleTree::with_leaf_capacity(leaves.len())?;
+        let parallel = options.is_parallel_for(leaves.len());
+        add_leaf_hashes(&mut merkle_tree.tree, leaves, parallel, options, |bytes| {
+            Ok(H::hash_leaf(&typed_hash(bytes)?))
+        })?;
+        MerkleTree::add_interior_nodes(&mut merkle_tree);
+        Ok(merkle_tree)
+    }
+
+    /// Creates a MerkleTree without nodes, with room for every node of `num_leaves` leaves.
+    fn with_leaf_capacity(num_leaves: usize) -> Result<MerkleTree<T, H>> {
+        ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        Ok(MerkleTree {
+            num_leaves,
+            tree: Vec::with_capacity(count_tree_nodes(num_leaves)),
+            hash_name: H::default().name(),
+            _dummy: Default::default(),
+            leaf_positions: OnceLock::new(),
+        })
+    }
+}
+
+/// Appends the hashes of `leaves` to `tree`, writing them into its spare capacity.
+///
+/// In parallel, the nodes are first filled with the hash of the first leaf, then overwritten
+/// in place, so no buffer is needed besides the tree.
+pub(crate) fn add_leaf_hashes<T, L, F>(
+    tree: &mut Vec<T>,
+    leaves: &[L],
+    parallel: bool,
+    options: &BuildOptions,
+    hash_leaf: F,
+) -> Result<()>
+where
+    T: Copy + Send + Sync,
+    L: Sync,
+    F: Fn(&L) -> Result<T> + Sync,
+{
+    if leaves.is_empty() {
+        return Ok(());
+    }
+    if !parallel {
+        for leaf in leaves {
+            tree.push(hash_leaf(leaf)?);
+        }
+        return Ok(());
+    }
+
+    let start = tree.len();
+    tree.resize(start + leaves.len(), hash_leaf(&leaves[0])?);
+    let hash_rest = |nodes: &mut [T]| {
+        nodes
+            .par_iter_mut()
+            .zip(&leaves[1..])
+            .with_min_len(options.min_chunk_len.max(1))
+            .try_for_each(|(node, leaf)| {
+                *node = hash_leaf(leaf)?;
+                Ok(())
+            })
+    };
+    let nodes = &mut tree[start + 1..];
+    match &options.thread_pool {
+        Some(thread_pool) => thread_pool.install(|| hash_rest(nodes)),
+        None => hash_rest(nodes),
+    }
+}
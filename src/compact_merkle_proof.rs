@@ -0,0 +1,204 @@
+use std::convert::Infallible;
+use std::marker::PhantomData;
+
+use anyhow::{anyhow, ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::binary_format::direction_bitmap;
+use crate::utils::{count_tree_levels, fold_proof_path};
+use crate::{CryptographicHasher, MerkleProof, MerkleTreeHasher};
+
+/// A [MerkleProof] without the hashes a verifier recomputes anyway.
+///
+/// Holds the leaf hash, the sibling hashes below the root and a bitmap with one bit per level,
+/// least significant bit first, set when the node on the path is the right-hand node. The
+/// result hashes and the root are left out, which about halves the size of the proof. The
+/// bitmap is the binary leaf index, so the index is not stored either. The number of leaves is
+/// kept, as it decides the prefix used on each level.
+///
+/// Converts losslessly from [MerkleProof] with [TryFrom] and back with [From].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct CompactMerkleProof<T: Copy, H: MerkleTreeHasher<T>> {
+    pub(crate) num_leaves: usize,
+    pub(crate) leaf_hash: T,
+    pub(crate) directions: Vec<u8>,
+    pub(crate) siblings: Vec<T>,
+    pub(crate) _dummy: PhantomData<H>,
+}
+
+impl<T, H> CompactMerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Checks that the siblings and the directions fit the number of leaves.
+    ///
+    /// Always true for a proof converted from a [MerkleProof], worth calling on a decoded one.
+    pub fn check_shape(&self) -> Result<()> {
+        let levels = count_tree_levels(self.num_leaves);
+        ensure!(self.num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        ensure!(
+            self.siblings.len() == levels,
+            "{} leaves need {} siblings, found {}",
+            self.num_leaves,
+            levels,
+            self.siblings.len()
+        );
+        ensure!(
+            self.leaf_index() < self.num_leaves,
+            "leaf index {} is out of range for {} leaves",
+            self.leaf_index(),
+            self.num_leaves
+        );
+        ensure!(
+            self.directions == direction_bitmap(self.leaf_index(), levels),
+            "the directions do not fit {} leaves",
+            self.num_leaves
+        );
+        Ok(())
+    }
+
+    /// Recomputes the root from the leaf hash and the siblings.
+    pub fn compute_root(&self) -> T {
+        let result_hashes = self.result_hashes();
+        result_hashes.last().copied().unwrap_or(self.leaf_hash)
+    }
+
+    /// Returns the index of the proven leaf, read from the directions.
+    pub fn leaf_index(&self) -> usize {
+        self.directions
+            .iter()
+            .enumerate()
+            .take(usize::BITS as usize / 8)
+            .fold(0, |leaf_index, (idx, byte)| {
+                leaf_index | ((*byte as usize) << (idx * 8))
+            })
+    }
+
+    /// Returns the number of leaves of the tree this proof was built from.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the hash of the proven leaf.
+    pub fn leaf_hash(&self) -> T {
+        self.leaf_hash
+    }
+
+    /// Returns the direction bitmap, one bit per level, set when the path goes through the right-hand node.
+    pub fn directions(&self) -> &[u8] {
+        &self.directions
+    }
+
+    /// Returns the sibling hashes from the leaf level up to below the root.
+    pub fn siblings(&self) -> &[T] {
+        &self.siblings
+    }
+
+    fn result_hashes(&self) -> Vec<T> {
+        fold_proof_path(
+            self.leaf_index(),
+            self.num_leaves,
+            &self.leaf_hash,
+            &self.siblings,
+            H::non_leaf_node_starting_prefix()[0],
+            H::wrap_to_value(),
+            |prefix, lhs, rhs| Ok::<T, Infallible>(H::hash_non_leaf_node(prefix, lhs, rhs)),
+        )
+        .unwrap_or_else(|never| match never {})
+    }
+}
+
+impl<T, H> CompactMerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: CryptographicHasher<T> + MerkleTreeHasher<T> + Default,
+{
+    /// Determine whether or not `leaf` belongs to the tree with the trusted root `root`.
+    ///
+    /// Only available for a [CryptographicHasher], like [`MerkleProof::verify`].
+    pub fn verify(&self, leaf: &T, root: &T) -> bool {
+        self.check_shape().is_ok()
+            && H::hash_leaf(leaf) == self.leaf_hash
+            && self.compute_root() == *root
+    }
+}
+
+impl<T, H> TryFrom<&MerkleProof<T, H>> for CompactMerkleProof<T, H>
+where
+    T: Copy,
+    H: Default + MerkleTreeHasher<T>,
+{
+    type Error = anyhow::Error;
+
+    /// Fails if `proof` has no root, which only a corrupt or hand-built proof lacks.
+    fn try_from(proof: &MerkleProof<T, H>) -> Result<Self> {
+        let (_, siblings) = proof
+            .sibling_hashes
+            .split_last()
+            .ok_or_else(|| anyhow!("the proof has no root"))?;
+        Ok(CompactMerkleProof {
+            num_leaves: proof.num_leaves,
+            leaf_hash: proof.leaf_hash,
+            directions: direction_bitmap(proof.leaf_index, siblings.len()),
+            siblings: siblings.to_vec(),
+            _dummy: Default::default(),
+        })
+    }
+}
+
+impl<T, H> TryFrom<MerkleProof<T, H>> for CompactMerkleProof<T, H>
+where
+    T: Copy,
+    H: Default + MerkleTreeHasher<T>,
+{
+    type Error = anyhow::Error;
+
+    fn try_from(proof: MerkleProof<T, H>) -> Result<Self> {
+        CompactMerkleProof::try_from(&proof)
+    }
+}
+
+impl<T, H> From<&CompactMerkleProof<T, H>> for MerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Recomputes the result hashes and the root.
+    fn from(proof: &CompactMerkleProof<T, H>) -> Self {
+        MerkleProof::from_siblings(
+            proof.leaf_index(),
+            proof.num_leaves,
+            proof.leaf_hash,
+            proof.siblings.clone(),
+        )
+    }
+}
+
+impl<T, H> From<CompactMerkleProof<T, H>> for MerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Recomputes the result hashes and the root.
+    fn from(proof: CompactMerkleProof<T, H>) -> Self {
+        MerkleProof::from_siblings(
+            proof.leaf_index(),
+            proof.num_leaves,
+            proof.leaf_hash,
+            proof.siblings,
+        )
+    }
+}
+
+impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleTreeHasher<T>> PartialEq
+    for CompactMerkleProof<T, H>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.num_leaves == other.num_leaves
+            && self.leaf_hash == other.leaf_hash
+            && self.directions == other.directions
+            && self.siblings == other.siblings
+    }
+}
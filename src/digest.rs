@@ -0,0 +1,110 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+use rustc_serialize::hex::ToHex;
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A hash of `N` bytes.
+///
+/// Use it as the hash type of a [MerkleTree](crate::MerkleTree) when a [MerkleTreeHasher](crate::MerkleTreeHasher)
+/// supports several output lengths. For example `MerkleTree<Digest<20>, Sha256MerkleTreeHasher>`
+/// truncates SHA-256 to 20 bytes.
+///
+/// Serialized the same way as `[u8; N]`.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Digest<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Digest<N> {
+    /// The number of bytes of this digest.
+    pub const LEN: usize = N;
+
+    /// Returns the bytes of this digest.
+    pub fn into_bytes(self) -> [u8; N] {
+        self.0
+    }
+}
+
+impl<const N: usize> Default for Digest<N> {
+    fn default() -> Self {
+        Digest([0; N])
+    }
+}
+
+impl<const N: usize> Debug for Digest<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Digest({})", self.0.to_hex())
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for Digest<N> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for Digest<N> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Digest<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        Digest(bytes)
+    }
+}
+
+impl<const N: usize> From<Digest<N>> for [u8; N] {
+    fn from(digest: Digest<N>) -> Self {
+        digest.0
+    }
+}
+
+impl<const N: usize> TryFrom<&[u8]> for Digest<N> {
+    type Error = std::array::TryFromSliceError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        <[u8; N]>::try_from(bytes).map(Digest)
+    }
+}
+
+// serde only implements arrays of up to 32 elements, so Digest writes the tuple itself.
+
+impl<const N: usize> Serialize for Digest<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for byte in &self.0 {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Digest<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple(N, DigestVisitor::<N>(PhantomData))
+    }
+}
+
+struct DigestVisitor<const N: usize>(PhantomData<Digest<N>>);
+
+impl<'de, const N: usize> Visitor<'de> for DigestVisitor<N> {
+    type Value = Digest<N>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of {} bytes", N)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Digest<N>, A::Error> {
+        let mut digest = Digest::<N>::default();
+        for (idx, byte) in digest.0.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(idx, &self))?;
+        }
+        Ok(digest)
+    }
+}
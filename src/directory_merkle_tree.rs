@@ -0,0 +1,394 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::merkle_tree_hasher::{CryptographicHasher, DirectoryMerkleTreeHasher};
+use crate::{MerkleProof, MerkleTree};
+
+/// Length in bytes of the chunks whose hashes are the leaves of the tree of a file.
+pub const CONTENT_CHUNK_LEN: usize = 1 << 20;
+
+/// The kind of a [DirectoryEntry], hashed into the entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub enum EntryKind {
+    /// A regular file, committed by the root of the tree of its contents.
+    File,
+    /// A directory, committed by the root of its [DirectoryMerkleTree].
+    Directory,
+    /// A symbolic link, committed like a file whose contents are the link target.
+    /// Links are never followed.
+    Symlink,
+}
+
+impl EntryKind {
+    fn code(&self) -> u8 {
+        match self {
+            EntryKind::File => 0,
+            EntryKind::Directory => 1,
+            EntryKind::Symlink => 2,
+        }
+    }
+}
+
+/// Code of the marker committed by an empty directory, which no [EntryKind] uses.
+const EMPTY_DIRECTORY_CODE: u8 = 3;
+
+/// Chooses what a [DirectoryMerkleTree] commits to besides names and contents.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DirectoryOptions {
+    /// Commit to the Unix permission bits (`mode & 0o7777`) of files and directories.
+    ///
+    /// Always 0 for symbolic links and on other platforms.
+    pub mode_bits: bool,
+    /// Leave out directories holding nothing, instead of committing them as empty.
+    ///
+    /// The top directory is committed even if it is empty.
+    pub skip_empty_directories: bool,
+}
+
+/// A named entry of a directory, as hashed into a leaf of its [DirectoryMerkleTree].
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DirectoryEntry<T> {
+    name: Vec<u8>,
+    kind: EntryKind,
+    mode: u32,
+    root: T,
+}
+
+impl<T: Copy> DirectoryEntry<T> {
+    /// The bytes hashed with the root: the kind, the mode as 4 big-endian bytes and the name.
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(5 + self.name.len());
+        bytes.push(self.kind.code());
+        bytes.extend_from_slice(&self.mode.to_be_bytes());
+        bytes.extend_from_slice(&self.name);
+        bytes
+    }
+
+    /// Returns the name of this entry within its directory, as encoded by the platform.
+    pub fn name(&self) -> &[u8] {
+        &self.name
+    }
+
+    /// Returns the kind of this entry.
+    pub fn kind(&self) -> EntryKind {
+        self.kind
+    }
+
+    /// Returns the committed permission bits, 0 unless [DirectoryOptions::mode_bits] is set.
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    /// Returns the root of the contents of a file or link, or of the tree of a directory.
+    pub fn root(&self) -> T {
+        self.root
+    }
+}
+
+/// Commits to a directory tree: the names, kinds and contents of everything below a directory.
+///
+/// The entries of every directory are sorted by the bytes of their names and each becomes a leaf
+/// of a [MerkleTree], hashed with [`DirectoryMerkleTreeHasher::hash_directory_entry`]. A file
+/// entry holds the root of a [MerkleTree] over its chunks of [CONTENT_CHUNK_LEN] bytes, a
+/// directory entry the root of its own DirectoryMerkleTree. An empty file is a single empty chunk,
+/// an empty directory a single leaf which no entry can hash to.
+///
+/// Building fails on anything but regular files, directories and symbolic links.
+#[derive(Clone, Debug)]
+pub struct DirectoryMerkleTree<T: Copy, H: DirectoryMerkleTreeHasher<T>> {
+    entries: Vec<DirectoryEntry<T>>,
+    subdirectories: Vec<Option<DirectoryMerkleTree<T, H>>>,
+    tree: MerkleTree<T, H>,
+}
+
+impl<T, H> DirectoryMerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync + PartialEq,
+    H: Default + DirectoryMerkleTreeHasher<T>,
+{
+    /// Builds a DirectoryMerkleTree of the directory at `path` using the default [DirectoryOptions].
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<DirectoryMerkleTree<T, H>> {
+        DirectoryMerkleTree::with_options(path, DirectoryOptions::default())
+    }
+
+    /// Builds a DirectoryMerkleTree of the directory at `path`.
+    pub fn with_options<P: AsRef<Path>>(
+        path: P,
+        options: DirectoryOptions,
+    ) -> Result<DirectoryMerkleTree<T, H>> {
+        let path = path.as_ref();
+        ensure!(
+            path.symlink_metadata()?.is_dir(),
+            "'{}' is not a directory",
+            path.display()
+        );
+        DirectoryMerkleTree::read_directory(path, &options)
+            .map(|tree| tree.unwrap_or_else(DirectoryMerkleTree::empty))
+    }
+
+    /// Returns [None] for a directory skipped because it is empty.
+    fn read_directory(
+        path: &Path,
+        options: &DirectoryOptions,
+    ) -> Result<Option<DirectoryMerkleTree<T, H>>> {
+        let mut entries = Vec::new();
+        for dir_entry in std::fs::read_dir(path)? {
+            let dir_entry = dir_entry?;
+            let entry_path = dir_entry.path();
+            let metadata = entry_path.symlink_metadata()?;
+            let file_type = metadata.file_type();
+            let (kind, root, subdirectory) = if file_type.is_dir() {
+                match DirectoryMerkleTree::read_directory(&entry_path, options)? {
+                    Some(subdirectory) => (
+                        EntryKind::Directory,
+                        subdirectory.root(),
+                        Some(subdirectory),
+                    ),
+                    None => continue,
+                }
+            } else if file_type.is_symlink() {
+                let target = std::fs::read_link(&entry_path)?;
+                let root = content_root::<T, H, _>(target.as_os_str().as_encoded_bytes())?;
+                (EntryKind::Symlink, root, None)
+            } else if file_type.is_file() {
+                let file = File::open(&entry_path)
+                    .with_context(|| format!("reading '{}'", entry_path.display()))?;
+                (EntryKind::File, content_root::<T, H, _>(file)?, None)
+            } else {
+                bail!("unsupported file type at '{}'", entry_path.display());
+            };
+
+            let mode = if options.mode_bits && kind != EntryKind::Symlink {
+                mode_bits(&metadata)
+            } else {
+                0
+            };
+            let entry = DirectoryEntry {
+                name: dir_entry.file_name().as_encoded_bytes().to_vec(),
+                kind,
+                mode,
+                root,
+            };
+            entries.push((entry, subdirectory));
+        }
+
+        if entries.is_empty() {
+            return Ok(if options.skip_empty_directories {
+                None
+            } else {
+                Some(DirectoryMerkleTree::empty())
+            });
+        }
+
+        entries.sort_by(|(lhs, _), (rhs, _)| lhs.name.cmp(&rhs.name));
+        let (entries, subdirectories): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let leaves: Vec<T> = entries
+            .iter()
+            .map(|entry| H::hash_directory_entry(&entry.encode(), &entry.root))
+            .collect();
+        Ok(Some(DirectoryMerkleTree {
+            entries,
+            subdirectories,
+            tree: MerkleTree::new(&leaves)?,
+        }))
+    }
+
+    /// The tree of a directory without entries.
+    fn empty() -> DirectoryMerkleTree<T, H> {
+        let marker = H::hash_directory_entry(&[EMPTY_DIRECTORY_CODE], &H::hash_content_chunk(&[]));
+        DirectoryMerkleTree {
+            entries: Vec::new(),
+            subdirectories: Vec::new(),
+            tree: MerkleTree::new(&[marker]).unwrap(),
+        }
+    }
+
+    /// Builds the [DirectoryProof] that the file or symbolic link at the relative `path` has
+    /// its committed contents.
+    pub fn build_proof<P: AsRef<Path>>(&self, path: P) -> Result<DirectoryProof<T, H>> {
+        let names = path_names(path.as_ref())?;
+        let mut layers = Vec::with_capacity(names.len());
+        let mut directory = self;
+        for (depth, name) in names.iter().enumerate() {
+            let idx = directory
+                .entries
+                .binary_search_by(|entry| entry.name.as_slice().cmp(name))
+                .map_err(|_| anyhow!("'{}' is not committed", path.as_ref().display()))?;
+            let entry = &directory.entries[idx];
+            layers.push((entry.clone(), directory.tree.build_proof(idx)?));
+
+            let is_last = depth + 1 == names.len();
+            match (&directory.subdirectories[idx], is_last) {
+                (Some(subdirectory), false) => directory = subdirectory,
+                (None, true) => {}
+                (Some(_), true) => bail!("'{}' is a directory", path.as_ref().display()),
+                (None, false) => bail!("'{}' is not a directory", path.as_ref().display()),
+            }
+        }
+        Ok(DirectoryProof { layers })
+    }
+
+    /// Returns the entries of this directory, sorted by name.
+    pub fn entries(&self) -> &[DirectoryEntry<T>] {
+        &self.entries
+    }
+
+    /// Returns the tree of the subdirectory at `idx` of [`entries`](DirectoryMerkleTree::entries).
+    pub fn subdirectory(&self, idx: usize) -> Option<&DirectoryMerkleTree<T, H>> {
+        self.subdirectories.get(idx).and_then(Option::as_ref)
+    }
+
+    /// Returns the root, committing to everything below this directory.
+    pub fn root(&self) -> T {
+        self.tree.root()
+    }
+}
+
+/// Returns the root of the tree over the chunks of `contents`, as committed for a file.
+pub fn content_root<T, H, R>(mut contents: R) -> Result<T>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync,
+    H: Default + DirectoryMerkleTreeHasher<T>,
+    R: Read,
+{
+    let mut chunk = Vec::with_capacity(CONTENT_CHUNK_LEN);
+    let mut leaves = Vec::new();
+    loop {
+        chunk.clear();
+        // Reading until the chunk is full keeps the chunks independent of short reads.
+        (&mut contents)
+            .take(CONTENT_CHUNK_LEN as u64)
+            .read_to_end(&mut chunk)?;
+        if chunk.is_empty() && !leaves.is_empty() {
+            break;
+        }
+        leaves.push(H::hash_content_chunk(&chunk));
+        if chunk.len() < CONTENT_CHUNK_LEN {
+            break;
+        }
+    }
+    Ok(MerkleTree::<T, H>::new(&leaves)?.root())
+}
+
+/// Splits a relative path into the bytes of its names, rejecting `..` and absolute paths.
+fn path_names(path: &Path) -> Result<Vec<&[u8]>> {
+    let mut names = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => names.push(name.as_encoded_bytes()),
+            Component::CurDir => {}
+            _ => bail!("'{}' is not a plain relative path", path.display()),
+        }
+    }
+    ensure!(!names.is_empty(), "the path is empty");
+    Ok(names)
+}
+
+#[cfg(unix)]
+fn mode_bits(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    metadata.permissions().mode() & 0o7777
+}
+
+#[cfg(not(unix))]
+fn mode_bits(_metadata: &std::fs::Metadata) -> u32 {
+    0
+}
+
+/// Proves that a path within a [DirectoryMerkleTree] has the given contents.
+///
+/// Created by [`DirectoryMerkleTree.build_proof`](DirectoryMerkleTree::build_proof). Holds the
+/// entry and its [MerkleProof] for every name of the path, from the top directory down.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct DirectoryProof<T: Copy + Sized, H: DirectoryMerkleTreeHasher<T> + Default> {
+    pub(crate) layers: Vec<(DirectoryEntry<T>, MerkleProof<T, H>)>,
+}
+
+impl<T, H> DirectoryProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync + PartialEq,
+    H: Default + DirectoryMerkleTreeHasher<T>,
+{
+    /// Determine whether or not `path` has `contents` according to this proof.
+    ///
+    /// Every layer is recomputed from the contents, only the root of the top directory is taken from this proof.
+    pub fn validate_proof<P: AsRef<Path>>(&self, path: P, contents: &[u8]) -> bool {
+        match (self.compute_root(path.as_ref(), contents), self.root()) {
+            (Ok(computed), Some(root)) => computed == root,
+            _ => false,
+        }
+    }
+
+    /// Hashes from the contents up through every directory, ignoring the roots stored in the layers.
+    fn compute_root(&self, path: &Path, contents: &[u8]) -> Result<T> {
+        let names = path_names(path)?;
+        ensure!(
+            names.len() == self.layers.len(),
+            "the proof is for a path of {} names",
+            self.layers.len()
+        );
+
+        let mut root = content_root::<T, H, _>(contents)?;
+        for (depth, ((entry, proof), name)) in self.layers.iter().zip(names).enumerate().rev() {
+            // Only the last entry is a file or link, every other one is a directory.
+            let is_last = depth + 1 == self.layers.len();
+            ensure!(
+                (entry.kind == EntryKind::Directory) != is_last,
+                "entry {} of the proof has the wrong kind",
+                depth
+            );
+            ensure!(entry.name == name, "the proof is for another path");
+
+            let leaf_hash = H::hash_leaf(&H::hash_directory_entry(&entry.encode(), &root));
+            ensure!(proof.leaf_hash == leaf_hash, "the contents do not match");
+            ensure!(
+                proof.leaf_index < proof.num_leaves,
+                "the proof has no leaf {}",
+                proof.leaf_index
+            );
+            let (_, siblings) = proof
+                .sibling_hashes
+                .split_last()
+                .ok_or_else(|| anyhow!("entry {} of the proof has no root", depth))?;
+            let computed: MerkleProof<T, H> = MerkleProof::from_siblings(
+                proof.leaf_index,
+                proof.num_leaves,
+                leaf_hash,
+                siblings.to_vec(),
+            );
+            root = computed.root();
+        }
+        Ok(root)
+    }
+
+    /// Returns the entries of the path, from the top directory down to the file or link.
+    pub fn entries(&self) -> impl Iterator<Item = &DirectoryEntry<T>> {
+        self.layers.iter().map(|(entry, _)| entry)
+    }
+
+    /// Returns the root of the top directory, or [None] for a proof without layers or without that root.
+    pub fn root(&self) -> Option<T> {
+        self.layers
+            .first()
+            .and_then(|(_, proof)| proof.sibling_hashes.last().copied())
+    }
+}
+
+impl<T, H> DirectoryProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync + PartialEq,
+    H: CryptographicHasher<T> + Default + DirectoryMerkleTreeHasher<T>,
+{
+    /// Determine whether or not `path` has `contents` in the directory tree with the trusted root `root`.
+    ///
+    /// Unlike [`validate_proof`](DirectoryProof::validate_proof), the root inside this proof is not trusted.
+    /// The kinds and modes of [`entries`](DirectoryProof::entries) are trusted once this returns true.
+    pub fn verify<P: AsRef<Path>>(&self, path: P, contents: &[u8], root: &T) -> bool {
+        matches!(self.compute_root(path.as_ref(), contents), Ok(computed) if computed == *root)
+    }
+}
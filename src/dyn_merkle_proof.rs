@@ -0,0 +1,214 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+
+use crate::binary_format::{
+    decode_proof, encode_proof, ensure_algorithm, hasher_algorithm, typed_hash,
+};
+use crate::hasher_registry::typed_hasher;
+use crate::utils::fold_proof_path;
+use crate::{DynMerkleTreeHasher, HashAlgorithm, HasherRegistry, MerkleProof, MerkleTreeHasher};
+
+/// A [MerkleProof] whose hashes are byte vectors and whose hasher is picked at runtime.
+///
+/// Created by [`DynMerkleTree::build_proof`](crate::DynMerkleTree::build_proof), decoded with
+/// [`from_bytes`](DynMerkleProof::from_bytes) or converted from a [MerkleProof] with [TryFrom].
+#[derive(Clone)]
+pub struct DynMerkleProof {
+    pub(crate) leaf_index: usize,
+    pub(crate) num_leaves: usize,
+    pub(crate) leaf_hash: Vec<u8>,
+    pub(crate) sibling_hashes: Vec<Vec<u8>>,
+    pub(crate) root: Vec<u8>,
+    pub(crate) hasher: Arc<dyn DynMerkleTreeHasher>,
+}
+
+impl DynMerkleProof {
+    /// Determine whether or not the specified leaf is valid for this Merkle Proof.
+    ///
+    /// Always false when the hasher is not cryptographic, see
+    /// [`DynMerkleTreeHasher::is_cryptographic`], since its proofs can be forged.
+    pub fn validate_proof(&self, leaf: &[u8]) -> bool {
+        if !self.hasher.is_cryptographic() {
+            return false;
+        }
+        match self.hasher.hash_leaf(leaf) {
+            Ok(hash) if hash == self.leaf_hash => {
+                matches!(self.compute_root(), Ok(root) if root == self.root)
+            }
+            _ => false,
+        }
+    }
+
+    /// Hashes from the leaf hash up to the root using the sibling hashes.
+    fn compute_root(&self) -> Result<Vec<u8>> {
+        let result_hashes = fold_proof_path(
+            self.leaf_index,
+            self.num_leaves,
+            &self.leaf_hash,
+            &self.sibling_hashes,
+            self.hasher.non_leaf_node_starting_prefix()[0],
+            self.hasher.wrap_to_value(),
+            |prefix, lhs, rhs| self.hasher.hash_non_leaf_node(prefix, lhs, rhs),
+        )?;
+        Ok(result_hashes.last().unwrap_or(&self.leaf_hash).to_vec())
+    }
+
+    /// Encodes this [DynMerkleProof] using the [binary_format](crate::binary_format).
+    ///
+    /// The bytes are the same as those of the equivalent [MerkleProof].
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_proof(
+            self.algorithm(),
+            self.leaf_index,
+            self.num_leaves,
+            &self.leaf_hash,
+            self.sibling_hashes
+                .iter()
+                .chain(Some(&self.root))
+                .map(|hash| hash.as_slice())
+                .collect::<Vec<&[u8]>>()
+                .into_iter(),
+        )
+    }
+
+    /// Decodes a proof encoded by [`to_bytes`](DynMerkleProof::to_bytes) or [`MerkleProof::to_bytes`],
+    /// using the compiled-in hasher for its algorithm.
+    ///
+    /// The sibling hashes must lead to the encoded root.
+    pub fn from_bytes(bytes: &[u8]) -> Result<DynMerkleProof> {
+        DynMerkleProof::from_bytes_with_registry(bytes, &HasherRegistry::default())
+    }
+
+    /// Like [`from_bytes`](DynMerkleProof::from_bytes), taking the hasher from `registry`.
+    pub fn from_bytes_with_registry(
+        bytes: &[u8],
+        registry: &HasherRegistry,
+    ) -> Result<DynMerkleProof> {
+        let parts = decode_proof(bytes, |hash| Ok(hash.to_vec()))?;
+        let hasher = registry.get(parts.algorithm, parts.leaf_hash.len())?;
+
+        let proof = DynMerkleProof {
+            leaf_index: parts.leaf_index,
+            num_leaves: parts.num_leaves,
+            leaf_hash: parts.leaf_hash,
+            sibling_hashes: parts.siblings,
+            root: parts.root,
+            hasher,
+        };
+        ensure!(
+            proof.compute_root()? == proof.root,
+            "the siblings do not lead to the encoded root"
+        );
+        Ok(proof)
+    }
+
+    /// Returns the [HashAlgorithm] of the hasher of this proof.
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.hasher.algorithm()
+    }
+
+    /// Returns the index of the proven leaf.
+    pub fn leaf_index(&self) -> usize {
+        self.leaf_index
+    }
+
+    /// Returns the number of leaves of the tree this proof was built from.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the hash of the proven leaf.
+    pub fn leaf_hash(&self) -> &[u8] {
+        &self.leaf_hash
+    }
+
+    /// Returns the sibling hashes from the leaf level upwards, without the root.
+    pub fn sibling_hashes(&self) -> &[Vec<u8>] {
+        &self.sibling_hashes
+    }
+
+    /// Returns the root of the tree this proof was built from.
+    pub fn root(&self) -> &[u8] {
+        &self.root
+    }
+}
+
+impl Debug for DynMerkleProof {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynMerkleProof")
+            .field("algorithm", &self.algorithm())
+            .field("leaf_index", &self.leaf_index)
+            .field("num_leaves", &self.num_leaves)
+            .field("leaf_hash", &self.leaf_hash)
+            .field("sibling_hashes", &self.sibling_hashes)
+            .field("root", &self.root)
+            .finish()
+    }
+}
+
+impl PartialEq for DynMerkleProof {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm() == other.algorithm()
+            && self.leaf_index == other.leaf_index
+            && self.num_leaves == other.num_leaves
+            && self.leaf_hash == other.leaf_hash
+            && self.sibling_hashes == other.sibling_hashes
+            && self.root == other.root
+    }
+}
+
+impl<T, H> TryFrom<&MerkleProof<T, H>> for DynMerkleProof
+where
+    T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]> + 'static,
+    H: Default + MerkleTreeHasher<T> + 'static,
+{
+    type Error = anyhow::Error;
+
+    /// Fails if `H` has no [HashAlgorithm] or `proof` has no root.
+    fn try_from(proof: &MerkleProof<T, H>) -> Result<DynMerkleProof> {
+        let (root, siblings) = proof
+            .sibling_hashes
+            .split_last()
+            .ok_or_else(|| anyhow!("the proof has no root"))?;
+        Ok(DynMerkleProof {
+            leaf_index: proof.leaf_index,
+            num_leaves: proof.num_leaves,
+            leaf_hash: proof.leaf_hash.as_ref().to_vec(),
+            sibling_hashes: siblings.iter().map(|hash| hash.as_ref().to_vec()).collect(),
+            root: root.as_ref().to_vec(),
+            hasher: typed_hasher::<T, H>()?,
+        })
+    }
+}
+
+impl<T, H> TryFrom<&DynMerkleProof> for MerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]>,
+    H: Default + MerkleTreeHasher<T>,
+{
+    type Error = anyhow::Error;
+
+    /// Fails unless the proof was hashed with the algorithm of `H` and leads to its root.
+    fn try_from(proof: &DynMerkleProof) -> Result<MerkleProof<T, H>> {
+        ensure_algorithm(proof.algorithm(), hasher_algorithm::<T, H>()?)?;
+        let typed_proof: MerkleProof<T, H> = MerkleProof::from_siblings(
+            proof.leaf_index,
+            proof.num_leaves,
+            typed_hash(&proof.leaf_hash)?,
+            proof
+                .sibling_hashes
+                .iter()
+                .map(|hash| typed_hash(hash))
+                .collect::<Result<Vec<T>>>()?,
+        );
+        ensure!(
+            typed_proof.sibling_hashes[typed_proof.sibling_hashes.len() - 1].as_ref()
+                == proof.root.as_slice(),
+            "the siblings do not lead to the root"
+        );
+        Ok(typed_proof)
+    }
+}
@@ -0,0 +1,265 @@
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, ensure, Result};
+
+use crate::binary_format::{
+    decode_tree, encode_tree, ensure_algorithm, hasher_algorithm, typed_hash,
+};
+use crate::hasher_registry::typed_hasher;
+use crate::utils::build_levels;
+use crate::{
+    add_1_if_odd, count_tree_nodes, is_odd, DynMerkleProof, DynMerkleTreeHasher, HashAlgorithm,
+    HasherRegistry, MerkleTree, MerkleTreeHasher,
+};
+
+/// A [MerkleTree] whose hashes are byte vectors and whose hasher is picked at runtime.
+///
+/// Builds the same nodes as [MerkleTree] with the same hasher, so both convert into each other
+/// with [TryFrom].
+#[derive(Clone)]
+pub struct DynMerkleTree {
+    pub(crate) num_leaves: usize,
+    pub(crate) tree: Vec<Vec<u8>>,
+    pub(crate) hasher: Arc<dyn DynMerkleTreeHasher>,
+}
+
+impl DynMerkleTree {
+    /// Builds a DynMerkleTree from `leaves` using the compiled-in hasher for `algorithm`.
+    ///
+    /// The digest length is the length of the first leaf. Fails if there are no leaves,
+    /// no hasher for `algorithm` and that length is enabled, or the leaves differ in length.
+    pub fn new<L: AsRef<[u8]>>(algorithm: HashAlgorithm, leaves: &[L]) -> Result<DynMerkleTree> {
+        let digest_len = leaves
+            .first()
+            .map(|leaf| leaf.as_ref().len())
+            .ok_or_else(|| anyhow!("a Merkle Tree needs at least one leaf"))?;
+        DynMerkleTree::with_hasher(
+            HasherRegistry::default().get(algorithm, digest_len)?,
+            leaves,
+        )
+    }
+
+    /// Builds a DynMerkleTree from `leaves` using `hasher`.
+    pub fn with_hasher<L: AsRef<[u8]>>(
+        hasher: Arc<dyn DynMerkleTreeHasher>,
+        leaves: &[L],
+    ) -> Result<DynMerkleTree> {
+        ensure!(!leaves.is_empty(), "a Merkle Tree needs at least one leaf");
+        let num_leaves = leaves.len();
+        let mut tree = Vec::with_capacity(count_tree_nodes(num_leaves));
+        for leaf in leaves {
+            tree.push(hasher.hash_leaf(leaf.as_ref())?);
+        }
+        DynMerkleTree::from_leaf_hashes(hasher, tree)
+    }
+
+    /// Builds a DynMerkleTree from already hashed leaves, recomputing the non-leaf nodes.
+    fn from_leaf_hashes(
+        hasher: Arc<dyn DynMerkleTreeHasher>,
+        mut tree: Vec<Vec<u8>>,
+    ) -> Result<DynMerkleTree> {
+        let num_leaves = tree.len();
+        tree.reserve_exact(count_tree_nodes(num_leaves) - num_leaves);
+
+        build_levels(
+            &mut tree,
+            num_leaves,
+            hasher.non_leaf_node_starting_prefix()[0],
+            hasher.wrap_to_value(),
+            |prefix, lhs, rhs| hasher.hash_non_leaf_node(prefix, lhs, rhs),
+        )?;
+
+        Ok(DynMerkleTree {
+            num_leaves,
+            tree,
+            hasher,
+        })
+    }
+
+    /// Builds the [DynMerkleProof] for the leaf at `leaf_index`.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<DynMerkleProof> {
+        ensure!(
+            leaf_index < self.num_leaves,
+            "leaf index {} is out of range for {} leaves",
+            leaf_index,
+            self.num_leaves
+        );
+
+        let mut sibling_hashes = Vec::new();
+        let mut level_start = 0;
+        let mut level_count = self.num_leaves;
+        let mut current_idx = leaf_index;
+
+        while level_count > 1 {
+            let sibling_idx = if is_odd(current_idx) {
+                current_idx - 1
+            } else {
+                (current_idx + 1).min(level_count - 1)
+            };
+            sibling_hashes.push(self.tree[level_start + sibling_idx].clone());
+
+            current_idx /= 2;
+            level_start += level_count;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        Ok(DynMerkleProof {
+            leaf_index,
+            num_leaves: self.num_leaves,
+            leaf_hash: self.tree[leaf_index].clone(),
+            sibling_hashes,
+            root: self.root().to_vec(),
+            hasher: self.hasher.clone(),
+        })
+    }
+
+    /// Checks that every non-leaf node equals the hash recomputed from its children.
+    pub fn verify_integrity(&self) -> Result<()> {
+        let expected = DynMerkleTree::from_leaf_hashes(
+            self.hasher.clone(),
+            self.tree[..self.num_leaves].to_vec(),
+        )?;
+        ensure!(
+            self.tree.len() == expected.tree.len(),
+            "{} leaves need {} nodes, found {}",
+            self.num_leaves,
+            expected.tree.len(),
+            self.tree.len()
+        );
+        if let Some(idx) =
+            (self.num_leaves..self.tree.len()).find(|idx| self.tree[*idx] != expected.tree[*idx])
+        {
+            bail!("node {} does not match the hash of its children", idx);
+        }
+        Ok(())
+    }
+
+    /// Encodes this [DynMerkleTree] using the [binary_format](crate::binary_format).
+    ///
+    /// The bytes are the same as those of the equivalent [MerkleTree].
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_tree(
+            self.algorithm(),
+            self.digest_len(),
+            self.tree[..self.num_leaves]
+                .iter()
+                .map(|hash| hash.as_slice()),
+        )
+    }
+
+    /// Decodes a tree encoded by [`to_bytes`](DynMerkleTree::to_bytes) or [`MerkleTree::to_bytes`],
+    /// using the compiled-in hasher for its algorithm.
+    pub fn from_bytes(bytes: &[u8]) -> Result<DynMerkleTree> {
+        DynMerkleTree::from_bytes_with_registry(bytes, &HasherRegistry::default())
+    }
+
+    /// Like [`from_bytes`](DynMerkleTree::from_bytes), taking the hasher from `registry`.
+    pub fn from_bytes_with_registry(
+        bytes: &[u8],
+        registry: &HasherRegistry,
+    ) -> Result<DynMerkleTree> {
+        let parts = decode_tree(bytes, |hash| Ok(hash.to_vec()))?;
+        let hasher = registry.get(parts.algorithm, parts.leaf_hashes[0].len())?;
+        DynMerkleTree::from_leaf_hashes(hasher, parts.leaf_hashes)
+    }
+
+    /// Returns the [HashAlgorithm] of the hasher of this tree.
+    pub fn algorithm(&self) -> HashAlgorithm {
+        self.hasher.algorithm()
+    }
+
+    /// Returns the name of the hasher of this tree.
+    pub fn hash_name(&self) -> String {
+        self.hasher.name()
+    }
+
+    /// Returns the length in bytes of every hash in this tree.
+    pub fn digest_len(&self) -> usize {
+        self.hasher.digest_len()
+    }
+
+    /// Returns the number of leaves used to create this Merkle Tree.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the total number of leaves and nodes in this tree.
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Always false, a tree has at least one leaf.
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Returns the Merkle Tree root.
+    pub fn root(&self) -> &[u8] {
+        &self.tree[self.tree.len() - 1]
+    }
+}
+
+impl Debug for DynMerkleTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynMerkleTree")
+            .field("algorithm", &self.algorithm())
+            .field("num_leaves", &self.num_leaves)
+            .field("tree", &self.tree)
+            .finish()
+    }
+}
+
+impl PartialEq for DynMerkleTree {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithm() == other.algorithm()
+            && self.num_leaves == other.num_leaves
+            && self.tree == other.tree
+    }
+}
+
+impl<T, H> TryFrom<&MerkleTree<T, H>> for DynMerkleTree
+where
+    T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]> + 'static,
+    H: Default + MerkleTreeHasher<T> + 'static,
+{
+    type Error = anyhow::Error;
+
+    /// Fails if `H` has no [HashAlgorithm].
+    fn try_from(merkle_tree: &MerkleTree<T, H>) -> Result<DynMerkleTree> {
+        Ok(DynMerkleTree {
+            num_leaves: merkle_tree.num_leaves,
+            tree: merkle_tree
+                .tree
+                .iter()
+                .map(|hash| hash.as_ref().to_vec())
+                .collect(),
+            hasher: typed_hasher::<T, H>()?,
+        })
+    }
+}
+
+impl<T, H> TryFrom<&DynMerkleTree> for MerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]>,
+    H: Default + MerkleTreeHasher<T>,
+{
+    type Error = anyhow::Error;
+
+    /// Fails unless the tree was hashed with the algorithm of `H`.
+    fn try_from(merkle_tree: &DynMerkleTree) -> Result<MerkleTree<T, H>> {
+        ensure_algorithm(merkle_tree.algorithm(), hasher_algorithm::<T, H>()?)?;
+        Ok(MerkleTree {
+            num_leaves: merkle_tree.num_leaves,
+            tree: merkle_tree
+                .tree
+                .iter()
+                .map(|hash| typed_hash(hash))
+                .collect::<Result<Vec<T>>>()?,
+            hash_name: H::default().name(),
+            _dummy: Default::default(),
+            leaf_positions: Default::default(),
+        })
+    }
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Stable identifier for the hash algorithm behind a [MerkleTreeHasher](crate::MerkleTreeHasher).
+///
+/// Each algorithm has a numeric [`code`](HashAlgorithm::code) taken from the
+/// [multicodec table](https://github.com/multiformats/multicodec/blob/master/table.csv),
+/// which is what the [binary_format](crate::binary_format) writes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub enum HashAlgorithm {
+    /// SHA-256, multicodec `sha2-256`.
+    Sha256,
+    /// SHA-512/256, multicodec `sha2-512-256`.
+    Sha512_256,
+    /// SHA3-256, multicodec `sha3-256`.
+    Sha3_256,
+    /// BLAKE2b with a 32 byte output, multicodec `blake2b-256`.
+    Blake2b256,
+    /// BLAKE2s with a 32 byte output, multicodec `blake2s-256`.
+    Blake2s256,
+    /// Keccak-256 as used by Ethereum, multicodec `keccak-256`.
+    Keccak256,
+    /// BLAKE3 with a 32 byte output, multicodec `blake3`.
+    Blake3,
+    /// MD5, multicodec `md5`.
+    Md5,
+    /// XXH3 with a 128 bit output, multicodec `xxh3-128`. Not a cryptographic hash.
+    Xxh3_128,
+    /// Poseidon over the BN254 scalar field with the circomlib parameters.
+    ///
+    /// The multicodec table has no code for it, so it uses `0x300001` from the private use range.
+    PoseidonBn254,
+    /// An algorithm outside this crate identified by its own code.
+    ///
+    /// Codes of the algorithms above are never returned as `Custom` by [`from_code`](HashAlgorithm::from_code).
+    Custom(u64),
+}
+
+impl HashAlgorithm {
+    /// The numeric identifier of this algorithm.
+    pub fn code(&self) -> u64 {
+        match self {
+            HashAlgorithm::Sha256 => 0x12,
+            HashAlgorithm::Sha512_256 => 0x1015,
+            HashAlgorithm::Sha3_256 => 0x16,
+            HashAlgorithm::Blake2b256 => 0xb220,
+            HashAlgorithm::Blake2s256 => 0xb260,
+            HashAlgorithm::Keccak256 => 0x1b,
+            HashAlgorithm::Blake3 => 0x1e,
+            HashAlgorithm::Md5 => 0xd5,
+            HashAlgorithm::Xxh3_128 => 0xb3e4,
+            HashAlgorithm::PoseidonBn254 => 0x300001,
+            HashAlgorithm::Custom(code) => *code,
+        }
+    }
+
+    /// The algorithm with the given numeric identifier.
+    pub fn from_code(code: u64) -> HashAlgorithm {
+        match code {
+            0x12 => HashAlgorithm::Sha256,
+            0x1015 => HashAlgorithm::Sha512_256,
+            0x16 => HashAlgorithm::Sha3_256,
+            0xb220 => HashAlgorithm::Blake2b256,
+            0xb260 => HashAlgorithm::Blake2s256,
+            0x1b => HashAlgorithm::Keccak256,
+            0x1e => HashAlgorithm::Blake3,
+            0xd5 => HashAlgorithm::Md5,
+            0xb3e4 => HashAlgorithm::Xxh3_128,
+            0x300001 => HashAlgorithm::PoseidonBn254,
+            _ => HashAlgorithm::Custom(code),
+        }
+    }
+
+    /// True if the hasher of this crate for this algorithm is a [CryptographicHasher](crate::CryptographicHasher).
+    ///
+    /// False for [Custom](HashAlgorithm::Custom), whose hasher is unknown. A hasher truncated
+    /// to less than 16 bytes is never cryptographic, whatever its algorithm.
+    pub fn is_cryptographic(&self) -> bool {
+        !matches!(
+            self,
+            HashAlgorithm::Md5 | HashAlgorithm::Xxh3_128 | HashAlgorithm::Custom(_)
+        )
+    }
+
+    /// The name used as `hash_name` by the hashers in this crate.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "SHA-256",
+            HashAlgorithm::Sha512_256 => "SHA-512/256",
+            HashAlgorithm::Sha3_256 => "SHA3-256",
+            HashAlgorithm::Blake2b256 => "BLAKE2b-256",
+            HashAlgorithm::Blake2s256 => "BLAKE2s-256",
+            HashAlgorithm::Keccak256 => "Keccak-256",
+            HashAlgorithm::Blake3 => "Blake3",
+            HashAlgorithm::Md5 => "MD5",
+            HashAlgorithm::Xxh3_128 => "XXH3-128",
+            HashAlgorithm::PoseidonBn254 => "Poseidon-BN254",
+            HashAlgorithm::Custom(_) => "Custom",
+        }
+    }
+
+    /// The name of this algorithm with `digest_len` byte hashes, for example `SHA-256/160`.
+    ///
+    /// Just [`name`](HashAlgorithm::name) when `digest_len` is `default_len`.
+    pub(crate) fn name_for_len(&self, digest_len: usize, default_len: usize) -> String {
+        if digest_len == default_len {
+            self.name().to_string()
+        } else {
+            format!("{}/{}", self.name(), digest_len * 8)
+        }
+    }
+
+    /// The algorithm with the given [`name`](HashAlgorithm::name), if it is one of this crate's algorithms.
+    pub fn from_name(name: &str) -> Option<HashAlgorithm> {
+        match name {
+            "SHA-256" => Some(HashAlgorithm::Sha256),
+            "SHA-512/256" => Some(HashAlgorithm::Sha512_256),
+            "SHA3-256" => Some(HashAlgorithm::Sha3_256),
+            "BLAKE2b-256" => Some(HashAlgorithm::Blake2b256),
+            "BLAKE2s-256" => Some(HashAlgorithm::Blake2s256),
+            "Keccak-256" => Some(HashAlgorithm::Keccak256),
+            "Blake3" => Some(HashAlgorithm::Blake3),
+            "MD5" => Some(HashAlgorithm::Md5),
+            "XXH3-128" => Some(HashAlgorithm::Xxh3_128),
+            "Poseidon-BN254" => Some(HashAlgorithm::PoseidonBn254),
+            _ => None,
+        }
+    }
+}
+
+impl Display for HashAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HashAlgorithm::Custom(code) => write!(f, "Custom({:#x})", code),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+
+use crate::binary_format::{decode_proof, hasher_algorithm};
+use crate::merkle_tree_hasher::MIN_CRYPTOGRAPHIC_DIGEST_LEN;
+use crate::utils::fold_proof_path;
+use crate::{CryptographicHasher, HashAlgorithm, MerkleTreeHasher, NonCryptographic};
+
+/// An object safe view of a [MerkleTreeHasher] working on byte slices.
+///
+/// Lets a [HasherRegistry] pick the hasher for a tree or proof at runtime.
+pub trait DynMerkleTreeHasher: Send + Sync {
+    /// The [HashAlgorithm] of the underlying [MerkleTreeHasher].
+    fn algorithm(&self) -> HashAlgorithm;
+
+    /// The name of the underlying [MerkleTreeHasher].
+    fn name(&self) -> String;
+
+    /// The length in bytes of every hash.
+    fn digest_len(&self) -> usize;
+
+    /// Hash a Leaf Node. Fails if `leaf` is not [`digest_len()`](DynMerkleTreeHasher::digest_len) bytes long.
+    fn hash_leaf(&self, leaf: &[u8]) -> Result<Vec<u8>>;
+
+    /// Hash a non-Leaf Node. Fails if `lhs` or `rhs` are not [`digest_len()`](DynMerkleTreeHasher::digest_len) bytes long.
+    fn hash_non_leaf_node(&self, prefix: &[u8; 1], lhs: &[u8], rhs: &[u8]) -> Result<Vec<u8>>;
+
+    /// Starting prefix for non-Leaf Nodes.
+    fn non_leaf_node_starting_prefix(&self) -> [u8; 1];
+
+    /// Should the non-leaf-node-prefix exceed 255 it will wrap around to this number.
+    fn wrap_to_value(&self) -> u8;
+
+    /// True if the underlying hasher is a [CryptographicHasher].
+    ///
+    /// Proofs are only validated with a cryptographic hasher, since any other hasher lets an
+    /// attacker forge them. Defaults to false.
+    fn is_cryptographic(&self) -> bool {
+        false
+    }
+}
+
+/// Adapts a [MerkleTreeHasher] over `T` to [DynMerkleTreeHasher].
+struct TypedHasher<T, H> {
+    algorithm: HashAlgorithm,
+    cryptographic: bool,
+    _dummy: PhantomData<fn() -> (T, H)>,
+}
+
+/// Wraps the [MerkleTreeHasher] `H` as a [DynMerkleTreeHasher].
+///
+/// `H` is taken as cryptographic if the hasher of this crate for its algorithm is, see
+/// [`HashAlgorithm::is_cryptographic`], and `T` is at least 16 bytes long.
+pub(crate) fn typed_hasher<T, H>() -> Result<Arc<dyn DynMerkleTreeHasher>>
+where
+    T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]> + 'static,
+    H: Default + MerkleTreeHasher<T> + 'static,
+{
+    let algorithm = hasher_algorithm::<T, H>()?;
+    Ok(Arc::new(TypedHasher::<T, H> {
+        algorithm,
+        cryptographic: algorithm.is_cryptographic()
+            && size_of::<T>() >= MIN_CRYPTOGRAPHIC_DIGEST_LEN,
+        _dummy: Default::default(),
+    }))
+}
+
+fn to_hash<T: for<'a> TryFrom<&'a [u8]>>(bytes: &[u8]) -> Result<T> {
+    T::try_from(bytes).map_err(|_| anyhow!("expected a {} byte hash", size_of::<T>()))
+}
+
+impl<T, H> DynMerkleTreeHasher for TypedHasher<T, H>
+where
+    T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]>,
+    H: Default + MerkleTreeHasher<T>,
+{
+    fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
+    }
+
+    fn name(&self) -> String {
+        H::default().name()
+    }
+
+    fn digest_len(&self) -> usize {
+        size_of::<T>()
+    }
+
+    fn hash_leaf(&self, leaf: &[u8]) -> Result<Vec<u8>> {
+        let leaf: T = to_hash(leaf)?;
+        Ok(H::hash_leaf(&leaf).as_ref().to_vec())
+    }
+
+    fn hash_non_leaf_node(&self, prefix: &[u8; 1], lhs: &[u8], rhs: &[u8]) -> Result<Vec<u8>> {
+        let lhs: T = to_hash(lhs)?;
+        let rhs: T = to_hash(rhs)?;
+        Ok(H::hash_non_leaf_node(prefix, &lhs, &rhs).as_ref().to_vec())
+    }
+
+    fn non_leaf_node_starting_prefix(&self) -> [u8; 1] {
+        H::non_leaf_node_starting_prefix()
+    }
+
+    fn wrap_to_value(&self) -> u8 {
+        H::wrap_to_value()
+    }
+
+    fn is_cryptographic(&self) -> bool {
+        self.cryptographic
+    }
+}
+
+/// Maps each [HashAlgorithm] and digest length to a [DynMerkleTreeHasher].
+///
+/// [`HasherRegistry::default()`](HasherRegistry::default) holds every hasher enabled by a cargo feature.
+/// Other hashers can be added with [`register`](HasherRegistry::register).
+///
+/// This is used to verify trees and proofs loaded from disk without knowing their hasher at compile time.
+#[derive(Clone)]
+pub struct HasherRegistry {
+    hashers: HashMap<(HashAlgorithm, usize), Arc<dyn DynMerkleTreeHasher>>,
+}
+
+impl HasherRegistry {
+    /// Creates a registry without any hashers.
+    pub fn new() -> HasherRegistry {
+        HasherRegistry {
+            hashers: HashMap::new(),
+        }
+    }
+
+    /// Registers the [MerkleTreeHasher] `H`, replacing any hasher with the same [HashAlgorithm]
+    /// and digest length.
+    ///
+    /// Fails if `H` has no [HashAlgorithm].
+    pub fn register<T, H>(&mut self) -> Result<()>
+    where
+        T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]> + 'static,
+        H: CryptographicHasher<T> + Default + MerkleTreeHasher<T> + 'static,
+    {
+        self.register_typed::<T, H>(hasher_algorithm::<T, H>()?, true);
+        Ok(())
+    }
+
+    /// Like [`register`](HasherRegistry::register) for a [NonCryptographic] hasher.
+    ///
+    /// Its trees can be decoded, but [`validate_proof_bytes`](HasherRegistry::validate_proof_bytes)
+    /// refuses its proofs.
+    pub fn register_non_cryptographic<T, H>(&mut self) -> Result<()>
+    where
+        T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]> + 'static,
+        H: NonCryptographic + Default + MerkleTreeHasher<T> + 'static,
+    {
+        self.register_typed::<T, H>(hasher_algorithm::<T, H>()?, false);
+        Ok(())
+    }
+
+    fn register_typed<T, H>(&mut self, algorithm: HashAlgorithm, cryptographic: bool)
+    where
+        T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]> + 'static,
+        H: Default + MerkleTreeHasher<T> + 'static,
+    {
+        self.register_dyn(Arc::new(TypedHasher::<T, H> {
+            algorithm,
+            cryptographic,
+            _dummy: Default::default(),
+        }));
+    }
+
+    /// Registers a [DynMerkleTreeHasher], replacing any hasher with the same [HashAlgorithm]
+    /// and digest length.
+    pub fn register_dyn(&mut self, hasher: Arc<dyn DynMerkleTreeHasher>) {
+        self.hashers
+            .insert((hasher.algorithm(), hasher.digest_len()), hasher);
+    }
+
+    /// Returns the hasher for `algorithm` producing `digest_len` byte hashes.
+    pub fn get(
+        &self,
+        algorithm: HashAlgorithm,
+        digest_len: usize,
+    ) -> Result<Arc<dyn DynMerkleTreeHasher>> {
+        self.hashers
+            .get(&(algorithm, digest_len))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow!(
+                    "no hasher registered for {} with {} byte hashes",
+                    algorithm,
+                    digest_len
+                )
+            })
+    }
+
+    /// Returns the registered algorithms with their digest lengths.
+    pub fn algorithms(&self) -> Vec<(HashAlgorithm, usize)> {
+        self.hashers.keys().copied().collect()
+    }
+
+    /// Determine whether or not `leaf` is valid for a [MerkleProof](crate::MerkleProof)
+    /// encoded by [`MerkleProof::to_bytes`](crate::MerkleProof::to_bytes).
+    ///
+    /// The hasher is picked using the algorithm id of the encoded proof.
+    /// Fails if the proof is malformed, its algorithm is not registered or its hasher is not
+    /// cryptographic, see [`DynMerkleTreeHasher::is_cryptographic`].
+    pub fn validate_proof_bytes(&self, proof: &[u8], leaf: &[u8]) -> Result<bool> {
+        let parts = decode_proof(proof, |hash| Ok(hash.to_vec()))?;
+        let hasher = self.get(parts.algorithm, parts.leaf_hash.len())?;
+        ensure!(
+            hasher.is_cryptographic(),
+            "{} is not a cryptographic hash, its proofs can be forged",
+            parts.algorithm
+        );
+
+        if hasher.hash_leaf(leaf)? != parts.leaf_hash {
+            return Ok(false);
+        }
+        let result_hashes = fold_proof_path(
+            parts.leaf_index,
+            parts.num_leaves,
+            &parts.leaf_hash,
+            &parts.siblings,
+            hasher.non_leaf_node_starting_prefix()[0],
+            hasher.wrap_to_value(),
+            |prefix, lhs, rhs| hasher.hash_non_leaf_node(prefix, lhs, rhs),
+        )?;
+        let root = result_hashes.last().unwrap_or(&parts.leaf_hash);
+        Ok(*root == parts.root)
+    }
+}
+
+impl Default for HasherRegistry {
+    /// Creates a registry holding every hasher enabled by a cargo feature.
+    fn default() -> HasherRegistry {
+        #[allow(unused_mut)]
+        let mut registry = HasherRegistry::new();
+        #[cfg(any(feature = "blake2b_256_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::Blake2b256MerkleTreeHasher>(
+            HashAlgorithm::Blake2b256,
+            true,
+        );
+        #[cfg(any(feature = "blake2s_256_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::Blake2s256MerkleTreeHasher>(
+            HashAlgorithm::Blake2s256,
+            true,
+        );
+        #[cfg(any(feature = "blake3_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::Blake3MerkleTreeHasher>(
+            HashAlgorithm::Blake3,
+            true,
+        );
+        #[cfg(any(feature = "keccak256_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::Keccak256MerkleTreeHasher>(
+            HashAlgorithm::Keccak256,
+            true,
+        );
+        #[cfg(any(feature = "md5_hash", test))]
+        registry.register_typed::<[u8; 16], crate::algos::Md5MerkleTreeHasher>(
+            HashAlgorithm::Md5,
+            false,
+        );
+        #[cfg(any(feature = "poseidon_bn254_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::PoseidonBn254MerkleTreeHasher>(
+            HashAlgorithm::PoseidonBn254,
+            true,
+        );
+        #[cfg(any(feature = "sha256_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::Sha256MerkleTreeHasher>(
+            HashAlgorithm::Sha256,
+            true,
+        );
+        #[cfg(any(feature = "sha3_256_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::Sha3_256MerkleTreeHasher>(
+            HashAlgorithm::Sha3_256,
+            true,
+        );
+        #[cfg(any(feature = "sha512_256_hash", test))]
+        registry.register_typed::<[u8; 32], crate::algos::Sha512_256MerkleTreeHasher>(
+            HashAlgorithm::Sha512_256,
+            true,
+        );
+        #[cfg(any(feature = "xxh3_128_hash", test))]
+        registry.register_typed::<[u8; 16], crate::algos::Xxh3_128MerkleTreeHasher>(
+            HashAlgorithm::Xxh3_128,
+            false,
+        );
+        registry
+    }
+}
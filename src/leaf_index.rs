@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use anyhow::{anyhow, Result};
+
+use crate::{MerkleProof, MerkleTree, MerkleTreeHasher};
+
+/// Maps leaf hashes to their positions in a [MerkleTree].
+#[derive(Clone, Debug)]
+pub(crate) enum LeafIndex<T> {
+    /// The leaf hashes of the tree are in ascending order and are searched in place.
+    Sorted,
+    /// The positions of every leaf hash, in ascending order.
+    Map(HashMap<T, Vec<usize>>),
+}
+
+impl<T: AsRef<[u8]> + Copy + Eq + Hash> LeafIndex<T> {
+    fn map(leaf_hashes: &[T]) -> LeafIndex<T> {
+        let mut positions: HashMap<T, Vec<usize>> = HashMap::with_capacity(leaf_hashes.len());
+        for (idx, leaf_hash) in leaf_hashes.iter().enumerate() {
+            positions.entry(*leaf_hash).or_default().push(idx);
+        }
+        LeafIndex::Map(positions)
+    }
+
+    /// Searches `leaf_hashes` in place if they are in ascending order, otherwise maps them.
+    fn sorted_or_map(leaf_hashes: &[T]) -> LeafIndex<T> {
+        if leaf_hashes
+            .windows(2)
+            .all(|pair| pair[0].as_ref() <= pair[1].as_ref())
+        {
+            LeafIndex::Sorted
+        } else {
+            LeafIndex::map(leaf_hashes)
+        }
+    }
+
+    /// Returns the positions of `leaf_hash` among `leaf_hashes`, the leaves this index was built from.
+    fn positions_of(&self, leaf_hashes: &[T], leaf_hash: &T) -> Vec<usize> {
+        match self {
+            LeafIndex::Sorted => sorted_positions_of(leaf_hashes, leaf_hash).collect(),
+            LeafIndex::Map(positions) => positions.get(leaf_hash).cloned().unwrap_or_default(),
+        }
+    }
+
+    fn position_of(&self, leaf_hashes: &[T], leaf_hash: &T) -> Option<usize> {
+        match self {
+            LeafIndex::Sorted => sorted_positions_of(leaf_hashes, leaf_hash).next(),
+            LeafIndex::Map(positions) => positions.get(leaf_hash).map(|idxs| idxs[0]),
+        }
+    }
+}
+
+/// Binary search for the positions of `leaf_hash` in leaf hashes in ascending order.
+fn sorted_positions_of<T: AsRef<[u8]>>(leaf_hashes: &[T], leaf_hash: &T) -> std::ops::Range<usize> {
+    let start = leaf_hashes.partition_point(|hash| hash.as_ref() < leaf_hash.as_ref());
+    let len = leaf_hashes[start..].partition_point(|hash| hash.as_ref() == leaf_hash.as_ref());
+    start..start + len
+}
+
+impl<T, H> MerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Eq + Hash + Send + Sync,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Builds the leaf index now instead of on the first lookup.
+    ///
+    /// The index keeps a map from each leaf hash to its positions.
+    pub fn with_leaf_index(self) -> MerkleTree<T, H> {
+        self.leaf_positions();
+        self
+    }
+
+    /// Builds a leaf index without extra memory now, unless an index was already built.
+    ///
+    /// When the leaf hashes of this tree are in ascending order, for example because the leaves
+    /// were sorted by their leaf hash, lookups binary-search them in place. Otherwise this builds
+    /// the same map as [`with_leaf_index`](MerkleTree::with_leaf_index).
+    pub fn with_compact_leaf_index(self) -> MerkleTree<T, H> {
+        self.leaf_positions
+            .get_or_init(|| LeafIndex::sorted_or_map(self.leaf_hashes()));
+        self
+    }
+
+    fn leaf_positions(&self) -> &LeafIndex<T> {
+        self.leaf_positions
+            .get_or_init(|| LeafIndex::map(self.leaf_hashes()))
+    }
+
+    fn leaf_hashes(&self) -> &[T] {
+        &self.tree[..self.num_leaves]
+    }
+
+    /// Returns the first position of `leaf`, if it is a leaf of this tree.
+    ///
+    /// `leaf` is the value passed to [`new`](MerkleTree::new), not its hash.
+    pub fn position_of(&self, leaf: &T) -> Option<usize> {
+        let leaf_hash = H::hash_leaf(leaf);
+        self.leaf_positions()
+            .position_of(self.leaf_hashes(), &leaf_hash)
+    }
+
+    /// Returns every position of `leaf` in ascending order, empty if it is not a leaf of this tree.
+    pub fn positions_of(&self, leaf: &T) -> Vec<usize> {
+        let leaf_hash = H::hash_leaf(leaf);
+        self.leaf_positions()
+            .positions_of(self.leaf_hashes(), &leaf_hash)
+    }
+
+    /// Builds the [MerkleProof] for the first position of `leaf`.
+    ///
+    /// Fails if `leaf` is not a leaf of this tree.
+    pub fn build_proof_for_leaf(&self, leaf: &T) -> Result<MerkleProof<T, H>> {
+        let leaf_index = self
+            .position_of(leaf)
+            .ok_or_else(|| anyhow!("the value is not a leaf of this tree"))?;
+        self.build_proof(leaf_index)
+    }
+}
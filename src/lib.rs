@@ -10,25 +10,139 @@
 //! ## Crate Features
 #![doc = document_features::document_features!()]
 
+#[cfg(any(feature = "blake2s_256_hash", test, bench))]
+pub use crate::algos::Blake2s256MerkleTree;
 #[cfg(any(feature = "keccak256_hash", test, bench))]
 pub use crate::algos::Keccak256MerkleTree;
 #[cfg(any(feature = "md5_hash", test, bench))]
 pub use crate::algos::Md5MerkleTree;
-#[cfg(any(feature = "sha256_hash", test, bench))]
-pub use crate::algos::Sha256MerkleTree;
+#[cfg(any(feature = "poseidon_bn254_hash", test, bench))]
+pub use crate::algos::PoseidonBn254MerkleTree;
+#[cfg(any(feature = "sha3_256_hash", test, bench))]
+pub use crate::algos::Sha3_256MerkleTree;
+#[cfg(any(feature = "sha512_256_hash", test, bench))]
+pub use crate::algos::Sha512_256MerkleTree;
+#[cfg(any(feature = "xxh3_128_hash", test, bench))]
+pub use crate::algos::Xxh3_128MerkleTree;
 #[cfg(any(feature = "blake3_hash", test, bench))]
-pub use crate::algos::{blake3_hash_leaf_values, BlakeMerkleTree};
+pub use crate::algos::{blake3_hash_leaf_values, Blake3XofMerkleTree, BlakeMerkleTree};
+#[cfg(any(feature = "blake2b_256_hash", test, bench))]
+pub use crate::algos::{Blake2b256MerkleTree, Blake2bVarMerkleTree};
+#[cfg(any(feature = "sha256_hash", test, bench))]
+pub use crate::algos::{Sha256MerkleTree, TruncatedSha256MerkleTree};
 
+pub use annotated_merkle_tree::{AnnotatedMerkleProof, AnnotatedMerkleTree};
+pub use annotation::Annotation;
+#[cfg(any(feature = "async_build", test))]
+pub use async_builder::{
+    AsyncMerkleTreeBuilder, BuildProgress, CancellationToken, MerkleTreeBuild,
+};
+#[cfg(any(feature = "parallel_hashing", feature = "async_build", test))]
+pub use build_options::BuildOptions;
+pub use compact_merkle_proof::CompactMerkleProof;
+pub use digest::Digest;
+pub use directory_merkle_tree::{
+    DirectoryEntry, DirectoryMerkleTree, DirectoryOptions, DirectoryProof, EntryKind,
+};
+pub use dyn_merkle_proof::DynMerkleProof;
+pub use dyn_merkle_tree::DynMerkleTree;
+pub use hash_algorithm::HashAlgorithm;
+pub use hasher_registry::{DynMerkleTreeHasher, HasherRegistry};
+#[cfg(any(feature = "mmap", test))]
+pub use mapped_merkle_tree::MappedMerkleTree;
 pub use merkle_proof::MerkleProof;
+pub use merkle_proofs::MerkleProofs;
+pub use merkle_sum_tree::{MerkleSumProof, MerkleSumTree};
 pub use merkle_tree::MerkleTree;
-pub use merkle_tree_hasher::MerkleTreeHasher;
+pub use merkle_tree_hasher::{
+    AnnotatedMerkleTreeHasher, CryptographicHasher, DirectoryMerkleTreeHasher, MerkleSumTreeHasher,
+    MerkleTreeHasher, NestedMerkleTreeHasher, NonCryptographic,
+};
+pub use nested_merkle_tree::{NestedChild, NestedMerkleTree, NestedProof};
+pub use persistent_merkle_tree::{MerkleTreeVersion, PersistentMerkleTree, RetentionPolicy};
+pub use pruned_merkle_tree::PrunedMerkleTree;
+pub use serde_hash::{Base64Encoded, HexEncoded};
+pub use sorted_merkle_tree::{NonMembershipProof, SortedMerkleTree};
+pub use stored_merkle_tree::StoredMerkleTree;
+pub use tree_partition::{MerkleSubtree, SubtreeProof, TreePartition};
 
 use crate::utils::{add_1_if_odd, count_tree_nodes, increment_or_wrap_around, is_odd};
 
 mod algos;
+mod annotated_merkle_tree;
+pub mod annotation;
+#[cfg(any(feature = "async_build", test))]
+pub mod async_builder;
+pub mod binary_format;
+#[cfg(any(feature = "parallel_hashing", feature = "async_build", test))]
+mod build_options;
+mod compact_merkle_proof;
+mod digest;
+pub mod directory_merkle_tree;
+mod dyn_merkle_proof;
+mod dyn_merkle_tree;
+mod hash_algorithm;
+mod hasher_registry;
+mod leaf_index;
+#[cfg(any(feature = "mmap", test))]
+pub mod mapped_merkle_tree;
 mod merkle_proof;
+mod merkle_proofs;
+mod merkle_sum_tree;
 mod merkle_tree;
 mod merkle_tree_hasher;
+mod nested_merkle_tree;
+pub mod node_store;
+mod persistent_merkle_tree;
+mod pruned_merkle_tree;
+pub mod serde_hash;
+mod sorted_merkle_tree;
+mod stored_merkle_tree;
+#[cfg(test)]
+mod test_annotated_merkle_tree;
+#[cfg(test)]
+mod test_async_builder;
+#[cfg(test)]
+mod test_binary_format;
+#[cfg(test)]
+mod test_build_options;
+#[cfg(test)]
+mod test_compact_merkle_proof;
+#[cfg(test)]
+mod test_digest;
+#[cfg(test)]
+mod test_directory_merkle_tree;
+#[cfg(test)]
+mod test_dyn_merkle_tree;
+#[cfg(test)]
+mod test_hasher_registry;
+#[cfg(test)]
+mod test_leaf_index;
+#[cfg(test)]
+mod test_mapped_merkle_tree;
 #[cfg(test)]
 mod test_merkle_proof;
+#[cfg(test)]
+mod test_merkle_proofs;
+#[cfg(test)]
+mod test_merkle_sum_tree;
+#[cfg(test)]
+mod test_nested_merkle_tree;
+#[cfg(test)]
+mod test_node_store;
+#[cfg(test)]
+mod test_persistent_merkle_tree;
+#[cfg(test)]
+mod test_pruned_merkle_tree;
+#[cfg(test)]
+mod test_serde_hash;
+#[cfg(test)]
+mod test_sorted_merkle_tree;
+#[cfg(test)]
+mod test_stored_merkle_tree;
+#[cfg(test)]
+mod test_tree_partition;
+#[cfg(test)]
+mod test_utils;
+mod tree_partition;
 mod utils;
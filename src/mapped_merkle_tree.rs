@@ -0,0 +1,256 @@
+//! A fixed on-disk layout of [MerkleTree] which can be memory-mapped and used without decoding.
+//!
+//! ## File format
+//!
+//! A file starts with a header of [MAPPED_HEADER_LEN] bytes, followed by every node of the tree
+//! in the level order of [MerkleTree], leaves first and root last. All integers are little endian.
+//! The header holds:
+//!
+//! - the magic number [MAPPED_TREE_MAGIC] and the format version,
+//! - a reserved byte and the digest length as a u16,
+//! - the algorithm id (see [HashAlgorithm::code](crate::HashAlgorithm::code)) and the number of leaves as u64s,
+//! - a CRC-32 of the nodes,
+//! - a CRC-32 of the previous 28 bytes.
+//!
+//! [`MappedMerkleTree::open`] checks the header and the file length only, so opening is
+//! independent of the size of the tree. [`verify_checksum`](MappedMerkleTree::verify_checksum)
+//! and [`verify_integrity`](MappedMerkleTree::verify_integrity) read every node.
+
+use std::fs::File;
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::Path;
+
+use anyhow::{anyhow, ensure, Result};
+use memmap2::Mmap;
+
+use crate::binary_format::{ensure_algorithm, hasher_algorithm, typed_hash, FORMAT_VERSION};
+use crate::utils::{count_tree_levels, crc32, crc32_update, for_each_parent};
+use crate::MerkleTreeHasher;
+use crate::{add_1_if_odd, count_tree_nodes, is_odd, HashAlgorithm, MerkleProof, MerkleTree};
+
+/// Magic number starting a memory-mappable [MerkleTree] file.
+pub const MAPPED_TREE_MAGIC: [u8; 4] = *b"NDMM";
+
+/// Length in bytes of the header preceding the nodes.
+pub const MAPPED_HEADER_LEN: usize = 32;
+
+impl<T, H> MerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Writes every node of this [MerkleTree] using the layout described in
+    /// [mapped_merkle_tree](crate::mapped_merkle_tree), to be opened by [MappedMerkleTree].
+    pub fn write_mapped<W: Write>(&self, mut writer: W) -> Result<()> {
+        let algorithm = hasher_algorithm::<T, H>()?;
+        let digest_len =
+            u16::try_from(self.root().as_ref().len()).map_err(|_| anyhow!("digest is too long"))?;
+
+        let checksum = self
+            .tree
+            .iter()
+            .fold(0, |crc, node| crc32_update(crc, node.as_ref()));
+
+        let mut header = [0; MAPPED_HEADER_LEN];
+        header[..4].copy_from_slice(&MAPPED_TREE_MAGIC);
+        header[4] = FORMAT_VERSION;
+        header[6..8].copy_from_slice(&digest_len.to_le_bytes());
+        header[8..16].copy_from_slice(&algorithm.code().to_le_bytes());
+        header[16..24].copy_from_slice(&(self.num_leaves as u64).to_le_bytes());
+        header[24..28].copy_from_slice(&checksum.to_le_bytes());
+        let header_checksum = crc32(&header[..28]);
+        header[28..32].copy_from_slice(&header_checksum.to_le_bytes());
+
+        writer.write_all(&header)?;
+        for node in &self.tree {
+            writer.write_all(node.as_ref())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// A read-only Merkle Tree borrowing its nodes from a memory-mapped file.
+///
+/// The file is written by [`MerkleTree::write_mapped`]. Nodes are read in place, so
+/// [`root`](MappedMerkleTree::root) and [`build_proof`](MappedMerkleTree::build_proof) only
+/// touch the pages holding the nodes they need instead of loading the whole tree.
+#[derive(Debug)]
+pub struct MappedMerkleTree<T: Copy, H: MerkleTreeHasher<T>> {
+    mmap: Mmap,
+    num_leaves: usize,
+    digest_len: usize,
+    _dummy: PhantomData<(T, H)>,
+}
+
+impl<T, H> MappedMerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq + for<'a> TryFrom<&'a [u8]>,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Maps the file at `path`, checking its header and length against `H` and `T`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other process, while the
+    /// returned tree is alive. The nodes are read from the mapping directly, so a change would
+    /// be seen through shared references and a truncation would crash this process.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<MappedMerkleTree<T, H>> {
+        let file = File::open(path)?;
+        // Safety: the caller keeps the file unchanged while mapped.
+        let mmap = unsafe { Mmap::map(&file)? };
+        ensure!(
+            mmap.len() >= MAPPED_HEADER_LEN,
+            "the file is too short for a header"
+        );
+
+        let header = &mmap[..MAPPED_HEADER_LEN];
+        let read_u64 =
+            |start: usize| u64::from_le_bytes(header[start..start + 8].try_into().unwrap());
+        ensure!(
+            header[..4] == MAPPED_TREE_MAGIC,
+            "not a memory-mappable Merkle Tree"
+        );
+        ensure!(
+            header[4] == FORMAT_VERSION,
+            "unsupported format version {}",
+            header[4]
+        );
+        ensure!(
+            u32::from_le_bytes(header[28..32].try_into().unwrap()) == crc32(&header[..28]),
+            "the header checksum does not match"
+        );
+        ensure_algorithm(
+            HashAlgorithm::from_code(read_u64(8)),
+            hasher_algorithm::<T, H>()?,
+        )?;
+
+        let digest_len = u16::from_le_bytes([header[6], header[7]]) as usize;
+        let num_leaves = usize::try_from(read_u64(16))
+            .map_err(|_| anyhow!("too many leaves for this platform"))?;
+        ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        let nodes_len = count_tree_nodes(num_leaves)
+            .checked_mul(digest_len)
+            .ok_or_else(|| anyhow!("too many nodes for this platform"))?;
+        ensure!(
+            mmap.len() - MAPPED_HEADER_LEN == nodes_len,
+            "expected {} bytes of nodes, found {}",
+            nodes_len,
+            mmap.len() - MAPPED_HEADER_LEN
+        );
+
+        let tree = MappedMerkleTree {
+            mmap,
+            num_leaves,
+            digest_len,
+            _dummy: Default::default(),
+        };
+        tree.root()?;
+        Ok(tree)
+    }
+
+    /// Checks the nodes against the checksum in the header, detecting accidental corruption.
+    pub fn verify_checksum(&self) -> Result<()> {
+        let expected = u32::from_le_bytes(self.mmap[24..28].try_into().unwrap());
+        ensure!(
+            crc32(&self.mmap[MAPPED_HEADER_LEN..]) == expected,
+            "the node checksum does not match"
+        );
+        Ok(())
+    }
+
+    /// Rehashes every non-leaf node from the nodes below it, level by level.
+    ///
+    /// Unlike [`verify_checksum`](MappedMerkleTree::verify_checksum) this detects nodes that were
+    /// changed on purpose, so the root can be trusted for the stored leaves.
+    pub fn verify_integrity(&self) -> Result<()> {
+        for_each_parent(
+            self.num_leaves,
+            H::non_leaf_node_starting_prefix()[0],
+            H::wrap_to_value(),
+            |node| {
+                let lhs = self.node(node.lhs())?;
+                let rhs = self.node(node.rhs())?;
+                ensure!(
+                    H::hash_non_leaf_node(&node.prefix, &lhs, &rhs) == self.node(node.parent)?,
+                    "node {} does not match the nodes below it",
+                    node.parent
+                );
+                Ok(())
+            },
+        )
+    }
+
+    /// Builds the [MerkleProof] for the leaf at `leaf_index`, reading one node per level.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<MerkleProof<T, H>> {
+        ensure!(
+            leaf_index < self.num_leaves,
+            "leaf index {} is out of range for {} leaves",
+            leaf_index,
+            self.num_leaves
+        );
+
+        let mut siblings = Vec::with_capacity(count_tree_levels(self.num_leaves));
+        let mut level_start = 0;
+        let mut level_count = self.num_leaves;
+        let mut current_idx = leaf_index;
+
+        while level_count > 1 {
+            let sibling_idx = if is_odd(current_idx) {
+                current_idx - 1
+            } else {
+                (current_idx + 1).min(level_count - 1)
+            };
+            siblings.push(self.node(level_start + sibling_idx)?);
+
+            current_idx /= 2;
+            level_start += level_count;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        Ok(MerkleProof::from_siblings(
+            leaf_index,
+            self.num_leaves,
+            self.node(leaf_index)?,
+            siblings,
+        ))
+    }
+
+    /// Returns the node at `idx` of the level-ordered tree.
+    pub fn node(&self, idx: usize) -> Result<T> {
+        let bytes = self
+            .node_bytes(idx)
+            .ok_or_else(|| anyhow!("node {} is out of range for {} nodes", idx, self.len()))?;
+        typed_hash(bytes)
+    }
+
+    /// Returns the bytes of the node at `idx` of the level-ordered tree, borrowed from the file.
+    pub fn node_bytes(&self, idx: usize) -> Option<&[u8]> {
+        if idx >= self.len() {
+            return None;
+        }
+        let start = MAPPED_HEADER_LEN + idx * self.digest_len;
+        Some(&self.mmap[start..start + self.digest_len])
+    }
+
+    /// Returns the Merkle Tree root.
+    pub fn root(&self) -> Result<T> {
+        self.node(self.len() - 1)
+    }
+
+    /// Returns the number of leaves used to create this Merkle Tree.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the number of nodes, including leaves and root.
+    pub fn len(&self) -> usize {
+        count_tree_nodes(self.num_leaves)
+    }
+
+    /// Always false, as a Merkle Tree has at least one leaf.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
@@ -1,8 +1,10 @@
+use std::convert::Infallible;
 use std::marker::PhantomData;
 
 use serde::{Deserialize, Serialize};
 
-use crate::merkle_tree_hasher::MerkleTreeHasher;
+use crate::merkle_tree_hasher::{CryptographicHasher, MerkleTreeHasher};
+use crate::utils::fold_proof_path;
 use crate::{increment_or_wrap_around, is_odd};
 
 /// Holds data needed for a Merkle Proof for a given index.
@@ -18,9 +20,39 @@ pub struct MerkleProof<T: Copy + Sized, H: MerkleTreeHasher<T> + Default> {
     pub(crate) _dummy: PhantomData<H>,
 }
 
-impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleTreeHasher<T> + Default> MerkleProof<T, H> {
+impl<T, H> MerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: CryptographicHasher<T> + MerkleTreeHasher<T> + Default,
+{
     /// Determine whether or not the specified leaf is valid for this Merkle Proof.
+    ///
+    /// Only available for a [CryptographicHasher], like [`verify`](MerkleProof::verify).
+    /// A proof without a root is never valid.
     pub fn validate_proof(&self, leaf: &T) -> bool {
+        self.leads_to_root(leaf)
+    }
+
+    /// Determine whether or not `leaf` belongs to the tree with the trusted root `root`.
+    ///
+    /// Unlike [`validate_proof`](MerkleProof::validate_proof), the root inside this proof is not trusted.
+    /// Only available for a [CryptographicHasher], since any other hasher lets an attacker forge proofs.
+    pub fn verify(&self, leaf: &T, root: &T) -> bool {
+        self.sibling_hashes.last() == Some(root) && self.leads_to_root(leaf)
+    }
+}
+
+impl<T, H> MerkleProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: MerkleTreeHasher<T> + Default,
+{
+    /// Hashes from `leaf` up through the result hashes, checking they lead to the root of this proof.
+    pub(crate) fn leads_to_root(&self, leaf: &T) -> bool {
+        let (root, siblings_wo_leaf) = match self.sibling_hashes.split_last() {
+            Some(split) => split,
+            None => return false,
+        };
         let mut hash = <H as MerkleTreeHasher<T>>::hash_leaf(leaf);
         if self.leaf_hash != hash {
             return false;
@@ -31,7 +63,6 @@ impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleTreeHasher<T> + Default> Merkle
         let wrap_to_value = <H as MerkleTreeHasher<T>>::wrap_to_value();
         let mut interior_node_level_prefix =
             <H as MerkleTreeHasher<T>>::non_leaf_node_starting_prefix();
-        let siblings_wo_leaf = &self.sibling_hashes[..&self.sibling_hashes.len() - 1];
 
         for (idx, sibling_hash) in siblings_wo_leaf.iter().enumerate() {
             let mut incremented = false;
@@ -60,9 +91,7 @@ impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleTreeHasher<T> + Default> Merkle
                     increment_or_wrap_around(interior_node_level_prefix[0], wrap_to_value);
             }
 
-            let result = self.result_hashes.as_slice()[idx];
-
-            if result != hash {
+            if self.result_hashes.get(idx) != Some(&hash) {
                 return false;
             }
 
@@ -77,8 +106,66 @@ impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleTreeHasher<T> + Default> Merkle
             num_leaves_for_level /= 2;
         }
 
-        let root = self.sibling_hashes[self.sibling_hashes.len() - 1];
-        hash == root
+        hash == *root
+    }
+}
+
+impl<T: Copy, H: MerkleTreeHasher<T> + Default> MerkleProof<T, H> {
+    /// Returns the index of the proven leaf.
+    pub fn leaf_index(&self) -> usize {
+        self.leaf_index
+    }
+
+    /// Returns the number of leaves of the tree this proof was built from.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the root of the tree this proof was built from.
+    ///
+    /// Panics for a proof without a root, which [`validate_proof`](MerkleProof::validate_proof) refuses.
+    pub fn root(&self) -> T {
+        self.sibling_hashes[self.sibling_hashes.len() - 1]
+    }
+
+    /// Rebuilds a [MerkleProof] from the leaf hash and the sibling hashes below the root.
+    ///
+    /// The result hashes and the root are recomputed using the same level prefixes as
+    /// [MerkleTree](crate::MerkleTree).
+    pub(crate) fn from_siblings(
+        leaf_index: usize,
+        num_leaves: usize,
+        leaf_hash: T,
+        siblings: Vec<T>,
+    ) -> MerkleProof<T, H> {
+        let result_hashes = fold_proof_path(
+            leaf_index,
+            num_leaves,
+            &leaf_hash,
+            &siblings,
+            <H as MerkleTreeHasher<T>>::non_leaf_node_starting_prefix()[0],
+            <H as MerkleTreeHasher<T>>::wrap_to_value(),
+            |prefix, lhs, rhs| {
+                Ok::<T, Infallible>(<H as MerkleTreeHasher<T>>::hash_non_leaf_node(
+                    prefix, lhs, rhs,
+                ))
+            },
+        )
+        .unwrap_or_else(|never| match never {});
+        let root = result_hashes.last().copied().unwrap_or(leaf_hash);
+
+        let mut sibling_hashes = siblings;
+        sibling_hashes.push(root);
+
+        MerkleProof {
+            leaf_index,
+            num_leaves,
+            leaf_hash,
+            result_hashes,
+            sibling_hashes,
+            hash_name: H::default().name(),
+            _dummy: Default::default(),
+        }
     }
 }
 
@@ -0,0 +1,146 @@
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+use anyhow::Result;
+#[cfg(any(feature = "parallel_hashing", test))]
+use rayon::prelude::*;
+
+use crate::{add_1_if_odd, is_odd, MerkleProof, MerkleTree, MerkleTreeHasher};
+
+impl<T, H> MerkleTree<T, H>
+where
+    T: Copy,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Returns an iterator building the [MerkleProof] of every leaf, in leaf order.
+    ///
+    /// The level layout is worked out once for all proofs, and each proof is built only when
+    /// the iterator reaches it, so proofs can be written out without holding all of them.
+    pub fn proofs(&self) -> MerkleProofs<'_, T, H> {
+        MerkleProofs {
+            merkle_tree: self,
+            levels: proof_levels(self.num_leaves),
+            hash_name: H::default().name(),
+            range: 0..self.num_leaves,
+        }
+    }
+
+    /// Builds the [MerkleProof] of every leaf, in leaf order.
+    ///
+    /// Equal to calling [`build_proof`](MerkleTree::build_proof) for each leaf.
+    pub fn build_all_proofs(&self) -> Vec<MerkleProof<T, H>> {
+        self.proofs().collect()
+    }
+
+    /// Builds the [MerkleProof] of every leaf in parallel, in leaf order.
+    #[cfg(any(feature = "parallel_hashing", test))]
+    pub fn par_build_all_proofs(&self) -> Vec<MerkleProof<T, H>>
+    where
+        T: Send + Sync,
+        H: Send + Sync,
+    {
+        let proofs = self.proofs();
+        (0..self.num_leaves)
+            .into_par_iter()
+            .map(|leaf_index| proofs.proof_at(leaf_index))
+            .collect()
+    }
+
+    /// Hands the [MerkleProof] of every leaf to `sink`, in leaf order, holding one at a time.
+    ///
+    /// Stops at the first error returned by `sink`.
+    pub fn stream_proofs<F>(&self, sink: F) -> Result<()>
+    where
+        F: FnMut(MerkleProof<T, H>) -> Result<()>,
+    {
+        self.proofs().try_for_each(sink)
+    }
+}
+
+/// The start and number of nodes of every level below the root of a tree of `num_leaves` leaves.
+fn proof_levels(num_leaves: usize) -> Vec<(usize, usize)> {
+    let mut levels = Vec::new();
+    let mut level_start = 0;
+    let mut level_count = num_leaves;
+    while level_count > 1 {
+        levels.push((level_start, level_count));
+        level_start += level_count;
+        level_count = add_1_if_odd(level_count) / 2;
+    }
+    levels
+}
+
+/// An iterator over the [MerkleProof] of every leaf of a [MerkleTree].
+///
+/// Created by [`MerkleTree::proofs`].
+#[derive(Clone, Debug)]
+pub struct MerkleProofs<'a, T: Copy, H: MerkleTreeHasher<T>> {
+    merkle_tree: &'a MerkleTree<T, H>,
+    levels: Vec<(usize, usize)>,
+    hash_name: String,
+    range: Range<usize>,
+}
+
+impl<T: Copy, H: Default + MerkleTreeHasher<T>> MerkleProofs<'_, T, H> {
+    fn proof_at(&self, leaf_index: usize) -> MerkleProof<T, H> {
+        let tree = &self.merkle_tree.tree;
+        let mut sibling_hashes = Vec::with_capacity(self.levels.len() + 1);
+        let mut result_hashes = Vec::with_capacity(self.levels.len());
+        let mut current_idx = leaf_index;
+
+        for &(level_start, level_count) in &self.levels {
+            let sibling_idx = if is_odd(current_idx) {
+                current_idx - 1
+            } else {
+                (current_idx + 1).min(level_count - 1)
+            };
+            sibling_hashes.push(tree[level_start + sibling_idx]);
+
+            current_idx /= 2;
+            result_hashes.push(tree[level_start + level_count + current_idx]);
+        }
+        sibling_hashes.push(tree[tree.len() - 1]);
+
+        MerkleProof {
+            leaf_index,
+            num_leaves: self.merkle_tree.num_leaves,
+            leaf_hash: tree[leaf_index],
+            sibling_hashes,
+            result_hashes,
+            hash_name: self.hash_name.clone(),
+            _dummy: Default::default(),
+        }
+    }
+}
+
+impl<T: Copy, H: Default + MerkleTreeHasher<T>> Iterator for MerkleProofs<'_, T, H> {
+    type Item = MerkleProof<T, H>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range
+            .next()
+            .map(|leaf_index| self.proof_at(leaf_index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range
+            .nth(n)
+            .map(|leaf_index| self.proof_at(leaf_index))
+    }
+}
+
+impl<T: Copy, H: Default + MerkleTreeHasher<T>> DoubleEndedIterator for MerkleProofs<'_, T, H> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range
+            .next_back()
+            .map(|leaf_index| self.proof_at(leaf_index))
+    }
+}
+
+impl<T: Copy, H: Default + MerkleTreeHasher<T>> ExactSizeIterator for MerkleProofs<'_, T, H> {}
+
+impl<T: Copy, H: Default + MerkleTreeHasher<T>> FusedIterator for MerkleProofs<'_, T, H> {}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::annotation::Sum;
+use crate::merkle_tree_hasher::{CryptographicHasher, MerkleSumTreeHasher};
+use crate::{AnnotatedMerkleProof, AnnotatedMerkleTree};
+
+/// A Merkle Tree whose leaves carry a balance and whose nodes commit to the sum of the balances below them.
+///
+/// An [AnnotatedMerkleTree] annotated with [Sum]. The last node of an odd level is hashed with
+/// itself, like in a [MerkleTree](crate::MerkleTree), but its sum is only counted once. The root
+/// sum is the total of all balances, for example the liabilities of a proof-of-reserves.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    transparent,
+    bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>")
+)]
+pub struct MerkleSumTree<T: Copy + Sized, H: MerkleSumTreeHasher<T>> {
+    pub(crate) tree: AnnotatedMerkleTree<T, H, Sum>,
+}
+
+impl<T: AsRef<[u8]> + Copy + PartialEq, H: Default + MerkleSumTreeHasher<T>> MerkleSumTree<T, H> {
+    /// Builds a MerkleSumTree from leaves and their balances.
+    ///
+    /// Fails if there are no leaves or the total of the balances overflows a [u128].
+    pub fn new(leaves: &[(T, u128)]) -> Result<MerkleSumTree<T, H>> {
+        let leaves: Vec<(T, Sum)> = leaves
+            .iter()
+            .map(|(leaf, balance)| (*leaf, Sum(*balance)))
+            .collect();
+        Ok(MerkleSumTree {
+            tree: AnnotatedMerkleTree::new(&leaves)?,
+        })
+    }
+
+    /// Builds the [MerkleSumProof] for the leaf at `leaf_index`.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<MerkleSumProof<T, H>> {
+        Ok(MerkleSumProof {
+            proof: self.tree.build_proof(leaf_index)?,
+        })
+    }
+
+    /// Returns the number of leaves used to create this Merkle Tree.
+    pub fn num_leaves(&self) -> usize {
+        self.tree.num_leaves()
+    }
+
+    /// Returns the Merkle Tree root.
+    pub fn root(&self) -> T {
+        self.tree.root()
+    }
+
+    /// Returns the total of all balances, committed to by the root.
+    pub fn total(&self) -> u128 {
+        self.tree.root_annotation().0
+    }
+}
+
+/// Holds the data needed to prove that a leaf and its balance are included in a [MerkleSumTree].
+///
+/// Created by [`MerkleSumTree.build_proof`](MerkleSumTree::build_proof). Every sibling carries
+/// its sum, so a verifier recomputes the total and the root itself. Sums are unsigned and every
+/// addition is checked, so no node of a valid proof can hold a negative sum or hide a wrapped one.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(
+    transparent,
+    bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>")
+)]
+pub struct MerkleSumProof<T: Copy + Sized, H: MerkleSumTreeHasher<T> + Default> {
+    pub(crate) proof: AnnotatedMerkleProof<T, H, Sum>,
+}
+
+impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleSumTreeHasher<T> + Default> MerkleSumProof<T, H> {
+    /// Determine whether or not the specified leaf and balance are valid for this Merkle Sum Proof.
+    pub fn validate_proof(&self, leaf: &T, balance: u128) -> bool {
+        self.proof.validate_proof(leaf, &Sum(balance))
+    }
+
+    /// Returns the index of the proven leaf.
+    pub fn leaf_index(&self) -> usize {
+        self.proof.leaf_index()
+    }
+
+    /// Returns the number of leaves of the tree this proof was built from.
+    pub fn num_leaves(&self) -> usize {
+        self.proof.num_leaves()
+    }
+
+    /// Returns the balance of the proven leaf.
+    pub fn balance(&self) -> u128 {
+        self.proof.annotation().0
+    }
+
+    /// Returns the root of the tree this proof was built from.
+    pub fn root(&self) -> T {
+        self.proof.root()
+    }
+
+    /// Returns the total of all balances of the tree this proof was built from.
+    pub fn total(&self) -> u128 {
+        self.proof.root_annotation().0
+    }
+}
+
+impl<T, H> MerkleSumProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: CryptographicHasher<T> + MerkleSumTreeHasher<T> + Default,
+{
+    /// Determine whether or not `leaf` and `balance` belong to the tree with the trusted root `root`
+    /// and the published total `total`.
+    ///
+    /// Unlike [`validate_proof`](MerkleSumProof::validate_proof), the root and total inside this proof are not trusted.
+    pub fn verify(&self, leaf: &T, balance: u128, root: &T, total: u128) -> bool {
+        self.total() == total && self.proof.verify(leaf, &Sum(balance), root)
+    }
+}
+
+impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleSumTreeHasher<T> + Default> PartialEq
+    for MerkleSumProof<T, H>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.proof == other.proof
+    }
+}
@@ -1,16 +1,19 @@
+use std::convert::Infallible;
 use std::marker::PhantomData;
 use std::slice::Iter;
+use std::sync::OnceLock;
 
-use anyhow::Result;
+use anyhow::{bail, ensure, Result};
 use len_trait::{Empty, Len};
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[cfg(any(feature = "parallel_hashing"))]
 use rayon::prelude::*;
 
-use crate::{
-    add_1_if_odd, count_tree_nodes, increment_or_wrap_around, is_odd, MerkleProof, MerkleTreeHasher,
-};
+use crate::leaf_index::LeafIndex;
+use crate::utils::build_levels;
+use crate::{add_1_if_odd, count_tree_nodes, is_odd, MerkleProof, MerkleTreeHasher};
 
 #[cfg(any(test))]
 use std::ops::Index;
@@ -18,10 +21,13 @@ use std::ops::Index;
 /// A Merkle Tree implementation which uses levels for non-leaf nodes.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MerkleTree<T: Copy + Sized, H: MerkleTreeHasher<T>> {
-    num_leaves: usize,
+    pub(crate) num_leaves: usize,
     pub(crate) tree: Vec<T>,
-    hash_name: String,
-    _dummy: PhantomData<H>,
+    pub(crate) hash_name: String,
+    pub(crate) _dummy: PhantomData<H>,
+    /// Built on the first leaf lookup, see [`position_of`](MerkleTree::position_of).
+    #[serde(skip, default = "OnceLock::new")]
+    pub(crate) leaf_positions: OnceLock<LeafIndex<T>>,
 }
 
 impl<'a, T: 'a + AsRef<[u8]> + Copy + Send + Sync, H: Default + MerkleTreeHasher<T>>
@@ -44,81 +50,47 @@ impl<'a, T: 'a + AsRef<[u8]> + Copy + Send + Sync, H: Default + MerkleTreeHasher
             tree: Vec::with_capacity(count_tree_nodes(num_leaves)),
             hash_name: H::default().name(),
             _dummy: Default::default(),
+            leaf_positions: OnceLock::new(),
         };
 
         MerkleTree::<T, H>::add_leaves(&mut merkle_tree, leaves);
+        MerkleTree::<T, H>::add_interior_nodes(&mut merkle_tree);
 
-        let wrap_to_value = <H as MerkleTreeHasher<T>>::wrap_to_value();
-
-        // Prefixes are added to thwart Merkle Tree Second Preimage Attacks
-        let mut interior_node_level_prefix: [u8; 1] =
-            <H as MerkleTreeHasher<T>>::non_leaf_node_starting_prefix();
-
-        let mut actual_level_count = num_leaves;
-        let mut actual_level_count_idx = actual_level_count - 1;
-
-        // if actual_level_count is odd we will use the last hash twice to get an even number of hashes.
-        // This is done at each level of the tree other than the last level.
-        let mut logical_level_count = add_1_if_odd(actual_level_count);
-
-        // We need to keep track of the index offset for each level.
-        let mut non_leaf_nodes_calculated = 0;
-
-        while actual_level_count > 1 {
-            let mut count = 0;
-
-            while count < actual_level_count {
-                let lhs_idx = count + non_leaf_nodes_calculated;
-
-                // If this is the last index for a tree level then use the last index for the level.
-                let rhs_idx = if count == actual_level_count_idx {
-                    lhs_idx
-                } else {
-                    lhs_idx + 1
-                };
-
-                // if the number of leaves is odd, thwart an attacker inserting an extra leaf with the same hash as the last leaf.
-                if lhs_idx == rhs_idx {
-                    interior_node_level_prefix[0] =
-                        increment_or_wrap_around(interior_node_level_prefix[0], wrap_to_value);
-                }
-
-                let lhs = &merkle_tree.tree[lhs_idx];
-                let rhs = &merkle_tree.tree[rhs_idx];
-
-                let hash_val = <H as MerkleTreeHasher<T>>::hash_non_leaf_node(
-                    &interior_node_level_prefix,
-                    lhs,
-                    rhs,
-                );
-
-                // Collecting the hash values in a Vec before adding them the tree slows things considerably.
-                // This observation rules out functional programming for now...
-                merkle_tree.tree.push(hash_val);
-
-                // advance index to the next pair of nodes to hash.
-                count += 2;
-            }
-
-            // Merkle Tree Second Preimage attacks are a little harder when hashing with a tree level.
-            interior_node_level_prefix[0] =
-                increment_or_wrap_around(interior_node_level_prefix[0], 1);
-
-            non_leaf_nodes_calculated += actual_level_count;
-
-            // The next level will have half the number of items.
-            // The compiler should automatically optimize this by right shifting bits.
-            actual_level_count = logical_level_count / 2;
+        Ok(merkle_tree)
+    }
 
-            // So we don't add one inside the loop
-            actual_level_count_idx = actual_level_count - 1;
+    /// Builds a MerkleTree from already hashed leaves, recomputing the non-leaf nodes.
+    pub(crate) fn from_leaf_hashes(leaf_hashes: &[T]) -> MerkleTree<T, H> {
+        let num_leaves = leaf_hashes.len();
+        let mut tree = Vec::with_capacity(count_tree_nodes(num_leaves));
+        tree.extend_from_slice(leaf_hashes);
 
-            // use a even number if odd. For example:
-            //   if there are three nodes on the previous level we will have two results: 3/2 + 1 = 2
-            logical_level_count = add_1_if_odd(actual_level_count);
-        }
+        let mut merkle_tree = MerkleTree {
+            num_leaves,
+            tree,
+            hash_name: H::default().name(),
+            _dummy: Default::default(),
+            leaf_positions: OnceLock::new(),
+        };
+        MerkleTree::<T, H>::add_interior_nodes(&mut merkle_tree);
+        merkle_tree
+    }
 
-        Ok(merkle_tree)
+    /// Hashes the non-leaf nodes level by level, appending them after the leaves.
+    pub(crate) fn add_interior_nodes(merkle_tree: &mut MerkleTree<T, H>) {
+        // Prefixes are added to thwart Merkle Tree Second Preimage Attacks
+        build_levels(
+            &mut merkle_tree.tree,
+            merkle_tree.num_leaves,
+            <H as MerkleTreeHasher<T>>::non_leaf_node_starting_prefix()[0],
+            <H as MerkleTreeHasher<T>>::wrap_to_value(),
+            |prefix, lhs, rhs| {
+                Ok::<T, Infallible>(<H as MerkleTreeHasher<T>>::hash_non_leaf_node(
+                    prefix, lhs, rhs,
+                ))
+            },
+        )
+        .unwrap_or_else(|never| match never {})
     }
 
     #[cfg(not(any(feature = "parallel_hashing")))]
@@ -131,14 +103,15 @@ impl<'a, T: 'a + AsRef<[u8]> + Copy + Send + Sync, H: Default + MerkleTreeHasher
     }
 
     // This is not enabled for testing by default.
+    // The leaves are hashed from the slice behind the iterator, straight into the tree.
     #[cfg(any(feature = "parallel_hashing"))]
     fn add_leaves(merkle_tree: &mut MerkleTree<T, H>, leaves: Iter<T>) {
-        leaves
-            .map(|leaf| leaf.clone())
-            .collect::<Vec<T>>()
-            .par_iter()
-            .map(|leaf| <H as MerkleTreeHasher<T>>::hash_leaf(leaf))
-            .collect_into_vec(&mut merkle_tree.tree);
+        merkle_tree.tree.par_extend(
+            leaves
+                .as_slice()
+                .par_iter()
+                .map(<H as MerkleTreeHasher<T>>::hash_leaf),
+        );
     }
 
     pub fn build_proof(&self, leaf_index: usize) -> Result<MerkleProof<T, H>> {
@@ -197,6 +170,49 @@ impl<'a, T: 'a + AsRef<[u8]> + Copy + Send + Sync, H: Default + MerkleTreeHasher
         })
     }
 
+    /// Checks that this [MerkleTree] is consistent, for example after deserializing it.
+    ///
+    /// The number of nodes must match the number of leaves, the hasher name must match `H`
+    /// and every non-leaf node must equal the hash recomputed from its children.
+    pub fn verify_integrity(&self) -> Result<()> {
+        ensure!(self.num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        ensure!(
+            self.tree.len() == count_tree_nodes(self.num_leaves),
+            "{} leaves need {} nodes, found {}",
+            self.num_leaves,
+            count_tree_nodes(self.num_leaves),
+            self.tree.len()
+        );
+        let hash_name = H::default().name();
+        ensure!(
+            self.hash_name == hash_name,
+            "tree was hashed with '{}', expected '{}'",
+            self.hash_name,
+            hash_name
+        );
+
+        let expected = MerkleTree::<T, H>::from_leaf_hashes(&self.tree[..self.num_leaves]);
+        if let Some(idx) = (self.num_leaves..self.tree.len())
+            .find(|idx| self.tree[*idx].as_ref() != expected.tree[*idx].as_ref())
+        {
+            bail!("node {} does not match the hash of its children", idx);
+        }
+        Ok(())
+    }
+
+    /// Deserializes a [MerkleTree] and checks it with [`verify_integrity`](MerkleTree::verify_integrity).
+    ///
+    /// Usable with `#[serde(deserialize_with = "MerkleTree::deserialize_checked")]`.
+    pub fn deserialize_checked<'de, D>(deserializer: D) -> Result<MerkleTree<T, H>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let tree = MerkleTree::<T, H>::deserialize(deserializer)?;
+        tree.verify_integrity().map_err(D::Error::custom)?;
+        Ok(tree)
+    }
+
     /// Returns the number of leaves used to create this Merkle Tree.
     pub fn num_leaves(&self) -> usize {
         self.num_leaves
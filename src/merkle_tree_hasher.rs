@@ -1,5 +1,10 @@
+use crate::HashAlgorithm;
+
 pub(crate) const LEAF_PREFIX: [u8; 1] = [0_u8];
 pub(crate) const NON_LEAF_NODE_STARTING_PREFIX: [u8; 1] = [1_u8];
+pub(crate) const CHILD_ROOT_TAG: &[u8] = b"child-root";
+pub(crate) const CONTENT_CHUNK_TAG: &[u8] = b"content-chunk";
+pub(crate) const DIRECTORY_ENTRY_TAG: &[u8] = b"directory-entry";
 
 /// A Hasher for generating Merkle Tree leaf and node hashes.
 ///
@@ -17,6 +22,14 @@ pub trait MerkleTreeHasher<T: Copy> {
     /// or [MerkleProof](crate::MerkleProof).
     fn name(&self) -> String;
 
+    /// The [HashAlgorithm] of this [MerkleTreeHasher] implementation.
+    ///
+    /// Defaults to looking up [`name()`](MerkleTreeHasher::name). Hashers outside this crate
+    /// return [None] unless they override this method, typically with [HashAlgorithm::Custom].
+    fn algorithm(&self) -> Option<HashAlgorithm> {
+        HashAlgorithm::from_name(&self.name())
+    }
+
     /// Hash a Leaf Node. Prefixing it with [`leaf_prefix()`](MerkleTreeHasher.leaf_prefix).
     fn hash_leaf(value: &T) -> T;
 
@@ -38,3 +51,92 @@ pub trait MerkleTreeHasher<T: Copy> {
         1
     }
 }
+
+/// A [MerkleTreeHasher] which also hashes the annotations of an [AnnotatedMerkleTree](crate::AnnotatedMerkleTree).
+///
+/// The encoded [Annotation](crate::Annotation) is hashed after the hashes it belongs to.
+pub trait AnnotatedMerkleTreeHasher<T: Copy>: MerkleTreeHasher<T> {
+    /// Hash a Leaf Node and its encoded annotation. Prefixing it with [`leaf_prefix()`](MerkleTreeHasher.leaf_prefix).
+    fn hash_annotated_leaf(value: &T, annotation: &[u8]) -> T;
+
+    /// Hash a non-Leaf Node and the encoded annotation combined from its children. Prefixing it with the specified prefix.
+    fn hash_annotated_node(prefix: &[u8; 1], lhs: &T, rhs: &T, annotation: &[u8]) -> T;
+}
+
+/// An [AnnotatedMerkleTreeHasher] used by a [MerkleSumTree](crate::MerkleSumTree).
+///
+/// Balances and sums are hashed as the 16 big-endian bytes of a [Sum](crate::annotation::Sum).
+pub trait MerkleSumTreeHasher<T: Copy>: AnnotatedMerkleTreeHasher<T> {}
+
+impl<T: Copy, H: AnnotatedMerkleTreeHasher<T>> MerkleSumTreeHasher<T> for H {}
+
+/// A [MerkleTreeHasher] which also hashes the child roots committed by a [NestedMerkleTree](crate::NestedMerkleTree).
+///
+/// A child root is hashed after the leaf prefix, a tag and the height of the child, so it never
+/// hashes like a leaf, nor like the root of a child with a different height.
+pub trait NestedMerkleTreeHasher<T: Copy>: MerkleTreeHasher<T> {
+    /// Hash the root of a child whose leaves are `height` layers below it. Prefixing it with [`leaf_prefix()`](MerkleTreeHasher.leaf_prefix).
+    fn hash_child_root(root: &T, height: u8) -> T;
+}
+
+/// A [MerkleTreeHasher] which also hashes the file contents and entries of a [DirectoryMerkleTree](crate::DirectoryMerkleTree).
+///
+/// Both are hashed after their own tag, so a chunk of a file never hashes like a directory entry.
+pub trait DirectoryMerkleTreeHasher<T: Copy>: MerkleTreeHasher<T> {
+    /// Hash a chunk of file contents, used as a leaf of the tree of the file.
+    fn hash_content_chunk(chunk: &[u8]) -> T;
+
+    /// Hash an encoded directory entry and the root of its contents, used as a leaf of the tree of the directory.
+    fn hash_directory_entry(entry: &[u8], root: &T) -> T;
+}
+
+/// Marks a [MerkleTreeHasher] whose `T` hashes are believed to be collision resistant.
+///
+/// Required wherever a [MerkleProof](crate::MerkleProof) is a security boundary,
+/// for example [`MerkleProof::verify`](crate::MerkleProof::verify).
+///
+/// The hashers of this crate only implement it for hashes of at least 16 bytes. A hasher
+/// truncated to a [Digest](crate::Digest) shorter than that, or longer than 64 bytes, is not
+/// marked, since collisions of a short hash can be found by brute force.
+pub trait CryptographicHasher<T> {}
+
+/// The shortest hash, in bytes, which the hashers of this crate take as collision resistant.
+pub(crate) const MIN_CRYPTOGRAPHIC_DIGEST_LEN: usize = 16;
+
+/// Implements [CryptographicHasher] for a hasher over each [Digest](crate::Digest) length from
+/// [MIN_CRYPTOGRAPHIC_DIGEST_LEN] to the given maximum, 32 or 64 bytes.
+#[cfg(any(
+    feature = "sha256_hash",
+    feature = "blake2b_256_hash",
+    feature = "blake3_hash",
+    test
+))]
+macro_rules! impl_cryptographic_digests {
+    ($hasher:ty, 32) => {
+        $crate::merkle_tree_hasher::impl_cryptographic_digests!(@impl $hasher,
+            16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32);
+    };
+    ($hasher:ty, 64) => {
+        $crate::merkle_tree_hasher::impl_cryptographic_digests!($hasher, 32);
+        $crate::merkle_tree_hasher::impl_cryptographic_digests!(@impl $hasher,
+            33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48
+            49 50 51 52 53 54 55 56 57 58 59 60 61 62 63 64);
+    };
+    (@impl $hasher:ty, $($len:literal)*) => {
+        $(impl $crate::merkle_tree_hasher::CryptographicHasher<$crate::Digest<$len>> for $hasher {})*
+    };
+}
+
+#[cfg(any(
+    feature = "sha256_hash",
+    feature = "blake2b_256_hash",
+    feature = "blake3_hash",
+    test
+))]
+pub(crate) use impl_cryptographic_digests;
+
+/// Marks a [MerkleTreeHasher] whose hash is not collision resistant.
+///
+/// Trees built with it detect accidental changes but an attacker can forge leaves, nodes and proofs.
+/// A hasher must never implement both [NonCryptographic] and [CryptographicHasher].
+pub trait NonCryptographic {}
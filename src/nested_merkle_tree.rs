@@ -0,0 +1,225 @@
+use anyhow::{anyhow, ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::merkle_tree_hasher::{CryptographicHasher, NestedMerkleTreeHasher};
+use crate::{MerkleProof, MerkleTree};
+
+/// A child of a [NestedMerkleTree], either a tree of leaves or another layer of trees.
+#[derive(Clone, Debug)]
+pub enum NestedChild<T: Copy, H: NestedMerkleTreeHasher<T>> {
+    /// A [MerkleTree] of leaves, with a height of 1.
+    Tree(MerkleTree<T, H>),
+    /// A [NestedMerkleTree], one layer higher than its own children.
+    Nested(NestedMerkleTree<T, H>),
+}
+
+impl<T, H> NestedChild<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync + PartialEq,
+    H: Default + NestedMerkleTreeHasher<T>,
+{
+    /// The number of layers from the root of this child down to its leaves.
+    pub fn height(&self) -> u8 {
+        match self {
+            NestedChild::Tree(_) => 1,
+            NestedChild::Nested(tree) => tree.height,
+        }
+    }
+
+    /// Returns the root of this child.
+    pub fn root(&self) -> T {
+        match self {
+            NestedChild::Tree(tree) => tree.root(),
+            NestedChild::Nested(tree) => tree.root(),
+        }
+    }
+}
+
+/// A tree of trees, committing the roots of its children as the leaves of a parent [MerkleTree].
+///
+/// Every child has the same height, for example tenants holding buckets holding objects is a
+/// NestedMerkleTree of NestedMerkleTrees of [MerkleTree]s. A child root is committed using
+/// [`NestedMerkleTreeHasher::hash_child_root`] instead of hashing it like a leaf, so the root of
+/// a child can never be proven as a leaf, nor as the root of a child on another layer.
+#[derive(Clone, Debug)]
+pub struct NestedMerkleTree<T: Copy, H: NestedMerkleTreeHasher<T>> {
+    children: Vec<NestedChild<T, H>>,
+    parent: MerkleTree<T, H>,
+    height: u8,
+}
+
+impl<T, H> NestedMerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync + PartialEq,
+    H: Default + NestedMerkleTreeHasher<T>,
+{
+    /// Builds a NestedMerkleTree over trees of leaves.
+    pub fn new(children: Vec<MerkleTree<T, H>>) -> Result<NestedMerkleTree<T, H>> {
+        NestedMerkleTree::from_children(children.into_iter().map(NestedChild::Tree).collect())
+    }
+
+    /// Builds a NestedMerkleTree one layer above other NestedMerkleTrees.
+    pub fn nest(children: Vec<NestedMerkleTree<T, H>>) -> Result<NestedMerkleTree<T, H>> {
+        NestedMerkleTree::from_children(children.into_iter().map(NestedChild::Nested).collect())
+    }
+
+    /// Builds a NestedMerkleTree over `children`, which must all have the same height.
+    pub fn from_children(children: Vec<NestedChild<T, H>>) -> Result<NestedMerkleTree<T, H>> {
+        ensure!(
+            !children.is_empty(),
+            "a Merkle Tree needs at least one leaf"
+        );
+        let child_height = children[0].height();
+        ensure!(
+            children.iter().all(|child| child.height() == child_height),
+            "every child must have a height of {}",
+            child_height
+        );
+        let height = child_height
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("too many layers"))?;
+
+        let leaf_hashes: Vec<T> = children
+            .iter()
+            .map(|child| H::hash_child_root(&child.root(), child_height))
+            .collect();
+        Ok(NestedMerkleTree {
+            children,
+            parent: MerkleTree::from_leaf_hashes(&leaf_hashes),
+            height,
+        })
+    }
+
+    /// Builds the [NestedProof] for the leaf found by following `path` from the top layer down.
+    ///
+    /// `path` holds one index per layer, the last being the index of the leaf in its [MerkleTree].
+    pub fn build_proof(&self, path: &[usize]) -> Result<NestedProof<T, H>> {
+        ensure!(
+            path.len() == self.height as usize,
+            "expected a path of {} indices, found {}",
+            self.height,
+            path.len()
+        );
+
+        let mut layers = Vec::with_capacity(path.len());
+        let mut tree = self;
+        for (depth, idx) in path[..path.len() - 1].iter().enumerate() {
+            layers.push(build_layer_proof(&tree.parent, *idx)?);
+            match &tree.children[*idx] {
+                NestedChild::Nested(child) => tree = child,
+                NestedChild::Tree(child) => {
+                    layers.push(build_layer_proof(child, path[depth + 1])?);
+                }
+            }
+        }
+        layers.reverse();
+        Ok(NestedProof { layers })
+    }
+
+    /// Returns the child at `idx`.
+    pub fn child(&self, idx: usize) -> Option<&NestedChild<T, H>> {
+        self.children.get(idx)
+    }
+
+    /// Returns the children, in the order of their roots in the parent tree.
+    pub fn children(&self) -> &[NestedChild<T, H>] {
+        &self.children
+    }
+
+    /// Returns the number of layers from the root down to the leaves.
+    pub fn height(&self) -> u8 {
+        self.height
+    }
+
+    /// Returns the root, committing to every child.
+    pub fn root(&self) -> T {
+        self.parent.root()
+    }
+}
+
+/// Builds the [MerkleProof] for the leaf at `leaf_index`, failing if it is out of range.
+fn build_layer_proof<T, H>(tree: &MerkleTree<T, H>, leaf_index: usize) -> Result<MerkleProof<T, H>>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync,
+    H: Default + NestedMerkleTreeHasher<T>,
+{
+    ensure!(
+        leaf_index < tree.num_leaves,
+        "leaf index {} is out of range for {} leaves",
+        leaf_index,
+        tree.num_leaves
+    );
+    tree.build_proof(leaf_index)
+}
+
+/// Proves that a leaf is included in a [NestedMerkleTree], one [MerkleProof] per layer.
+///
+/// Created by [`NestedMerkleTree.build_proof`](NestedMerkleTree::build_proof). The first proof
+/// proves the leaf within its [MerkleTree], each following one proves the root computed by the
+/// previous one as a child of the layer above.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct NestedProof<T: Copy + Sized, H: NestedMerkleTreeHasher<T> + Default> {
+    pub(crate) layers: Vec<MerkleProof<T, H>>,
+}
+
+impl<T, H> NestedProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: Default + NestedMerkleTreeHasher<T>,
+{
+    /// Determine whether or not the specified leaf is valid for this proof.
+    ///
+    /// Every layer is recomputed from the leaf, only the root of the top layer is taken from this proof.
+    pub fn validate_proof(&self, leaf: &T) -> bool {
+        matches!((self.compute_root(leaf), self.root()), (Some(computed), Some(root)) if computed == root)
+    }
+
+    /// Hashes from the leaf up through every layer, ignoring the roots stored in the layers.
+    fn compute_root(&self, leaf: &T) -> Option<T> {
+        let mut leaf_hash = H::hash_leaf(leaf);
+        let mut root = None;
+        for (height, layer) in self.layers.iter().enumerate() {
+            if let Some(child_root) = root {
+                leaf_hash = H::hash_child_root(&child_root, u8::try_from(height).ok()?);
+            }
+            if layer.leaf_hash != leaf_hash || layer.leaf_index >= layer.num_leaves {
+                return None;
+            }
+            let (_, siblings) = layer.sibling_hashes.split_last()?;
+            let computed: MerkleProof<T, H> = MerkleProof::from_siblings(
+                layer.leaf_index,
+                layer.num_leaves,
+                leaf_hash,
+                siblings.to_vec(),
+            );
+            root = Some(computed.root());
+        }
+        root
+    }
+
+    /// Returns the proofs from the leaf layer up to the top layer.
+    pub fn layers(&self) -> &[MerkleProof<T, H>] {
+        &self.layers
+    }
+
+    /// Returns the root of the top layer, or [None] for a proof without layers or without that root.
+    pub fn root(&self) -> Option<T> {
+        self.layers
+            .last()
+            .and_then(|layer| layer.sibling_hashes.last().copied())
+    }
+}
+
+impl<T, H> NestedProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: CryptographicHasher<T> + Default + NestedMerkleTreeHasher<T>,
+{
+    /// Determine whether or not `leaf` belongs to the [NestedMerkleTree] with the trusted root `root`.
+    ///
+    /// Unlike [`validate_proof`](NestedProof::validate_proof), the root inside this proof is not trusted.
+    pub fn verify(&self, leaf: &T, root: &T) -> bool {
+        self.compute_root(leaf).as_ref() == Some(root)
+    }
+}
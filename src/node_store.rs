@@ -0,0 +1,404 @@
+//! Storage for the nodes of a [StoredMerkleTree](crate::StoredMerkleTree).
+//!
+//! A [NodeStore] addresses nodes by level, 0 being the leaves, and by index within the level.
+//! [MemoryNodeStore] keeps every node in memory like [MerkleTree](crate::MerkleTree) does.
+//! [FileNodeStore] appends nodes to a file so trees bigger than memory can be built and queried.
+//!
+//! ## File format
+//!
+//! A [FileNodeStore] file starts with two header slots of [HEADER_SLOT_LEN] bytes, followed by
+//! the nodes in the level order of [MerkleTree](crate::MerkleTree). All integers are little endian.
+//! A slot holds:
+//!
+//! - the magic number [NODE_STORE_MAGIC] and the format version,
+//! - a reserved byte and the digest length as a u16,
+//! - the number of leaves, the number of committed nodes and a sequence number as u64s,
+//! - a CRC-32 of the previous 32 bytes, followed by 4 zero bytes.
+//!
+//! [`commit`](NodeStore::commit) syncs the nodes to disk before writing the slot not holding
+//! the latest header, so a crash leaves at least one valid slot. Opening the file uses the valid
+//! slot with the highest sequence number and drops any node written after its last commit.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, ensure, Result};
+
+use crate::add_1_if_odd;
+use crate::binary_format::{typed_hash, FORMAT_VERSION};
+use crate::utils::crc32;
+
+/// Magic number starting each header slot of a [FileNodeStore].
+pub const NODE_STORE_MAGIC: [u8; 4] = *b"NDMS";
+
+/// Length in bytes of each of the two header slots of a [FileNodeStore].
+pub const HEADER_SLOT_LEN: usize = 40;
+
+/// Length in bytes of the header of a [FileNodeStore], before the first node.
+const HEADER_LEN: u64 = 2 * HEADER_SLOT_LEN as u64;
+
+/// Nodes buffered by a [FileNodeStore] before they are written to the file.
+const PENDING_LIMIT: usize = 1 << 16;
+
+/// Stores the nodes of a Merkle Tree by level and index.
+pub trait NodeStore<T: Copy> {
+    /// Returns the node at `idx` of `level`. Fails if that node was never stored.
+    fn get(&self, level: usize, idx: usize) -> Result<T>;
+
+    /// Stores `node` at `idx` of `level`.
+    fn put(&mut self, level: usize, idx: usize, node: T) -> Result<()>;
+
+    /// Makes every node stored so far durable. Does nothing by default.
+    fn commit(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Keeps every node in memory, one [Vec] per level.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryNodeStore<T> {
+    pub(crate) levels: Vec<Vec<T>>,
+}
+
+impl<T> MemoryNodeStore<T> {
+    /// Creates an empty store.
+    pub fn new() -> MemoryNodeStore<T> {
+        MemoryNodeStore { levels: Vec::new() }
+    }
+}
+
+impl<T: Copy> NodeStore<T> for MemoryNodeStore<T> {
+    fn get(&self, level: usize, idx: usize) -> Result<T> {
+        self.levels
+            .get(level)
+            .and_then(|nodes| nodes.get(idx))
+            .copied()
+            .ok_or_else(|| anyhow!("no node at index {} of level {}", idx, level))
+    }
+
+    /// Replaces a stored node, or appends `node` to its level or to the next level.
+    fn put(&mut self, level: usize, idx: usize, node: T) -> Result<()> {
+        ensure!(
+            level <= self.levels.len(),
+            "level {} is above the next level {}",
+            level,
+            self.levels.len()
+        );
+        if level == self.levels.len() {
+            self.levels.push(Vec::new());
+        }
+        let nodes = &mut self.levels[level];
+        match idx.cmp(&nodes.len()) {
+            std::cmp::Ordering::Less => nodes[idx] = node,
+            std::cmp::Ordering::Equal => nodes.push(node),
+            std::cmp::Ordering::Greater => bail!(
+                "index {} of level {} is after the next index {}",
+                idx,
+                level,
+                nodes.len()
+            ),
+        }
+        Ok(())
+    }
+}
+
+/// Appends nodes to a file in the level order of [MerkleTree](crate::MerkleTree).
+///
+/// Nodes must be put in that order and are never overwritten. Only committed nodes survive
+/// a crash, see the [file format](self#file-format).
+#[derive(Debug)]
+pub struct FileNodeStore<T> {
+    file: File,
+    num_leaves: usize,
+    /// The position of the first node of each level.
+    level_starts: Vec<usize>,
+    /// Zero until the first node is put.
+    digest_len: usize,
+    committed_nodes: usize,
+    written_nodes: usize,
+    pending: Vec<u8>,
+    sequence: u64,
+    _dummy: PhantomData<T>,
+}
+
+impl<T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]>> FileNodeStore<T> {
+    /// Creates the file at `path` for a tree with `num_leaves` leaves, replacing any existing file.
+    pub fn create<P: AsRef<Path>>(path: P, num_leaves: usize) -> Result<FileNodeStore<T>> {
+        ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        let mut store = FileNodeStore {
+            file,
+            num_leaves,
+            level_starts: level_starts(num_leaves),
+            digest_len: 0,
+            committed_nodes: 0,
+            written_nodes: 0,
+            pending: Vec::new(),
+            sequence: 0,
+            _dummy: Default::default(),
+        };
+        store.file.write_all(&[0; HEADER_LEN as usize])?;
+        store.write_header(0)?;
+        Ok(store)
+    }
+
+    /// Opens a file created by [`create`](FileNodeStore::create), dropping nodes put after the last commit.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<FileNodeStore<T>> {
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut header = [0; HEADER_LEN as usize];
+        file.read_exact(&mut header)?;
+        let slot = header
+            .chunks_exact(HEADER_SLOT_LEN)
+            .filter_map(HeaderSlot::decode)
+            .max_by_key(|slot| slot.sequence)
+            .ok_or_else(|| anyhow!("no valid node store header"))?;
+
+        let level_starts = level_starts(slot.num_leaves);
+        ensure!(
+            slot.committed_nodes <= level_starts[level_starts.len() - 1] + 1,
+            "{} committed nodes is more than {} leaves need",
+            slot.committed_nodes,
+            slot.num_leaves
+        );
+        let committed_len = slot
+            .committed_nodes
+            .checked_mul(slot.digest_len)
+            .and_then(|len| u64::try_from(len).ok())
+            .and_then(|len| len.checked_add(HEADER_LEN))
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} committed nodes overflow the file length",
+                    slot.committed_nodes
+                )
+            })?;
+        let file_len = file.metadata()?.len();
+        ensure!(
+            file_len >= committed_len,
+            "the file is {} bytes but its {} committed nodes need {} bytes",
+            file_len,
+            slot.committed_nodes,
+            committed_len
+        );
+        file.set_len(committed_len)?;
+        Ok(FileNodeStore {
+            file,
+            num_leaves: slot.num_leaves,
+            level_starts,
+            digest_len: slot.digest_len,
+            committed_nodes: slot.committed_nodes,
+            written_nodes: slot.committed_nodes,
+            pending: Vec::new(),
+            sequence: slot.sequence,
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Returns the number of leaves of the tree stored in this file.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the number of nodes which survive a crash.
+    pub fn committed_nodes(&self) -> usize {
+        self.committed_nodes
+    }
+
+    /// True once every node of the tree has been committed.
+    pub fn is_complete(&self) -> bool {
+        self.committed_nodes == self.level_starts[self.level_starts.len() - 1] + 1
+    }
+
+    /// The position of the node at `idx` of `level` in the level order.
+    fn position(&self, level: usize, idx: usize) -> Result<usize> {
+        let level_start = *self
+            .level_starts
+            .get(level)
+            .ok_or_else(|| anyhow!("level {} is above the root", level))?;
+        let level_end = self
+            .level_starts
+            .get(level + 1)
+            .copied()
+            .unwrap_or(level_start + 1);
+        ensure!(
+            level_start + idx < level_end,
+            "index {} is out of range for the {} nodes of level {}",
+            idx,
+            level_end - level_start,
+            level
+        );
+        Ok(level_start + idx)
+    }
+
+    fn flush_pending(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let offset = HEADER_LEN + (self.written_nodes * self.digest_len) as u64;
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(&self.pending)?;
+        self.written_nodes += self.pending.len() / self.digest_len;
+        self.pending.clear();
+        Ok(())
+    }
+
+    /// Writes the header slot following the latest one, then syncs it.
+    fn write_header(&mut self, sequence: u64) -> Result<()> {
+        let slot = HeaderSlot {
+            digest_len: self.digest_len,
+            num_leaves: self.num_leaves,
+            committed_nodes: self.written_nodes,
+            sequence,
+        };
+        let slot_offset = (sequence % 2) * HEADER_SLOT_LEN as u64;
+        self.file.seek(SeekFrom::Start(slot_offset))?;
+        self.file.write_all(&slot.encode()?)?;
+        self.file.sync_data()?;
+        self.sequence = sequence;
+        self.committed_nodes = self.written_nodes;
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]> + Copy + for<'a> TryFrom<&'a [u8]>> NodeStore<T> for FileNodeStore<T> {
+    fn get(&self, level: usize, idx: usize) -> Result<T> {
+        let position = self.position(level, idx)?;
+        let pending_nodes = self.pending.len() / self.digest_len.max(1);
+        ensure!(
+            position < self.written_nodes + pending_nodes,
+            "no node at index {} of level {}",
+            idx,
+            level
+        );
+        if position >= self.written_nodes {
+            let start = (position - self.written_nodes) * self.digest_len;
+            return typed_hash(&self.pending[start..start + self.digest_len]);
+        }
+
+        let mut node = vec![0; self.digest_len];
+        read_exact_at(
+            &self.file,
+            &mut node,
+            HEADER_LEN + (position * self.digest_len) as u64,
+        )?;
+        typed_hash(&node)
+    }
+
+    /// Appends `node`, failing unless it is the next node in level order.
+    fn put(&mut self, level: usize, idx: usize, node: T) -> Result<()> {
+        let position = self.position(level, idx)?;
+        let node = node.as_ref();
+        if self.digest_len == 0 {
+            self.digest_len = node.len();
+        }
+        ensure!(
+            node.len() == self.digest_len,
+            "expected a {} byte node, found {} bytes",
+            self.digest_len,
+            node.len()
+        );
+        let next_position = self.written_nodes + self.pending.len() / self.digest_len;
+        ensure!(
+            position == next_position,
+            "the store is append-only, expected node {} in level order, found node {}",
+            next_position,
+            position
+        );
+
+        self.pending.extend_from_slice(node);
+        if self.pending.len() >= PENDING_LIMIT * self.digest_len {
+            self.flush_pending()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the pending nodes and syncs them before recording them in the header.
+    fn commit(&mut self) -> Result<()> {
+        self.flush_pending()?;
+        self.file.sync_data()?;
+        self.write_header(self.sequence + 1)
+    }
+}
+
+/// Fills `buf` from `offset` without moving the cursor, so readers sharing `&File` do not race.
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> std::io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+/// Fills `buf` from `offset`. `seek_read` moves the cursor, but every other access seeks first.
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> std::io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset)? {
+            0 => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+            read => {
+                buf = &mut buf[read..];
+                offset += read as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The position of the first node of each level of a tree with `num_leaves` leaves.
+fn level_starts(num_leaves: usize) -> Vec<usize> {
+    let mut level_starts = vec![0];
+    let mut level_count = num_leaves;
+    while level_count > 1 {
+        level_starts.push(level_starts[level_starts.len() - 1] + level_count);
+        level_count = add_1_if_odd(level_count) / 2;
+    }
+    level_starts
+}
+
+/// The contents of one header slot of a [FileNodeStore].
+struct HeaderSlot {
+    digest_len: usize,
+    num_leaves: usize,
+    committed_nodes: usize,
+    sequence: u64,
+}
+
+impl HeaderSlot {
+    fn encode(&self) -> Result<[u8; HEADER_SLOT_LEN]> {
+        let digest_len =
+            u16::try_from(self.digest_len).map_err(|_| anyhow!("digest is too long"))?;
+        let mut slot = [0; HEADER_SLOT_LEN];
+        slot[..4].copy_from_slice(&NODE_STORE_MAGIC);
+        slot[4] = FORMAT_VERSION;
+        slot[6..8].copy_from_slice(&digest_len.to_le_bytes());
+        slot[8..16].copy_from_slice(&(self.num_leaves as u64).to_le_bytes());
+        slot[16..24].copy_from_slice(&(self.committed_nodes as u64).to_le_bytes());
+        slot[24..32].copy_from_slice(&self.sequence.to_le_bytes());
+        let checksum = crc32(&slot[..32]);
+        slot[32..36].copy_from_slice(&checksum.to_le_bytes());
+        Ok(slot)
+    }
+
+    /// Returns [None] for a slot which was never written, torn or corrupted.
+    fn decode(slot: &[u8]) -> Option<HeaderSlot> {
+        let read_u64 =
+            |start: usize| u64::from_le_bytes(slot[start..start + 8].try_into().unwrap());
+        let checksum = u32::from_le_bytes(slot[32..36].try_into().unwrap());
+        if slot[..4] != NODE_STORE_MAGIC
+            || slot[4] != FORMAT_VERSION
+            || checksum != crc32(&slot[..32])
+        {
+            return None;
+        }
+        Some(HeaderSlot {
+            digest_len: u16::from_le_bytes([slot[6], slot[7]]) as usize,
+            num_leaves: usize::try_from(read_u64(8)).ok()?,
+            committed_nodes: usize::try_from(read_u64(16)).ok()?,
+            sequence: read_u64(24),
+        })
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Result};
+
+use crate::utils::{build_levels, next_level_prefix, node_prefix};
+use crate::{add_1_if_odd, MerkleProof, MerkleTreeHasher};
+
+/// Decides which versions a [PersistentMerkleTree] keeps after an update.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RetentionPolicy {
+    /// Keep every version.
+    KeepAll,
+    /// Keep the latest `n` versions, at least one.
+    KeepLast(usize),
+}
+
+/// A node shared by every version it is unchanged in.
+#[derive(Debug)]
+pub(crate) struct PersistentNode<T> {
+    pub(crate) hash: T,
+    /// Missing for leaves. Both children are the same node for the last node of an odd level.
+    pub(crate) children: Option<Children<T>>,
+}
+
+/// The left-hand and right-hand child of a [PersistentNode].
+type Children<T> = (Arc<PersistentNode<T>>, Arc<PersistentNode<T>>);
+
+/// The shape of a tree with a given number of leaves, shared by all its versions.
+#[derive(Debug)]
+pub(crate) struct TreeShape {
+    /// The number of nodes on each level, from the leaves up to the root.
+    level_counts: Vec<usize>,
+    /// The prefix used to hash the nodes of each level into their parents.
+    level_prefixes: Vec<u8>,
+    wrap_to_value: u8,
+}
+
+impl TreeShape {
+    fn new(num_leaves: usize, starting_prefix: u8, wrap_to_value: u8) -> TreeShape {
+        let mut level_counts = vec![num_leaves];
+        let mut level_prefixes = Vec::new();
+        let mut level_prefix = starting_prefix;
+        let mut level_count = num_leaves;
+        while level_count > 1 {
+            level_prefixes.push(level_prefix);
+            level_prefix = next_level_prefix(level_prefix, level_count, wrap_to_value);
+            level_count = add_1_if_odd(level_count) / 2;
+            level_counts.push(level_count);
+        }
+        TreeShape {
+            level_counts,
+            level_prefixes,
+            wrap_to_value,
+        }
+    }
+
+    /// The prefix used to hash the node at `idx` of `level` with its sibling.
+    fn prefix(&self, level: usize, idx: usize) -> [u8; 1] {
+        [node_prefix(
+            self.level_prefixes[level],
+            self.level_counts[level],
+            idx,
+            self.wrap_to_value,
+        )]
+    }
+
+    fn num_leaves(&self) -> usize {
+        self.level_counts[0]
+    }
+
+    fn root_level(&self) -> usize {
+        self.level_counts.len() - 1
+    }
+}
+
+/// One immutable version of a [PersistentMerkleTree].
+///
+/// Cloning a version is cheap, and a clone stays usable after the tree has dropped the version.
+#[derive(Debug)]
+pub struct MerkleTreeVersion<T: Copy, H: MerkleTreeHasher<T>> {
+    pub(crate) version: u64,
+    pub(crate) root: Arc<PersistentNode<T>>,
+    pub(crate) shape: Arc<TreeShape>,
+    pub(crate) _dummy: PhantomData<H>,
+}
+
+impl<T: Copy, H: MerkleTreeHasher<T>> Clone for MerkleTreeVersion<T, H> {
+    fn clone(&self) -> Self {
+        MerkleTreeVersion {
+            version: self.version,
+            root: self.root.clone(),
+            shape: self.shape.clone(),
+            _dummy: Default::default(),
+        }
+    }
+}
+
+impl<T: Copy, H: Default + MerkleTreeHasher<T>> MerkleTreeVersion<T, H> {
+    /// Returns the number of this version, starting at 0 for the tree passed to [`PersistentMerkleTree::new`].
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the Merkle Tree root of this version.
+    pub fn root(&self) -> T {
+        self.root.hash
+    }
+
+    /// Returns the number of leaves of this version.
+    pub fn num_leaves(&self) -> usize {
+        self.shape.num_leaves()
+    }
+
+    /// Builds the [MerkleProof] for the leaf at `leaf_index` of this version.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<MerkleProof<T, H>> {
+        ensure!(
+            leaf_index < self.num_leaves(),
+            "leaf index {} is out of range for {} leaves",
+            leaf_index,
+            self.num_leaves()
+        );
+
+        let mut siblings = Vec::with_capacity(self.shape.root_level());
+        let mut node = &self.root;
+        for level in (0..self.shape.root_level()).rev() {
+            let (lhs, rhs) = node
+                .children
+                .as_ref()
+                .expect("only leaves have no children");
+            let (child, sibling) = if (leaf_index >> level) & 1 == 1 {
+                (rhs, lhs)
+            } else {
+                (lhs, rhs)
+            };
+            siblings.push(sibling.hash);
+            node = child;
+        }
+        siblings.reverse();
+
+        Ok(MerkleProof::from_siblings(
+            leaf_index,
+            self.num_leaves(),
+            node.hash,
+            siblings,
+        ))
+    }
+
+    /// Returns a version with the leaf at `leaf_index` replaced, sharing every other node with this one.
+    fn with_leaf(&self, version: u64, leaf_index: usize, leaf: &T) -> MerkleTreeVersion<T, H> {
+        MerkleTreeVersion {
+            version,
+            root: self.replace_leaf(&self.root, self.shape.root_level(), 0, leaf_index, leaf),
+            shape: self.shape.clone(),
+            _dummy: Default::default(),
+        }
+    }
+
+    /// Rebuilds the path from `node`, at `idx` of `level`, down to the leaf at `leaf_index`.
+    fn replace_leaf(
+        &self,
+        node: &Arc<PersistentNode<T>>,
+        level: usize,
+        idx: usize,
+        leaf_index: usize,
+        leaf: &T,
+    ) -> Arc<PersistentNode<T>> {
+        let (lhs, rhs) = match &node.children {
+            None => {
+                return Arc::new(PersistentNode {
+                    hash: H::hash_leaf(leaf),
+                    children: None,
+                })
+            }
+            Some(children) => children,
+        };
+        let child_level = level - 1;
+        let lhs_idx = idx * 2;
+        let child_idx = leaf_index >> child_level;
+        let (lhs, rhs) = if Arc::ptr_eq(lhs, rhs) {
+            let child = self.replace_leaf(lhs, child_level, child_idx, leaf_index, leaf);
+            (child.clone(), child)
+        } else if child_idx == lhs_idx {
+            let child = self.replace_leaf(lhs, child_level, child_idx, leaf_index, leaf);
+            (child, rhs.clone())
+        } else {
+            let child = self.replace_leaf(rhs, child_level, child_idx, leaf_index, leaf);
+            (lhs.clone(), child)
+        };
+        Arc::new(PersistentNode {
+            hash: H::hash_non_leaf_node(
+                &self.shape.prefix(child_level, lhs_idx),
+                &lhs.hash,
+                &rhs.hash,
+            ),
+            children: Some((lhs, rhs)),
+        })
+    }
+}
+
+/// A Merkle Tree keeping several versions, where an update shares every unchanged node with the previous version.
+///
+/// Every version has the same root and proofs as a [MerkleTree](crate::MerkleTree) built from its
+/// leaves. An update rebuilds only the nodes on the path from the changed leaf to the root, so
+/// keeping `n` versions of a tree with `l` leaves costs about `l + n * log2(l)` nodes instead of `n * l`.
+///
+/// The number of leaves is fixed. Versions are dropped according to the [RetentionPolicy].
+#[derive(Debug)]
+pub struct PersistentMerkleTree<T: Copy, H: MerkleTreeHasher<T>> {
+    versions: VecDeque<MerkleTreeVersion<T, H>>,
+    retention: RetentionPolicy,
+}
+
+impl<T: Copy, H: Default + MerkleTreeHasher<T>> PersistentMerkleTree<T, H> {
+    /// Builds version 0 from `leaves`.
+    pub fn new(leaves: &[T], retention: RetentionPolicy) -> Result<PersistentMerkleTree<T, H>> {
+        ensure!(!leaves.is_empty(), "a Merkle Tree needs at least one leaf");
+        ensure!(
+            retention != RetentionPolicy::KeepLast(0),
+            "at least one version must be kept"
+        );
+        let shape = Arc::new(TreeShape::new(
+            leaves.len(),
+            H::non_leaf_node_starting_prefix()[0],
+            H::wrap_to_value(),
+        ));
+
+        let mut nodes: Vec<Arc<PersistentNode<T>>> = leaves
+            .iter()
+            .map(|leaf| {
+                Arc::new(PersistentNode {
+                    hash: H::hash_leaf(leaf),
+                    children: None,
+                })
+            })
+            .collect();
+        build_levels(
+            &mut nodes,
+            leaves.len(),
+            H::non_leaf_node_starting_prefix()[0],
+            H::wrap_to_value(),
+            |prefix, lhs, rhs| {
+                Ok::<_, Infallible>(Arc::new(PersistentNode {
+                    hash: H::hash_non_leaf_node(prefix, &lhs.hash, &rhs.hash),
+                    children: Some((lhs.clone(), rhs.clone())),
+                }))
+            },
+        )
+        .unwrap_or_else(|never| match never {});
+
+        let root = nodes.pop().expect("a tree always has a root");
+        Ok(PersistentMerkleTree {
+            versions: VecDeque::from([MerkleTreeVersion {
+                version: 0,
+                root,
+                shape,
+                _dummy: Default::default(),
+            }]),
+            retention,
+        })
+    }
+
+    /// Creates a new version from the latest one with the leaf at `leaf_index` replaced by `leaf`.
+    ///
+    /// Returns the number of the new version.
+    pub fn update(&mut self, leaf_index: usize, leaf: T) -> Result<u64> {
+        self.update_leaves(&[(leaf_index, leaf)])
+    }
+
+    /// Creates a single new version from the latest one with every `(leaf_index, leaf)` replaced.
+    ///
+    /// Returns the number of the new version. Nothing changes if an index is out of range.
+    pub fn update_leaves(&mut self, leaves: &[(usize, T)]) -> Result<u64> {
+        let latest = self.latest();
+        if let Some((leaf_index, _)) = leaves
+            .iter()
+            .find(|(leaf_index, _)| *leaf_index >= latest.num_leaves())
+        {
+            return Err(anyhow!(
+                "leaf index {} is out of range for {} leaves",
+                leaf_index,
+                latest.num_leaves()
+            ));
+        }
+
+        let version_number = latest.version + 1;
+        let mut version = latest.clone();
+        for (leaf_index, leaf) in leaves {
+            version = version.with_leaf(version_number, *leaf_index, leaf);
+        }
+        version.version = version_number;
+        self.versions.push_back(version);
+        self.apply_retention();
+        Ok(version_number)
+    }
+
+    /// Drops every version newer than `version`, making it the latest again.
+    ///
+    /// The next update reuses the number of the first dropped version.
+    pub fn rollback(&mut self, version: u64) -> Result<()> {
+        ensure!(
+            self.version(version).is_some(),
+            "version {} is not retained",
+            version
+        );
+        while self.latest().version > version {
+            self.versions.pop_back();
+        }
+        Ok(())
+    }
+
+    /// Changes the [RetentionPolicy], dropping the versions it no longer keeps.
+    pub fn set_retention(&mut self, retention: RetentionPolicy) -> Result<()> {
+        ensure!(
+            retention != RetentionPolicy::KeepLast(0),
+            "at least one version must be kept"
+        );
+        self.retention = retention;
+        self.apply_retention();
+        Ok(())
+    }
+
+    /// Drops the oldest versions beyond the [RetentionPolicy]. Nodes only they use are freed.
+    fn apply_retention(&mut self) {
+        if let RetentionPolicy::KeepLast(keep) = self.retention {
+            while self.versions.len() > keep {
+                self.versions.pop_front();
+            }
+        }
+    }
+
+    /// Returns the latest version.
+    pub fn latest(&self) -> &MerkleTreeVersion<T, H> {
+        self.versions.back().expect("at least one version is kept")
+    }
+
+    /// Returns `version`, if it is retained.
+    pub fn version(&self, version: u64) -> Option<&MerkleTreeVersion<T, H>> {
+        let oldest = self.versions.front()?.version;
+        let version = self.versions.get(version.checked_sub(oldest)? as usize)?;
+        Some(version)
+    }
+
+    /// Returns the numbers of the retained versions, oldest first.
+    pub fn versions(&self) -> impl Iterator<Item = u64> + '_ {
+        self.versions.iter().map(|version| version.version)
+    }
+
+    /// Returns the Merkle Tree root of the latest version.
+    pub fn root(&self) -> T {
+        self.latest().root()
+    }
+
+    /// Builds the [MerkleProof] for the leaf at `leaf_index` of the latest version.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<MerkleProof<T, H>> {
+        self.latest().build_proof(leaf_index)
+    }
+
+    /// Returns the number of leaves of every version.
+    pub fn num_leaves(&self) -> usize {
+        self.latest().num_leaves()
+    }
+}
@@ -0,0 +1,185 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::tree_partition::{MerkleSubtree, SubtreeProof, TreePartition};
+use crate::utils::count_tree_levels;
+use crate::{add_1_if_odd, is_odd, MerkleProof, MerkleTree, MerkleTreeHasher};
+
+impl<T, H> MerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Drops every level below `level`, 0 being the leaves, keeping the root and the levels above.
+    ///
+    /// Each node kept on `level` is the root of a subtree of `2^level` leaves, see
+    /// [`PrunedMerkleTree::subtree_range`]. Proofs are rebuilt from those leaves on demand.
+    pub fn prune_below(self, level: usize) -> Result<PrunedMerkleTree<T, H>> {
+        let levels = count_tree_levels(self.num_leaves);
+        ensure!(
+            level <= levels,
+            "cannot prune below level {} of a tree with {} levels above the leaves",
+            level,
+            levels
+        );
+
+        let mut level_start = 0;
+        let mut level_count = self.num_leaves;
+        for _ in 0..level {
+            level_start += level_count;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        let mut tree = self.tree;
+        tree.drain(..level_start);
+        tree.shrink_to_fit();
+        Ok(PrunedMerkleTree {
+            num_leaves: self.num_leaves,
+            cap_level: level,
+            tree,
+            hash_name: self.hash_name,
+            _dummy: Default::default(),
+        })
+    }
+}
+
+/// A [MerkleTree] keeping only its upper levels, also known as a cap tree.
+///
+/// Created by [`MerkleTree::prune_below`]. Holds the nodes from the cap level up to the root,
+/// in the same level order, so memory shrinks by a factor of about `2^cap_level`.
+/// [`build_proof`](PrunedMerkleTree::build_proof) rehashes the leaves of one subtree below the
+/// cap level and checks them against the kept node before building the proof.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct PrunedMerkleTree<T: Copy + Sized, H: MerkleTreeHasher<T>> {
+    pub(crate) num_leaves: usize,
+    pub(crate) cap_level: usize,
+    pub(crate) tree: Vec<T>,
+    pub(crate) hash_name: String,
+    pub(crate) _dummy: PhantomData<H>,
+}
+
+impl<T, H> PrunedMerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Returns the [TreePartition] whose subtrees are below the nodes of the cap level.
+    pub fn partition(&self) -> TreePartition {
+        TreePartition {
+            num_leaves: self.num_leaves,
+            level: self.cap_level,
+        }
+    }
+
+    /// Returns the range of leaf indices below the node at `cap_idx` of the cap level.
+    ///
+    /// The last subtree holds fewer leaves unless the number of leaves is a multiple of `2^cap_level`.
+    pub fn subtree_range(&self, cap_idx: usize) -> Result<Range<usize>> {
+        self.partition().subtree_range(cap_idx)
+    }
+
+    /// Returns the index of the subtree holding the leaf at `leaf_index`.
+    pub fn subtree_of(&self, leaf_index: usize) -> Result<usize> {
+        self.partition().subtree_of(leaf_index)
+    }
+
+    /// Builds the [MerkleProof] for the leaf at `leaf_index` from `subtree_leaves`, the leaves in
+    /// [`subtree_range`](PrunedMerkleTree::subtree_range) of [`subtree_of(leaf_index)`](PrunedMerkleTree::subtree_of).
+    ///
+    /// Fails if the leaves do not hash to the node kept for their subtree.
+    pub fn build_proof(
+        &self,
+        leaf_index: usize,
+        subtree_leaves: &[T],
+    ) -> Result<MerkleProof<T, H>> {
+        let cap_idx = self.subtree_of(leaf_index)?;
+        let subtree: MerkleSubtree<T, H> =
+            self.partition().build_subtree(cap_idx, subtree_leaves)?;
+        ensure!(
+            subtree.root() == self.tree[cap_idx],
+            "the leaves do not match subtree {}",
+            cap_idx
+        );
+        self.stitch_proof(&subtree.build_proof(leaf_index)?)
+    }
+
+    /// Extends a [SubtreeProof] with the siblings kept above the cap level into a [MerkleProof].
+    ///
+    /// Fails if the proof does not lead to the root of this tree.
+    pub fn stitch_proof(&self, proof: &SubtreeProof<T, H>) -> Result<MerkleProof<T, H>> {
+        let mut current_idx = self.subtree_of(proof.leaf_index)?;
+        ensure!(
+            proof.siblings.len() == self.cap_level,
+            "expected {} siblings below the cap level, found {}",
+            self.cap_level,
+            proof.siblings.len()
+        );
+
+        let mut siblings = Vec::with_capacity(count_tree_levels(self.num_leaves));
+        siblings.extend_from_slice(&proof.siblings);
+        let mut level_start = 0;
+        let mut level_count = self.cap_count();
+        while level_count > 1 {
+            let sibling_idx = if is_odd(current_idx) {
+                current_idx - 1
+            } else {
+                (current_idx + 1).min(level_count - 1)
+            };
+            siblings.push(self.tree[level_start + sibling_idx]);
+
+            current_idx /= 2;
+            level_start += level_count;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        let stitched: MerkleProof<T, H> = MerkleProof::from_siblings(
+            proof.leaf_index,
+            self.num_leaves,
+            proof.leaf_hash,
+            siblings,
+        );
+        ensure!(
+            stitched.root() == self.root(),
+            "the proof does not lead to the root"
+        );
+        Ok(stitched)
+    }
+
+    /// Returns the node kept for the subtree at `cap_idx` of the cap level.
+    pub fn subtree_root(&self, cap_idx: usize) -> Option<T> {
+        if cap_idx < self.cap_count() {
+            Some(self.tree[cap_idx])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the level the tree was pruned below, 0 being the leaves.
+    pub fn cap_level(&self) -> usize {
+        self.cap_level
+    }
+
+    /// Returns the number of subtrees, which is the number of nodes on the cap level.
+    pub fn cap_count(&self) -> usize {
+        self.partition().len()
+    }
+
+    /// Returns the number of nodes kept, including the root.
+    pub fn node_count(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns the number of leaves used to create this Merkle Tree.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the Merkle Tree root.
+    pub fn root(&self) -> T {
+        self.tree[self.tree.len() - 1]
+    }
+}
@@ -0,0 +1,397 @@
+//! Opt-in serde representations for hash values.
+//!
+//! By default `serde` writes a hash like `[u8; 32]` as a sequence of 32 numbers.
+//! The adapters in this module write hashes as lowercase hex or base64 strings instead.
+//!
+//! When reading, every adapter accepts a hex string, a base64 string or a sequence of bytes,
+//! so JSON written by the derived implementations still parses. Strings valid as both hex and
+//! base64 are read as hex, which only happens for 2 and 4 byte hashes, so write those as hex.
+//!
+//! The `hex`, `base64`, `hex_seq` and `base64_seq` modules are meant for `#[serde(with = "...")]`
+//! on fields holding a hash or a [Vec] of hashes. [HexEncoded] and [Base64Encoded] wrap a whole
+//! [MerkleTree](crate::MerkleTree), [MerkleProof](crate::MerkleProof) or a single hash.
+
+use std::fmt;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+use rustc_serialize::base64::{FromBase64, ToBase64, STANDARD};
+use rustc_serialize::hex::{FromHex, ToHex};
+use serde::de::{Error, SeqAccess, Unexpected, Visitor};
+use serde::ser::{SerializeSeq, SerializeStruct};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Digest, MerkleProof, MerkleTree, MerkleTreeHasher};
+
+/// How a hash is written as a string.
+pub trait HashEncoding {
+    /// Encode the hash bytes as a string.
+    fn encode(bytes: &[u8]) -> String;
+}
+
+/// Writes hashes as lowercase hex strings.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hex {}
+
+/// Writes hashes as standard, padded base64 strings.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Base64 {}
+
+impl HashEncoding for Hex {
+    fn encode(bytes: &[u8]) -> String {
+        bytes.to_hex()
+    }
+}
+
+impl HashEncoding for Base64 {
+    fn encode(bytes: &[u8]) -> String {
+        bytes.to_base64(STANDARD)
+    }
+}
+
+/// Wraps a value so its hashes are serialized using the [HashEncoding] `E`.
+///
+/// Deserializing accepts hex strings, base64 strings and byte sequences regardless of `E`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Encoded<V, E: HashEncoding> {
+    /// The wrapped value.
+    pub value: V,
+    _encoding: PhantomData<E>,
+}
+
+/// Serializes the hashes of the wrapped value as lowercase hex strings.
+pub type HexEncoded<V> = Encoded<V, Hex>;
+
+/// Serializes the hashes of the wrapped value as base64 strings.
+pub type Base64Encoded<V> = Encoded<V, Base64>;
+
+impl<V, E: HashEncoding> Encoded<V, E> {
+    /// Wrap a value.
+    pub fn new(value: V) -> Self {
+        Encoded {
+            value,
+            _encoding: Default::default(),
+        }
+    }
+
+    /// Unwrap the value.
+    pub fn into_inner(self) -> V {
+        self.value
+    }
+}
+
+/// Serialize a single hash using `E`.
+struct EncodedHash<'a, T, E>(&'a T, PhantomData<E>);
+
+impl<'a, T: AsRef<[u8]>, E: HashEncoding> Serialize for EncodedHash<'a, T, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&E::encode(self.0.as_ref()))
+    }
+}
+
+/// Serialize a slice of hashes using `E`.
+struct EncodedHashes<'a, T, E>(&'a [T], PhantomData<E>);
+
+impl<'a, T: AsRef<[u8]>, E: HashEncoding> Serialize for EncodedHashes<'a, T, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for hash in self.0 {
+            seq.serialize_element(&EncodedHash::<T, E>(hash, PhantomData))?;
+        }
+        seq.end()
+    }
+}
+
+/// Deserialize a single hash from any of the accepted representations.
+struct AnyHash<T>(T);
+
+impl<'de, T: for<'a> TryFrom<&'a [u8]>> Deserialize<'de> for AnyHash<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(HashVisitor(PhantomData))
+            .map(AnyHash)
+    }
+}
+
+struct HashVisitor<T>(PhantomData<T>);
+
+impl<'de, T: for<'a> TryFrom<&'a [u8]>> Visitor<'de> for HashVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a hash as a hex string, a base64 string or a sequence of bytes")
+    }
+
+    fn visit_str<Err: Error>(self, value: &str) -> Result<T, Err> {
+        decode_str(value).ok_or_else(|| Err::invalid_value(Unexpected::Str(value), &self))
+    }
+
+    fn visit_bytes<Err: Error>(self, value: &[u8]) -> Result<T, Err> {
+        T::try_from(value).map_err(|_| Err::invalid_length(value.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        T::try_from(bytes.as_slice()).map_err(|_| A::Error::invalid_length(bytes.len(), &self))
+    }
+}
+
+struct HashesVisitor<T>(PhantomData<T>);
+
+impl<'de, T: for<'a> TryFrom<&'a [u8]>> Visitor<'de> for HashesVisitor<T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of hashes")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+        let mut hashes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(AnyHash(hash)) = seq.next_element::<AnyHash<T>>()? {
+            hashes.push(hash);
+        }
+        Ok(hashes)
+    }
+}
+
+/// Hex is tried first. For most hash lengths a hex string never has the length of a base64
+/// string for the same number of bytes, so at most one of them converts into a `T`. 2 and 4
+/// byte hashes are the exception: both encode to 4 and 8 characters, and a string that is valid
+/// as both is read as hex, so such hashes only round-trip when written as hex or bytes.
+fn decode_str<T: for<'a> TryFrom<&'a [u8]>>(value: &str) -> Option<T> {
+    if let Ok(bytes) = value.from_hex() {
+        if let Ok(hash) = T::try_from(bytes.as_slice()) {
+            return Some(hash);
+        }
+    }
+    let bytes = value.from_base64().ok()?;
+    T::try_from(bytes.as_slice()).ok()
+}
+
+fn serialize_hash<T: AsRef<[u8]>, E: HashEncoding, S: Serializer>(
+    hash: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    EncodedHash::<T, E>(hash, PhantomData).serialize(serializer)
+}
+
+fn serialize_hashes<T: AsRef<[u8]>, E: HashEncoding, S: Serializer>(
+    hashes: &[T],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    EncodedHashes::<T, E>(hashes, PhantomData).serialize(serializer)
+}
+
+/// Deserialize a hash written as a hex string, a base64 string or a sequence of bytes.
+pub fn deserialize_hash<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: for<'a> TryFrom<&'a [u8]>,
+    D: Deserializer<'de>,
+{
+    AnyHash::deserialize(deserializer).map(|hash| hash.0)
+}
+
+/// Deserialize a sequence of hashes each written as a hex string, a base64 string or a sequence of bytes.
+pub fn deserialize_hashes<'de, T, D>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    T: for<'a> TryFrom<&'a [u8]>,
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_seq(HashesVisitor(PhantomData))
+}
+
+/// `#[serde(with = "node_depth_merkle_tree::serde_hash::hex")]` for a single hash.
+pub mod hex {
+    pub use super::deserialize_hash as deserialize;
+    use super::Hex;
+    use serde::Serializer;
+
+    /// Serialize a hash as a lowercase hex string.
+    pub fn serialize<T: AsRef<[u8]>, S: Serializer>(
+        hash: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_hash::<T, Hex, S>(hash, serializer)
+    }
+}
+
+/// `#[serde(with = "node_depth_merkle_tree::serde_hash::base64")]` for a single hash.
+pub mod base64 {
+    pub use super::deserialize_hash as deserialize;
+    use super::Base64;
+    use serde::Serializer;
+
+    /// Serialize a hash as a base64 string.
+    pub fn serialize<T: AsRef<[u8]>, S: Serializer>(
+        hash: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_hash::<T, Base64, S>(hash, serializer)
+    }
+}
+
+/// `#[serde(with = "node_depth_merkle_tree::serde_hash::hex_seq")]` for a [Vec] of hashes.
+pub mod hex_seq {
+    pub use super::deserialize_hashes as deserialize;
+    use super::Hex;
+    use serde::Serializer;
+
+    /// Serialize hashes as a sequence of lowercase hex strings.
+    pub fn serialize<T: AsRef<[u8]>, S: Serializer>(
+        hashes: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_hashes::<T, Hex, S>(hashes, serializer)
+    }
+}
+
+/// `#[serde(with = "node_depth_merkle_tree::serde_hash::base64_seq")]` for a [Vec] of hashes.
+pub mod base64_seq {
+    pub use super::deserialize_hashes as deserialize;
+    use super::Base64;
+    use serde::Serializer;
+
+    /// Serialize hashes as a sequence of base64 strings.
+    pub fn serialize<T: AsRef<[u8]>, S: Serializer>(
+        hashes: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_hashes::<T, Base64, S>(hashes, serializer)
+    }
+}
+
+impl<const N: usize, E: HashEncoding> Serialize for Encoded<[u8; N], E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_hash::<[u8; N], E, S>(&self.value, serializer)
+    }
+}
+
+impl<'de, const N: usize, E: HashEncoding> Deserialize<'de> for Encoded<[u8; N], E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_hash(deserializer).map(Encoded::new)
+    }
+}
+
+impl<const N: usize, E: HashEncoding> Serialize for Encoded<Digest<N>, E> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_hash::<Digest<N>, E, S>(&self.value, serializer)
+    }
+}
+
+impl<'de, const N: usize, E: HashEncoding> Deserialize<'de> for Encoded<Digest<N>, E> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_hash(deserializer).map(Encoded::new)
+    }
+}
+
+// The field names below match the derived implementations of MerkleTree and MerkleProof.
+
+impl<T, H, E> Serialize for Encoded<MerkleTree<T, H>, E>
+where
+    T: AsRef<[u8]> + Copy,
+    H: MerkleTreeHasher<T>,
+    E: HashEncoding,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let tree = &self.value;
+        let mut state = serializer.serialize_struct("MerkleTree", 4)?;
+        state.serialize_field("num_leaves", &tree.num_leaves)?;
+        state.serialize_field("tree", &EncodedHashes::<T, E>(&tree.tree, PhantomData))?;
+        state.serialize_field("hash_name", &tree.hash_name)?;
+        state.serialize_field("_dummy", &PhantomData::<H>)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: for<'a> TryFrom<&'a [u8]>"))]
+struct MerkleTreeRepr<T> {
+    num_leaves: usize,
+    #[serde(deserialize_with = "deserialize_hashes")]
+    tree: Vec<T>,
+    hash_name: String,
+}
+
+impl<'de, T, H, E> Deserialize<'de> for Encoded<MerkleTree<T, H>, E>
+where
+    T: Copy + for<'a> TryFrom<&'a [u8]>,
+    H: MerkleTreeHasher<T>,
+    E: HashEncoding,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MerkleTreeRepr::<T>::deserialize(deserializer)?;
+        Ok(Encoded::new(MerkleTree {
+            num_leaves: repr.num_leaves,
+            tree: repr.tree,
+            hash_name: repr.hash_name,
+            _dummy: Default::default(),
+            leaf_positions: Default::default(),
+        }))
+    }
+}
+
+impl<T, H, E> Serialize for Encoded<MerkleProof<T, H>, E>
+where
+    T: AsRef<[u8]> + Copy,
+    H: MerkleTreeHasher<T> + Default,
+    E: HashEncoding,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let proof = &self.value;
+        let mut state = serializer.serialize_struct("MerkleProof", 7)?;
+        state.serialize_field("leaf_index", &proof.leaf_index)?;
+        state.serialize_field("num_leaves", &proof.num_leaves)?;
+        state.serialize_field(
+            "leaf_hash",
+            &EncodedHash::<T, E>(&proof.leaf_hash, PhantomData),
+        )?;
+        state.serialize_field(
+            "result_hashes",
+            &EncodedHashes::<T, E>(&proof.result_hashes, PhantomData),
+        )?;
+        state.serialize_field(
+            "sibling_hashes",
+            &EncodedHashes::<T, E>(&proof.sibling_hashes, PhantomData),
+        )?;
+        state.serialize_field("hash_name", &proof.hash_name)?;
+        state.serialize_field("_dummy", &PhantomData::<H>)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: for<'a> TryFrom<&'a [u8]>"))]
+struct MerkleProofRepr<T> {
+    leaf_index: usize,
+    num_leaves: usize,
+    #[serde(deserialize_with = "deserialize_hash")]
+    leaf_hash: T,
+    #[serde(deserialize_with = "deserialize_hashes")]
+    result_hashes: Vec<T>,
+    #[serde(deserialize_with = "deserialize_hashes")]
+    sibling_hashes: Vec<T>,
+    hash_name: String,
+}
+
+impl<'de, T, H, E> Deserialize<'de> for Encoded<MerkleProof<T, H>, E>
+where
+    T: Copy + for<'a> TryFrom<&'a [u8]>,
+    H: MerkleTreeHasher<T> + Default,
+    E: HashEncoding,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = MerkleProofRepr::<T>::deserialize(deserializer)?;
+        Ok(Encoded::new(MerkleProof {
+            leaf_index: repr.leaf_index,
+            num_leaves: repr.num_leaves,
+            leaf_hash: repr.leaf_hash,
+            result_hashes: repr.result_hashes,
+            sibling_hashes: repr.sibling_hashes,
+            hash_name: repr.hash_name,
+            _dummy: Default::default(),
+        }))
+    }
+}
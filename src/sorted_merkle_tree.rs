@@ -0,0 +1,204 @@
+use anyhow::{bail, ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::count_tree_levels;
+use crate::{CryptographicHasher, MerkleProof, MerkleTree, MerkleTreeHasher};
+
+/// A [MerkleTree] whose leaves are unique and in ascending byte order.
+///
+/// Besides inclusion proofs, it proves that a value is not one of its leaves with a [NonMembershipProof].
+#[derive(Clone, Debug)]
+pub struct SortedMerkleTree<T: Copy + Sized, H: MerkleTreeHasher<T>> {
+    leaves: Vec<T>,
+    merkle_tree: MerkleTree<T, H>,
+}
+
+impl<T, H> SortedMerkleTree<T, H>
+where
+    T: AsRef<[u8]> + Copy + Send + Sync,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Builds a SortedMerkleTree from `leaves`.
+    ///
+    /// Fails if there are no leaves or a leaf is not greater than the leaf before it.
+    pub fn new(leaves: &[T]) -> Result<SortedMerkleTree<T, H>> {
+        ensure!(!leaves.is_empty(), "a Merkle Tree needs at least one leaf");
+        if let Some(idx) = leaves
+            .windows(2)
+            .position(|pair| pair[0].as_ref() >= pair[1].as_ref())
+        {
+            bail!(
+                "leaf {} is not greater than the leaf before it, the leaves must be sorted and unique",
+                idx + 1
+            );
+        }
+        Ok(SortedMerkleTree {
+            leaves: leaves.to_vec(),
+            merkle_tree: MerkleTree::new(leaves)?,
+        })
+    }
+
+    /// Builds a SortedMerkleTree after sorting `leaves` and removing duplicates.
+    pub fn from_unsorted(leaves: &[T]) -> Result<SortedMerkleTree<T, H>> {
+        let mut leaves = leaves.to_vec();
+        leaves.sort_unstable_by(|lhs, rhs| lhs.as_ref().cmp(rhs.as_ref()));
+        leaves.dedup_by(|lhs, rhs| lhs.as_ref() == rhs.as_ref());
+        SortedMerkleTree::new(&leaves)
+    }
+
+    /// Returns the position of `leaf`, if it is a leaf of this tree.
+    pub fn position_of(&self, leaf: &T) -> Option<usize> {
+        self.leaves
+            .binary_search_by(|probe| probe.as_ref().cmp(leaf.as_ref()))
+            .ok()
+    }
+
+    /// True if `leaf` is a leaf of this tree.
+    pub fn contains(&self, leaf: &T) -> bool {
+        self.position_of(leaf).is_some()
+    }
+
+    /// Builds the [MerkleProof] for the leaf at `leaf_index`.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<MerkleProof<T, H>> {
+        self.merkle_tree.build_proof(leaf_index)
+    }
+
+    /// Builds the [NonMembershipProof] for `value` from the proofs of the leaves either side of it.
+    ///
+    /// Fails if `value` is a leaf of this tree.
+    pub fn build_non_membership_proof(&self, value: &T) -> Result<NonMembershipProof<T, H>> {
+        let upper_idx = match self
+            .leaves
+            .binary_search_by(|probe| probe.as_ref().cmp(value.as_ref()))
+        {
+            Ok(idx) => bail!("the value is leaf {} of this tree", idx),
+            Err(idx) => idx,
+        };
+        let neighbour = |idx: usize| -> Result<(T, MerkleProof<T, H>)> {
+            Ok((self.leaves[idx], self.build_proof(idx)?))
+        };
+
+        Ok(NonMembershipProof {
+            lower: match upper_idx {
+                0 => None,
+                _ => Some(neighbour(upper_idx - 1)?),
+            },
+            upper: match upper_idx < self.leaves.len() {
+                true => Some(neighbour(upper_idx)?),
+                false => None,
+            },
+        })
+    }
+
+    /// Returns the leaves in ascending byte order.
+    pub fn leaves(&self) -> &[T] {
+        &self.leaves
+    }
+
+    /// Returns the underlying [MerkleTree].
+    pub fn merkle_tree(&self) -> &MerkleTree<T, H> {
+        &self.merkle_tree
+    }
+
+    /// Returns the number of leaves used to create this Merkle Tree.
+    pub fn num_leaves(&self) -> usize {
+        self.merkle_tree.num_leaves()
+    }
+
+    /// Returns the Merkle Tree root.
+    pub fn root(&self) -> T {
+        self.merkle_tree.root()
+    }
+}
+
+/// Proves that a value is not a leaf of a [SortedMerkleTree].
+///
+/// Holds the inclusion proofs of the adjacent leaves bracketing the value. The lower leaf is
+/// missing when the value is below every leaf, and the upper leaf when it is above every leaf.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct NonMembershipProof<T: Copy + Sized, H: MerkleTreeHasher<T> + Default> {
+    pub(crate) lower: Option<(T, MerkleProof<T, H>)>,
+    pub(crate) upper: Option<(T, MerkleProof<T, H>)>,
+}
+
+impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleTreeHasher<T> + Default> NonMembershipProof<T, H> {
+    /// Returns the lower adjacent leaf and its proof, missing if the value is below every leaf.
+    pub fn lower(&self) -> Option<&(T, MerkleProof<T, H>)> {
+        self.lower.as_ref()
+    }
+
+    /// Returns the upper adjacent leaf and its proof, missing if the value is above every leaf.
+    pub fn upper(&self) -> Option<&(T, MerkleProof<T, H>)> {
+        self.upper.as_ref()
+    }
+
+    /// Returns the root of the tree this proof was built from, or [None] for a proof without one.
+    pub fn root(&self) -> Option<T> {
+        self.lower
+            .as_ref()
+            .or(self.upper.as_ref())
+            .and_then(|(_, proof)| proof.sibling_hashes.last().copied())
+    }
+}
+
+impl<T: AsRef<[u8]> + Copy + PartialEq, H: MerkleTreeHasher<T> + Default> PartialEq
+    for NonMembershipProof<T, H>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.lower == other.lower && self.upper == other.upper
+    }
+}
+
+impl<T, H> NonMembershipProof<T, H>
+where
+    T: AsRef<[u8]> + Copy + PartialEq,
+    H: CryptographicHasher<T> + MerkleTreeHasher<T> + Default,
+{
+    /// Determine whether or not this proof shows that `value` is not a leaf of a tree of `num_leaves` leaves.
+    ///
+    /// Both leaf proofs must be valid for the same root and built from a tree of `num_leaves`
+    /// leaves, the leaves must be adjacent and `value` must lie strictly between them. A missing
+    /// lower (upper) leaf is only accepted when the other one is the first (last) leaf.
+    pub fn validate_proof(&self, value: &T, num_leaves: usize) -> bool {
+        // The number of leaves decides which leaf is the last one, so it is never taken from the proofs.
+        let fits = |proof: &MerkleProof<T, H>| {
+            proof.num_leaves == num_leaves
+                && proof.sibling_hashes.len() == count_tree_levels(num_leaves) + 1
+        };
+        let value = value.as_ref();
+        match (&self.lower, &self.upper) {
+            (Some((lower, lower_proof)), Some((upper, upper_proof))) => {
+                lower.as_ref() < value
+                    && value < upper.as_ref()
+                    && fits(lower_proof)
+                    && fits(upper_proof)
+                    && lower_proof.leaf_index + 1 == upper_proof.leaf_index
+                    && lower_proof.root() == upper_proof.root()
+                    && lower_proof.validate_proof(lower)
+                    && upper_proof.validate_proof(upper)
+            }
+            (None, Some((upper, upper_proof))) => {
+                value < upper.as_ref()
+                    && fits(upper_proof)
+                    && upper_proof.leaf_index == 0
+                    && upper_proof.validate_proof(upper)
+            }
+            (Some((lower, lower_proof)), None) => {
+                lower.as_ref() < value
+                    && fits(lower_proof)
+                    && lower_proof.leaf_index + 1 == num_leaves
+                    && lower_proof.validate_proof(lower)
+            }
+            (None, None) => false,
+        }
+    }
+
+    /// Determine whether or not this proof shows that `value` is not a leaf of the tree with the
+    /// trusted root `root` and the trusted number of leaves `num_leaves`.
+    ///
+    /// Unlike [`validate_proof`](NonMembershipProof::validate_proof), the root inside this proof is not trusted.
+    pub fn verify(&self, value: &T, root: &T, num_leaves: usize) -> bool {
+        self.validate_proof(value, num_leaves) && self.root().as_ref() == Some(root)
+    }
+}
@@ -0,0 +1,130 @@
+use std::marker::PhantomData;
+
+use anyhow::{ensure, Result};
+
+use crate::node_store::NodeStore;
+use crate::utils::{count_tree_levels, for_each_parent};
+use crate::{add_1_if_odd, is_odd, MerkleProof, MerkleTreeHasher};
+
+/// A Merkle Tree whose nodes live in a [NodeStore] instead of a [Vec].
+///
+/// Builds the same nodes as [MerkleTree](crate::MerkleTree) with the same hasher. Building reads
+/// each level back from the store once, and [`build_proof`](StoredMerkleTree::build_proof) reads
+/// only the leaf and one sibling per level, so a file-backed store can hold trees bigger than memory.
+#[derive(Debug)]
+pub struct StoredMerkleTree<T: Copy, H: MerkleTreeHasher<T>, S: NodeStore<T>> {
+    num_leaves: usize,
+    store: S,
+    _dummy: PhantomData<(T, H)>,
+}
+
+impl<T, H, S> StoredMerkleTree<T, H, S>
+where
+    T: Copy,
+    H: Default + MerkleTreeHasher<T>,
+    S: NodeStore<T>,
+{
+    /// Builds a StoredMerkleTree from `leaves` into `store`, committing it once every node is stored.
+    pub fn new<I>(mut store: S, leaves: I) -> Result<StoredMerkleTree<T, H, S>>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let leaves = leaves.into_iter();
+        let num_leaves = leaves.len();
+        ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        for (idx, leaf) in leaves.enumerate() {
+            store.put(0, idx, H::hash_leaf(&leaf))?;
+        }
+
+        for_each_parent(
+            num_leaves,
+            H::non_leaf_node_starting_prefix()[0],
+            H::wrap_to_value(),
+            |node| {
+                let lhs = store.get(node.level, node.lhs_idx)?;
+                let rhs = store.get(node.level, node.rhs_idx)?;
+                store.put(
+                    node.level + 1,
+                    node.idx(),
+                    H::hash_non_leaf_node(&node.prefix, &lhs, &rhs),
+                )
+            },
+        )?;
+
+        store.commit()?;
+        Ok(StoredMerkleTree {
+            num_leaves,
+            store,
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Uses a `store` already holding every node of a tree with `num_leaves` leaves.
+    ///
+    /// Fails if the store has no root.
+    pub fn from_store(store: S, num_leaves: usize) -> Result<StoredMerkleTree<T, H, S>> {
+        ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        store.get(count_tree_levels(num_leaves), 0)?;
+        Ok(StoredMerkleTree {
+            num_leaves,
+            store,
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Builds the [MerkleProof] for the leaf at `leaf_index`.
+    pub fn build_proof(&self, leaf_index: usize) -> Result<MerkleProof<T, H>> {
+        ensure!(
+            leaf_index < self.num_leaves,
+            "leaf index {} is out of range for {} leaves",
+            leaf_index,
+            self.num_leaves
+        );
+
+        let mut siblings = Vec::with_capacity(count_tree_levels(self.num_leaves));
+        let mut level = 0;
+        let mut level_count = self.num_leaves;
+        let mut current_idx = leaf_index;
+
+        while level_count > 1 {
+            let sibling_idx = if is_odd(current_idx) {
+                current_idx - 1
+            } else {
+                (current_idx + 1).min(level_count - 1)
+            };
+            siblings.push(self.store.get(level, sibling_idx)?);
+
+            current_idx /= 2;
+            level += 1;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        Ok(MerkleProof::from_siblings(
+            leaf_index,
+            self.num_leaves,
+            self.store.get(0, leaf_index)?,
+            siblings,
+        ))
+    }
+
+    /// Returns the Merkle Tree root.
+    pub fn root(&self) -> Result<T> {
+        self.store.get(count_tree_levels(self.num_leaves), 0)
+    }
+
+    /// Returns the number of leaves used to create this Merkle Tree.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+
+    /// Returns the [NodeStore] holding the nodes.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Returns the [NodeStore], consuming this tree.
+    pub fn into_store(self) -> S {
+        self.store
+    }
+}
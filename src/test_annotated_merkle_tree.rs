@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher, Sha256MerkleTreeHasher};
+    use crate::annotation::{Count, KeyRange, Max, Min};
+    use crate::{AnnotatedMerkleProof, AnnotatedMerkleTree, Annotation};
+
+    type BlakeAnnotatedMerkleTree<A> = AnnotatedMerkleTree<[u8; 32], Blake3MerkleTreeHasher, A>;
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+    const TIMESTAMPS: [u64; 9] = [
+        1_700, 1_650, 1_720, 1_690, 1_710, 1_600, 1_730, 1_680, 1_640,
+    ];
+
+    fn annotated_leaves<A, F>(count: usize, annotate: F) -> Vec<([u8; 32], A)>
+    where
+        F: Fn(u64) -> A,
+    {
+        blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count])
+            .into_iter()
+            .zip(TIMESTAMPS.iter().map(|timestamp| annotate(*timestamp)))
+            .collect()
+    }
+
+    #[test]
+    fn annotation_monoid_test() {
+        assert_eq!(Count(5), Count(2).combine(&Count(3)).unwrap());
+        assert!(Count(u64::MAX).combine(&Count(1)).is_err());
+        assert_eq!(Max(7), Max(7).combine(&Max::identity()).unwrap());
+        assert_eq!(Min(7), Min::identity().combine(&Min(7)).unwrap());
+        assert_eq!(
+            KeyRange { min: 3, max: 9 },
+            KeyRange::new(9).combine(&KeyRange::new(3)).unwrap()
+        );
+        assert_eq!(
+            KeyRange::new(4),
+            KeyRange::new(4).combine(&KeyRange::identity()).unwrap()
+        );
+        assert_ne!((Count(1), Max(2)).to_bytes(), (Count(2), Max(1)).to_bytes());
+    }
+
+    #[test]
+    fn root_annotation_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let timestamps = &TIMESTAMPS[..count];
+            let tree: BlakeAnnotatedMerkleTree<(Count, (Max, Min))> =
+                AnnotatedMerkleTree::new(&annotated_leaves(count, |timestamp| {
+                    (Count(1), (Max(timestamp), Min(timestamp)))
+                }))
+                .unwrap();
+            let (total, (latest, earliest)) = tree.root_annotation();
+            assert_eq!(Count(count as u64), *total);
+            assert_eq!(Max(*timestamps.iter().max().unwrap()), *latest);
+            assert_eq!(Min(*timestamps.iter().min().unwrap()), *earliest);
+            assert_eq!(
+                Some(tree.root_annotation()),
+                tree.annotation(tree.tree.len() - 1)
+            );
+        }
+        assert!(BlakeAnnotatedMerkleTree::<Count>::new(&[]).is_err());
+    }
+
+    #[test]
+    fn root_commits_to_annotations_test() {
+        let leaves = annotated_leaves(5, Max);
+        let tree: BlakeAnnotatedMerkleTree<Max> = AnnotatedMerkleTree::new(&leaves).unwrap();
+        let mut changed = leaves.clone();
+        changed[0].1 = Max(0);
+        let changed_tree: BlakeAnnotatedMerkleTree<Max> =
+            AnnotatedMerkleTree::new(&changed).unwrap();
+        assert_eq!(tree.root_annotation(), changed_tree.root_annotation());
+        assert_ne!(tree.root(), changed_tree.root());
+    }
+
+    #[test]
+    fn count_overflow_test() {
+        let mut leaves = annotated_leaves(3, |_| Count(1));
+        leaves[2].1 = Count(u64::MAX);
+        assert!(BlakeAnnotatedMerkleTree::new(&leaves).is_err());
+    }
+
+    #[test]
+    fn proof_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = annotated_leaves(count, KeyRange::new);
+            let tree =
+                AnnotatedMerkleTree::<[u8; 32], Sha256MerkleTreeHasher, KeyRange>::new(&leaves)
+                    .unwrap();
+            for (idx, (leaf, annotation)) in leaves.iter().enumerate() {
+                let proof = tree.build_proof(idx).unwrap();
+                assert!(proof.validate_proof(leaf, annotation));
+                assert!(proof.verify(leaf, annotation, &tree.root()));
+                assert!(proof.root_annotation().contains(annotation.min));
+                assert!(!proof.validate_proof(leaf, &KeyRange::new(0)));
+                assert!(!proof.verify(leaf, annotation, leaf));
+            }
+            assert!(tree.build_proof(count).is_err());
+        }
+    }
+
+    #[test]
+    fn proof_failure_test() {
+        let leaves = annotated_leaves(7, Max);
+        let tree: BlakeAnnotatedMerkleTree<Max> = AnnotatedMerkleTree::new(&leaves).unwrap();
+        let proof = tree.build_proof(2).unwrap();
+        let (leaf, annotation) = &leaves[2];
+
+        // A sibling annotation is bound into the hashes above it.
+        let mut raised = proof.clone();
+        raised.sibling_nodes[1].1 = Max(u64::MAX);
+        raised.root_annotation = Max(u64::MAX);
+        assert!(!raised.validate_proof(leaf, annotation));
+
+        let mut truncated = proof.clone();
+        truncated.sibling_nodes.pop();
+        assert!(!truncated.validate_proof(leaf, annotation));
+
+        // The last leaf of an odd level is its own sibling.
+        let last = tree.build_proof(6).unwrap();
+        assert!(last.validate_proof(&leaves[6].0, &leaves[6].1));
+        let mut swapped = last.clone();
+        swapped.sibling_nodes[0] = (tree.tree[5], tree.annotations[5]);
+        assert!(!swapped.validate_proof(&leaves[6].0, &leaves[6].1));
+    }
+
+    #[test]
+    fn serde_test() {
+        let tree: BlakeAnnotatedMerkleTree<(Count, KeyRange)> =
+            AnnotatedMerkleTree::new(&annotated_leaves(6, |timestamp| {
+                (Count(1), KeyRange::new(timestamp))
+            }))
+            .unwrap();
+        let json = serde_json::to_string(&tree).unwrap();
+        let tree_de: BlakeAnnotatedMerkleTree<(Count, KeyRange)> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(tree.root(), tree_de.root());
+        assert_eq!(tree.root_annotation(), tree_de.root_annotation());
+
+        let proof = tree.build_proof(3).unwrap();
+        let json = serde_json::to_string(&proof).unwrap();
+        let proof_de: AnnotatedMerkleProof<[u8; 32], Blake3MerkleTreeHasher, (Count, KeyRange)> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(proof, proof_de);
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use futures_executor::block_on;
+
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::{
+        count_tree_nodes, AsyncMerkleTreeBuilder, BlakeMerkleTree, BuildProgress, CancellationToken,
+    };
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    fn builder() -> AsyncMerkleTreeBuilder<[u8; 32], Blake3MerkleTreeHasher> {
+        AsyncMerkleTreeBuilder::new()
+    }
+
+    fn many_leaves(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|idx| *blake3::hash(&(idx as u64).to_le_bytes()).as_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn same_as_merkle_tree_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+            let expected = BlakeMerkleTree::new(&leaves).unwrap();
+            let tree = block_on(builder().build(leaves)).unwrap();
+            assert_eq!(expected.tree, tree.tree);
+            assert_eq!(expected.num_leaves(), tree.num_leaves());
+        }
+    }
+
+    #[test]
+    fn own_thread_pool_test() {
+        let thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .unwrap(),
+        );
+        let leaves = many_leaves(1000);
+        let expected = BlakeMerkleTree::new(&leaves).unwrap();
+        let tree = block_on(builder().thread_pool(thread_pool).build(leaves)).unwrap();
+        assert_eq!(expected.root(), tree.root());
+    }
+
+    #[test]
+    fn progress_test() {
+        let reports: Arc<Mutex<Vec<BuildProgress>>> = Default::default();
+        let sink = reports.clone();
+        let leaves = many_leaves(40_000);
+        block_on(
+            builder()
+                .on_progress(move |progress| sink.lock().unwrap().push(progress))
+                .build(leaves),
+        )
+        .unwrap();
+
+        let reports = reports.lock().unwrap();
+        let total_nodes = count_tree_nodes(40_000);
+        assert!(reports
+            .iter()
+            .all(|report| report.total_nodes == total_nodes));
+        assert!(reports
+            .windows(2)
+            .all(|pair| pair[0].hashed_nodes < pair[1].hashed_nodes));
+        // Three chunks of leaves, then one report per chunk of non-leaf nodes.
+        assert_eq!(40_000, reports[2].hashed_nodes);
+        assert!(reports.last().unwrap().is_done());
+    }
+
+    #[test]
+    fn cancelled_test() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let build = builder().cancellation_token(token).build(many_leaves(100));
+        assert!(block_on(build).is_err());
+    }
+
+    #[test]
+    fn cancelled_while_building_test() {
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        let build = builder()
+            .cancellation_token(token)
+            .on_progress(move |progress| {
+                if progress.hashed_nodes > 40_000 {
+                    cancel.cancel();
+                }
+            })
+            .build(many_leaves(40_000));
+        assert!(block_on(build).is_err());
+    }
+
+    #[test]
+    fn dropped_build_test() {
+        let token = CancellationToken::new();
+        drop(
+            builder()
+                .cancellation_token(token.clone())
+                .build(many_leaves(10)),
+        );
+        assert!(token.is_cancelled());
+
+        let token = CancellationToken::new();
+        let build = builder()
+            .cancellation_token(token.clone())
+            .build(many_leaves(10));
+        block_on(build).unwrap();
+        assert!(!token.is_cancelled());
+    }
+
+    #[test]
+    fn no_leaves_test() {
+        assert!(block_on(builder().build(vec![])).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{
+        blake3_hash_leaf_values, Blake3MerkleTreeHasher, BlakeMerkleTree, Sha256MerkleTreeHasher,
+    };
+    use crate::{DynMerkleTree, MerkleProof, Sha256MerkleTree};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    #[test]
+    fn proof_bytes_round_trip_test() {
+        for num_leaves in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..num_leaves]);
+            let tree = BlakeMerkleTree::new(&leaves).unwrap();
+            for (idx, leaf) in leaves.iter().enumerate() {
+                let proof = tree.build_proof(idx).unwrap();
+                let bytes = proof.to_bytes().unwrap();
+                let proof_de = MerkleProof::from_bytes(&bytes).unwrap();
+                assert_eq!(proof, proof_de);
+                assert!(proof_de.validate_proof(leaf));
+            }
+        }
+    }
+
+    #[test]
+    fn proof_bytes_are_compact_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let tree = Sha256MerkleTree::new(&leaves).unwrap();
+        let proof = tree.build_proof(3).unwrap();
+        let bytes = proof.to_bytes().unwrap();
+        // header 7, index and count 2, leaf 32, bitmap 1, 4 siblings and the root 160
+        assert_eq!(bytes.len(), 202);
+    }
+
+    #[test]
+    fn tree_bytes_round_trip_test() {
+        for num_leaves in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..num_leaves]);
+            let tree = Sha256MerkleTree::new(&leaves).unwrap();
+            let bytes = tree.to_bytes().unwrap();
+            assert_eq!(tree, Sha256MerkleTree::from_bytes(&bytes).unwrap());
+        }
+    }
+
+    #[test]
+    fn proof_bytes_strict_length_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let mut bytes = tree.build_proof(4).unwrap().to_bytes().unwrap();
+        assert!(MerkleProof::<[u8; 32], Blake3MerkleTreeHasher>::from_bytes(
+            &bytes[..bytes.len() - 1]
+        )
+        .is_err());
+        bytes.push(0);
+        assert!(MerkleProof::<[u8; 32], Blake3MerkleTreeHasher>::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn tree_bytes_strict_length_test() {
+        let tree = Sha256MerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let mut bytes = tree.to_bytes().unwrap();
+        assert!(Sha256MerkleTree::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        bytes.push(0);
+        assert!(Sha256MerkleTree::from_bytes(&bytes).is_err());
+    }
+
+    #[test]
+    fn proof_bytes_tampered_test() {
+        let tree = Sha256MerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let bytes = tree.build_proof(4).unwrap().to_bytes().unwrap();
+
+        // A flipped bit in the last sibling no longer leads to the root.
+        let mut tampered = bytes.clone();
+        let sibling_idx = tampered.len() - 40;
+        tampered[sibling_idx] ^= 1;
+        assert!(MerkleProof::<[u8; 32], Sha256MerkleTreeHasher>::from_bytes(&tampered).is_err());
+
+        // The direction bitmap must match the leaf index.
+        let mut tampered = bytes.clone();
+        tampered[9 + 32] ^= 1;
+        assert!(MerkleProof::<[u8; 32], Sha256MerkleTreeHasher>::from_bytes(&tampered).is_err());
+
+        let mut tampered = bytes;
+        tampered[0] = b'X';
+        assert!(MerkleProof::<[u8; 32], Sha256MerkleTreeHasher>::from_bytes(&tampered).is_err());
+    }
+
+    #[test]
+    fn bytes_wrong_algorithm_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let bytes = tree.to_bytes().unwrap();
+        assert!(Sha256MerkleTree::from_bytes(&bytes).is_err());
+
+        let bytes = tree.build_proof(0).unwrap().to_bytes().unwrap();
+        assert!(MerkleProof::<[u8; 32], Sha256MerkleTreeHasher>::from_bytes(&bytes).is_err());
+    }
+
+    /// The 7 byte header of `bytes` with its digest length replaced, followed by `count`.
+    fn with_header(bytes: &[u8], digest_len: u8, mut count: u64) -> Vec<u8> {
+        let mut patched = bytes[..7].to_vec();
+        patched[6] = digest_len;
+        while count >= 0x80 {
+            patched.push(count as u8 | 0x80);
+            count >>= 7;
+        }
+        patched.push(count as u8);
+        patched
+    }
+
+    #[test]
+    fn bytes_bad_digest_len_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let bytes = tree.to_bytes().unwrap();
+
+        // A zero digest length must not let a huge leaf count through to an allocation.
+        let empty_leaves = with_header(&bytes, 0, 1 << 40);
+        assert!(DynMerkleTree::from_bytes(&empty_leaves).is_err());
+        assert!(BlakeMerkleTree::from_bytes(&with_header(&bytes, 0, 1 << 56)).is_err());
+
+        // A digest length which does not fit the hash type fails before the count is read.
+        assert!(BlakeMerkleTree::from_bytes(&with_header(&bytes, 1, 1 << 56)).is_err());
+        let proof_bytes = tree.build_proof(0).unwrap().to_bytes().unwrap();
+        assert!(
+            MerkleProof::<[u8; 32], Blake3MerkleTreeHasher>::from_bytes(&with_header(
+                &proof_bytes,
+                16,
+                0
+            ))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn proof_without_root_to_bytes_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let mut proof = tree.build_proof(2).unwrap();
+        proof.sibling_hashes.clear();
+        assert!(proof.to_bytes().is_err());
+    }
+}
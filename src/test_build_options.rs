@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rayon::prelude::*;
+
+    use crate::algos::Blake3MerkleTreeHasher;
+    use crate::{BlakeMerkleTree, BuildOptions, MerkleTree};
+
+    fn many_leaves(count: usize) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|idx| *blake3::hash(&(idx as u64).to_le_bytes()).as_bytes())
+            .collect()
+    }
+
+    fn build(leaves: &[[u8; 32]], options: &BuildOptions) -> BlakeMerkleTree {
+        MerkleTree::<[u8; 32], Blake3MerkleTreeHasher>::new_with_options(leaves, options).unwrap()
+    }
+
+    #[test]
+    fn same_as_merkle_tree_test() {
+        let thread_pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(2)
+                .build()
+                .unwrap(),
+        );
+        let all_options = [
+            BuildOptions::default(),
+            BuildOptions::serial(),
+            BuildOptions {
+                min_chunk_len: 50,
+                ..Default::default()
+            },
+            BuildOptions {
+                min_chunk_len: 0,
+                serial_threshold: 500,
+                ..Default::default()
+            },
+            BuildOptions {
+                thread_pool: Some(thread_pool),
+                min_chunk_len: 7,
+                ..Default::default()
+            },
+        ];
+        for count in [1, 2, 3, 9, 499, 500, 1001] {
+            let leaves = many_leaves(count);
+            let expected = BlakeMerkleTree::new(&leaves).unwrap();
+            for options in &all_options {
+                let tree = build(&leaves, options);
+                assert_eq!(expected.tree, tree.tree);
+                assert_eq!(count, tree.num_leaves());
+            }
+        }
+    }
+
+    #[test]
+    fn is_parallel_for_test() {
+        let options = BuildOptions {
+            serial_threshold: 500,
+            ..Default::default()
+        };
+        assert!(!options.is_parallel_for(499));
+        assert!(options.is_parallel_for(500));
+        assert!(BuildOptions::default().is_parallel_for(1));
+        assert!(!BuildOptions::serial().is_parallel_for(1_000_000));
+    }
+
+    #[test]
+    fn from_chunks_test() {
+        let leaves = many_leaves(1001);
+        let expected = BlakeMerkleTree::new(&leaves).unwrap();
+        let chunks: Vec<&[[u8; 32]]> =
+            vec![&leaves[..1], &leaves[1..1], &leaves[1..600], &leaves[600..]];
+        for options in [BuildOptions::default(), BuildOptions::serial()] {
+            let tree: BlakeMerkleTree = MerkleTree::from_chunks(&chunks, &options).unwrap();
+            assert_eq!(expected.tree, tree.tree);
+            assert_eq!(1001, tree.num_leaves());
+        }
+        assert!(BlakeMerkleTree::from_chunks(&[&leaves[..0]], &BuildOptions::default()).is_err());
+    }
+
+    #[test]
+    fn from_par_iter_test() {
+        let leaves = many_leaves(1001);
+        let expected = BlakeMerkleTree::new(&leaves).unwrap();
+
+        let tree = BlakeMerkleTree::from_par_iter(leaves.par_iter()).unwrap();
+        assert_eq!(expected.tree, tree.tree);
+        let tree = BlakeMerkleTree::from_par_iter(leaves.clone().into_par_iter().with_min_len(64))
+            .unwrap();
+        assert_eq!(expected.tree, tree.tree);
+        assert!(BlakeMerkleTree::from_par_iter(leaves[..0].par_iter()).is_err());
+    }
+
+    #[test]
+    fn from_leaf_bytes_test() {
+        let leaves = many_leaves(1001);
+        let expected = BlakeMerkleTree::new(&leaves).unwrap();
+        let mut bytes: Vec<&[u8]> = leaves.iter().map(|leaf| &leaf[..]).collect();
+        for options in [BuildOptions::default(), BuildOptions::serial()] {
+            let tree = BlakeMerkleTree::from_leaf_bytes(&bytes, &options).unwrap();
+            assert_eq!(expected.tree, tree.tree);
+        }
+
+        bytes[700] = &leaves[700][..31];
+        for options in [BuildOptions::default(), BuildOptions::serial()] {
+            assert!(BlakeMerkleTree::from_leaf_bytes(&bytes, &options).is_err());
+        }
+    }
+
+    #[test]
+    fn no_leaves_test() {
+        let result = MerkleTree::<[u8; 32], Blake3MerkleTreeHasher>::new_with_options(
+            &[],
+            &BuildOptions::default(),
+        );
+        assert!(result.is_err());
+    }
+}
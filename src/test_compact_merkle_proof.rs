@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher, Sha256MerkleTreeHasher};
+    use crate::binary_format::{peek_algorithm, COMPACT_PROOF_MAGIC};
+    use crate::{BlakeMerkleTree, CompactMerkleProof, HashAlgorithm, MerkleProof};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    type BlakeCompactProof = CompactMerkleProof<[u8; 32], Blake3MerkleTreeHasher>;
+
+    fn tree_of(count: usize) -> (Vec<[u8; 32]>, BlakeMerkleTree) {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        (leaves, tree)
+    }
+
+    #[test]
+    fn round_trip_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let (leaves, tree) = tree_of(count);
+            for (idx, leaf) in leaves.iter().enumerate() {
+                let proof = tree.build_proof(idx).unwrap();
+                let compact = BlakeCompactProof::try_from(&proof).unwrap();
+                assert_eq!(idx, compact.leaf_index());
+                assert_eq!(count, compact.num_leaves());
+                assert_eq!(proof.sibling_hashes.len() - 1, compact.siblings().len());
+                assert_eq!(tree.root(), compact.compute_root());
+                compact.check_shape().unwrap();
+                assert!(compact.verify(leaf, &tree.root()));
+
+                assert_eq!(proof, MerkleProof::from(&compact));
+                assert_eq!(proof, MerkleProof::from(compact));
+            }
+        }
+
+        // A proof without its root has nothing to compact.
+        let mut rootless = tree_of(3).1.build_proof(0).unwrap();
+        rootless.sibling_hashes.clear();
+        assert!(BlakeCompactProof::try_from(rootless).is_err());
+    }
+
+    #[test]
+    fn verify_test() {
+        let (leaves, tree) = tree_of(9);
+        let compact = BlakeCompactProof::try_from(tree.build_proof(4).unwrap()).unwrap();
+        assert!(compact.verify(&leaves[4], &tree.root()));
+        assert!(!compact.verify(&leaves[3], &tree.root()));
+        assert!(!compact.verify(&leaves[4], &leaves[0]));
+
+        let mut wrong_sibling = compact.clone();
+        wrong_sibling.siblings[1][0] ^= 1;
+        assert!(!wrong_sibling.verify(&leaves[4], &tree.root()));
+
+        let mut wrong_direction = compact.clone();
+        wrong_direction.directions[0] ^= 1;
+        assert!(!wrong_direction.verify(&leaves[4], &tree.root()));
+
+        // Leaf 12 would be out of range for 9 leaves.
+        let mut out_of_range = compact.clone();
+        out_of_range.directions[0] |= 8;
+        assert!(out_of_range.check_shape().is_err());
+        assert!(!out_of_range.verify(&leaves[4], &tree.root()));
+
+        let mut missing_sibling = compact;
+        missing_sibling.siblings.pop();
+        assert!(missing_sibling.check_shape().is_err());
+        assert!(!missing_sibling.verify(&leaves[4], &tree.root()));
+    }
+
+    #[test]
+    fn smaller_than_merkle_proof_test() {
+        let (_, tree) = tree_of(9);
+        let proof = tree.build_proof(8).unwrap();
+        let compact = BlakeCompactProof::try_from(&proof).unwrap();
+        let proof_json = serde_json::to_string(&proof).unwrap();
+        let compact_json = serde_json::to_string(&compact).unwrap();
+        assert!(compact_json.len() < proof_json.len());
+        assert_eq!(compact, serde_json::from_str(&compact_json).unwrap());
+
+        let bytes = compact.to_bytes().unwrap();
+        assert!(bytes.len() < proof.to_bytes().unwrap().len());
+        assert_eq!(compact, BlakeCompactProof::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn binary_format_test() {
+        let (_, tree) = tree_of(5);
+        let compact = BlakeCompactProof::try_from(tree.build_proof(3).unwrap()).unwrap();
+        let bytes = compact.to_bytes().unwrap();
+        assert_eq!(COMPACT_PROOF_MAGIC, bytes[..4]);
+        // Header of 7 bytes, the number of leaves, the leaf hash, the directions and 3 siblings.
+        assert_eq!(7 + 1 + 32 + 1 + 3 * 32, bytes.len());
+        assert_eq!(HashAlgorithm::Blake3, peek_algorithm(&bytes).unwrap());
+
+        assert!(BlakeCompactProof::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(
+            CompactMerkleProof::<[u8; 32], Sha256MerkleTreeHasher>::from_bytes(&bytes).is_err()
+        );
+
+        let mut wrong_direction = bytes.clone();
+        wrong_direction[7 + 1 + 32] = 0b101;
+        assert!(BlakeCompactProof::from_bytes(&wrong_direction).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{Blake3MerkleTreeHasher, Sha256MerkleTreeHasher};
+    use crate::{
+        Blake2bVarMerkleTree, Blake3XofMerkleTree, Digest, DynMerkleProof, DynMerkleTree,
+        HashAlgorithm, HasherRegistry, HexEncoded, MerkleProof, MerkleTreeHasher,
+        TruncatedSha256MerkleTree,
+    };
+
+    fn digests<const N: usize>(count: u8) -> Vec<Digest<N>> {
+        (0..count).map(|idx| Digest([idx; N])).collect()
+    }
+
+    #[test]
+    fn digest_conversion_test() {
+        let digest = Digest([7_u8; 48]);
+        assert_eq!(48, Digest::<48>::LEN);
+        assert_eq!(digest, Digest::try_from(digest.as_ref()).unwrap());
+        assert!(Digest::<48>::try_from(&[7_u8; 47][..]).is_err());
+        assert_eq!([7_u8; 48], <[u8; 48]>::from(digest));
+    }
+
+    #[test]
+    fn digest_serde_test() {
+        let digest = Digest([3_u8; 64]);
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(digest, serde_json::from_str(&json).unwrap());
+        assert!(serde_json::from_str::<Digest<65>>(&json).is_err());
+
+        // Written like the equivalent byte array.
+        let digest = Digest([3_u8; 32]);
+        assert_eq!(
+            serde_json::to_string(&[3_u8; 32]).unwrap(),
+            serde_json::to_string(&digest).unwrap()
+        );
+
+        let json = serde_json::to_string(&HexEncoded::new(digest)).unwrap();
+        assert_eq!(format!("\"{}\"", "03".repeat(32)), json);
+        let encoded: HexEncoded<Digest<32>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(digest, encoded.into_inner());
+    }
+
+    #[test]
+    fn digest_hasher_names_test() {
+        assert_eq!(
+            "SHA-256/160",
+            MerkleTreeHasher::<Digest<20>>::name(&Sha256MerkleTreeHasher::default())
+        );
+        assert_eq!(
+            "SHA-256",
+            MerkleTreeHasher::<Digest<32>>::name(&Sha256MerkleTreeHasher::default())
+        );
+        assert_eq!(
+            "Blake3/512",
+            MerkleTreeHasher::<Digest<64>>::name(&Blake3MerkleTreeHasher::default())
+        );
+    }
+
+    #[test]
+    fn digest_proof_test() {
+        let leaves = digests::<64>(7);
+        let tree = Blake3XofMerkleTree::<64>::new(&leaves).unwrap();
+        for (idx, leaf) in leaves.iter().enumerate() {
+            let proof = tree.build_proof(idx).unwrap();
+            assert!(proof.verify(leaf, &tree.root()));
+            let proof_de = MerkleProof::from_bytes(&proof.to_bytes().unwrap()).unwrap();
+            assert_eq!(proof, proof_de);
+        }
+        assert_eq!(
+            tree,
+            Blake3XofMerkleTree::<64>::from_bytes(&tree.to_bytes().unwrap()).unwrap()
+        );
+    }
+
+    #[test]
+    fn digest_length_is_checked_test() {
+        let tree = TruncatedSha256MerkleTree::<20>::new(&digests::<20>(5)).unwrap();
+        let bytes = tree.to_bytes().unwrap();
+        assert!(TruncatedSha256MerkleTree::<24>::from_bytes(&bytes).is_err());
+        assert!(crate::Sha256MerkleTree::from_bytes(&bytes).is_err());
+
+        // The default registry only knows full length SHA-256.
+        assert!(DynMerkleTree::from_bytes(&bytes).is_err());
+        let mut registry = HasherRegistry::default();
+        registry
+            .register::<Digest<20>, Sha256MerkleTreeHasher>()
+            .unwrap();
+        let dyn_tree = DynMerkleTree::from_bytes_with_registry(&bytes, &registry).unwrap();
+        assert_eq!(HashAlgorithm::Sha256, dyn_tree.algorithm());
+        assert_eq!(tree.root().as_ref(), dyn_tree.root());
+
+        let proof_bytes = tree.build_proof(4).unwrap().to_bytes().unwrap();
+        let dyn_proof = DynMerkleProof::from_bytes_with_registry(&proof_bytes, &registry).unwrap();
+        assert!(dyn_proof.validate_proof(&[4_u8; 20]));
+    }
+
+    /// Only digests of at least 16 bytes count as cryptographic.
+    #[test]
+    fn short_digest_is_not_cryptographic_test() {
+        let leaves = digests::<16>(5);
+        let tree = TruncatedSha256MerkleTree::<16>::new(&leaves).unwrap();
+        let proof = tree.build_proof(3).unwrap();
+        assert!(proof.verify(&leaves[3], &tree.root()));
+        assert!(DynMerkleProof::try_from(&proof)
+            .unwrap()
+            .validate_proof(leaves[3].as_ref()));
+        let leaves = digests::<16>(5);
+        let tree = Blake2bVarMerkleTree::<16>::new(&leaves).unwrap();
+        assert!(tree
+            .build_proof(3)
+            .unwrap()
+            .verify(&leaves[3], &tree.root()));
+
+        // Collisions of an 8 byte hash can be brute forced, so its proofs are never validated.
+        let leaves = digests::<8>(5);
+        let tree = TruncatedSha256MerkleTree::<8>::new(&leaves).unwrap();
+        let proof = tree.build_proof(3).unwrap();
+        assert!(!DynMerkleProof::try_from(&proof)
+            .unwrap()
+            .validate_proof(leaves[3].as_ref()));
+    }
+}
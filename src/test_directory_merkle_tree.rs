@@ -0,0 +1,196 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::algos::Blake3MerkleTreeHasher;
+    use crate::directory_merkle_tree::{content_root, CONTENT_CHUNK_LEN};
+    use crate::{DirectoryMerkleTree, DirectoryOptions, EntryKind};
+
+    type Directory = DirectoryMerkleTree<[u8; 32], Blake3MerkleTreeHasher>;
+
+    /// A directory in the temporary directory, removed with its contents when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> TempDir {
+            let path = std::env::temp_dir().join(format!(
+                "directory_merkle_tree_{}_{}",
+                std::process::id(),
+                name
+            ));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn write(&self, path: &str, contents: &[u8]) {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const FILES: [(&str, &[u8]); 5] = [
+        ("readme.md", b"hello"),
+        ("src/lib.rs", b"pub mod a;"),
+        ("src/a/mod.rs", b""),
+        ("src/a/b.rs", b"fn b() {}"),
+        ("z", b"last"),
+    ];
+
+    fn sample(name: &str) -> TempDir {
+        let dir = TempDir::new(name);
+        for (path, contents) in FILES {
+            dir.write(path, contents);
+        }
+        dir
+    }
+
+    #[test]
+    fn proofs_test() {
+        let dir = sample("proofs");
+        let tree = Directory::new(&dir.0).unwrap();
+        let names: Vec<&[u8]> = tree.entries().iter().map(|entry| entry.name()).collect();
+        assert_eq!(vec![&b"readme.md"[..], b"src", b"z"], names);
+        assert_eq!(EntryKind::Directory, tree.entries()[1].kind());
+        assert!(tree.subdirectory(1).is_some());
+        assert!(tree.subdirectory(0).is_none());
+
+        for (path, contents) in FILES {
+            let proof = tree.build_proof(path).unwrap();
+            assert_eq!(Some(tree.root()), proof.root());
+            assert!(proof.validate_proof(path, contents));
+            assert!(proof.verify(path, contents, &tree.root()));
+            assert!(proof.verify(Path::new(".").join(path), contents, &tree.root()));
+            assert!(!proof.verify(path, b"changed", &tree.root()));
+            assert!(!proof.verify("other", contents, &tree.root()));
+            assert_eq!(
+                Some(EntryKind::File),
+                proof.entries().last().map(|entry| entry.kind())
+            );
+        }
+        let lib = tree.build_proof("src/lib.rs").unwrap();
+        assert!(!lib.verify("src/a/b.rs", b"pub mod a;", &tree.root()));
+
+        // A layer without its root is refused rather than read out of bounds.
+        for layer in 0..lib.layers.len() {
+            let mut rootless = lib.clone();
+            rootless.layers[layer].1.sibling_hashes.clear();
+            assert!(!rootless.validate_proof("src/lib.rs", b"pub mod a;"));
+            assert!(!rootless.verify("src/lib.rs", b"pub mod a;", &tree.root()));
+        }
+
+        assert!(tree.build_proof("src").is_err());
+        assert!(tree.build_proof("src/missing.rs").is_err());
+        assert!(tree.build_proof("z/lib.rs").is_err());
+        assert!(tree.build_proof("src/../z").is_err());
+        assert!(tree.build_proof(dir.0.join("z")).is_err());
+        assert!(tree.build_proof("").is_err());
+    }
+
+    #[test]
+    fn root_commits_to_names_and_contents_test() {
+        let dir = sample("commits");
+        let root = Directory::new(&dir.0).unwrap().root();
+        assert_eq!(root, Directory::new(&dir.0).unwrap().root());
+        assert_eq!(
+            root,
+            Directory::new(&sample("commits_copy").0).unwrap().root()
+        );
+
+        dir.write("src/a/b.rs", b"fn c() {}");
+        let changed = Directory::new(&dir.0).unwrap().root();
+        assert_ne!(root, changed);
+
+        fs::rename(dir.0.join("z"), dir.0.join("y")).unwrap();
+        assert_ne!(changed, Directory::new(&dir.0).unwrap().root());
+    }
+
+    #[test]
+    fn empty_directories_test() {
+        let dir = sample("empty");
+        let root = Directory::new(&dir.0).unwrap().root();
+        fs::create_dir_all(dir.0.join("src/empty/nested")).unwrap();
+
+        let with_empty = Directory::new(&dir.0).unwrap();
+        assert_ne!(root, with_empty.root());
+        let skipping = DirectoryOptions {
+            skip_empty_directories: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            root,
+            Directory::with_options(&dir.0, skipping).unwrap().root()
+        );
+
+        let empty = TempDir::new("empty_top");
+        let empty_root = Directory::with_options(&empty.0, skipping).unwrap().root();
+        assert_eq!(empty_root, Directory::new(&empty.0).unwrap().root());
+        assert!(Directory::new(dir.0.join("z")).is_err());
+    }
+
+    #[test]
+    fn large_file_test() {
+        let dir = TempDir::new("large");
+        let contents: Vec<u8> = (0..CONTENT_CHUNK_LEN * 2 + 7)
+            .map(|idx| idx as u8)
+            .collect();
+        dir.write("large", &contents);
+        let tree = Directory::new(&dir.0).unwrap();
+        assert_eq!(
+            content_root::<[u8; 32], Blake3MerkleTreeHasher, _>(&contents[..]).unwrap(),
+            tree.entries()[0].root()
+        );
+        assert_ne!(
+            content_root::<[u8; 32], Blake3MerkleTreeHasher, _>(&contents[..]).unwrap(),
+            content_root::<[u8; 32], Blake3MerkleTreeHasher, _>(&contents[1..]).unwrap()
+        );
+        let proof = tree.build_proof("large").unwrap();
+        assert!(proof.verify("large", &contents, &tree.root()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_and_modes_test() {
+        use std::os::unix::fs::{symlink, PermissionsExt};
+
+        let dir = sample("unix");
+        symlink("readme.md", dir.0.join("link")).unwrap();
+        let tree = Directory::new(&dir.0).unwrap();
+        let proof = tree.build_proof("link").unwrap();
+        assert_eq!(
+            Some(EntryKind::Symlink),
+            proof.entries().last().map(|entry| entry.kind())
+        );
+        assert!(proof.verify("link", b"readme.md", &tree.root()));
+        assert!(!proof.verify("link", b"hello", &tree.root()));
+
+        let with_modes = DirectoryOptions {
+            mode_bits: true,
+            ..Default::default()
+        };
+        let before = Directory::with_options(&dir.0, with_modes).unwrap();
+        let default_root = tree.root();
+        let path = dir.0.join("z");
+        let mut permissions = fs::metadata(&path).unwrap().permissions();
+        permissions.set_mode(permissions.mode() ^ 0o100);
+        fs::set_permissions(&path, permissions).unwrap();
+
+        let after = Directory::with_options(&dir.0, with_modes).unwrap();
+        assert_ne!(before.root(), after.root());
+        assert_eq!(default_root, Directory::new(&dir.0).unwrap().root());
+        let proof = after.build_proof("z").unwrap();
+        assert!(proof.verify("z", b"last", &after.root()));
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o7777,
+            proof.entries().last().unwrap().mode()
+        );
+    }
+}
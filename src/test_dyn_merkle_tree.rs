@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher, Sha256MerkleTreeHasher};
+    use crate::{
+        BlakeMerkleTree, DynMerkleProof, DynMerkleTree, HashAlgorithm, Md5MerkleTree, MerkleProof,
+        MerkleTree, Sha256MerkleTree,
+    };
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    #[test]
+    fn dyn_tree_matches_typed_tree_test() {
+        for num_leaves in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..num_leaves]);
+            let typed_tree = Sha256MerkleTree::new(&leaves).unwrap();
+            let dyn_tree = DynMerkleTree::new(HashAlgorithm::Sha256, &leaves).unwrap();
+
+            assert_eq!(num_leaves, dyn_tree.num_leaves());
+            assert_eq!(typed_tree.root().as_slice(), dyn_tree.root());
+            assert_eq!(dyn_tree, DynMerkleTree::try_from(&typed_tree).unwrap());
+            assert_eq!(typed_tree, MerkleTree::try_from(&dyn_tree).unwrap());
+            dyn_tree.verify_integrity().unwrap();
+        }
+    }
+
+    #[test]
+    fn dyn_proof_matches_typed_proof_test() {
+        for num_leaves in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..num_leaves]);
+            let typed_tree = BlakeMerkleTree::new(&leaves).unwrap();
+            let dyn_tree = DynMerkleTree::new(HashAlgorithm::Blake3, &leaves).unwrap();
+
+            for (idx, leaf) in leaves.iter().enumerate() {
+                let typed_proof = typed_tree.build_proof(idx).unwrap();
+                let dyn_proof = dyn_tree.build_proof(idx).unwrap();
+                assert!(dyn_proof.validate_proof(leaf));
+                assert!(
+                    !dyn_proof.validate_proof(&leaves[(idx + 1) % num_leaves]) || num_leaves == 1
+                );
+                assert_eq!(dyn_proof, DynMerkleProof::try_from(&typed_proof).unwrap());
+                assert_eq!(typed_proof, MerkleProof::try_from(&dyn_proof).unwrap());
+                assert_eq!(
+                    typed_proof.to_bytes().unwrap(),
+                    dyn_proof.to_bytes().unwrap()
+                );
+
+                let mut rootless = typed_proof;
+                rootless.sibling_hashes.clear();
+                assert!(DynMerkleProof::try_from(&rootless).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn dyn_bytes_round_trip_test() {
+        let leaves: Vec<[u8; 16]> = (0..7_u8).map(|idx| [idx; 16]).collect();
+        let typed_tree = Md5MerkleTree::new(&leaves).unwrap();
+
+        let dyn_tree = DynMerkleTree::from_bytes(&typed_tree.to_bytes().unwrap()).unwrap();
+        assert_eq!(HashAlgorithm::Md5, dyn_tree.algorithm());
+        assert_eq!(16, dyn_tree.digest_len());
+        assert_eq!(typed_tree.root().as_slice(), dyn_tree.root());
+        assert_eq!(
+            typed_tree,
+            Md5MerkleTree::from_bytes(&dyn_tree.to_bytes().unwrap()).unwrap()
+        );
+
+        let bytes = typed_tree.build_proof(6).unwrap().to_bytes().unwrap();
+        let dyn_proof = DynMerkleProof::from_bytes(&bytes).unwrap();
+        assert_eq!(typed_tree.root().as_slice(), dyn_proof.root());
+        assert_eq!(bytes, dyn_proof.to_bytes().unwrap());
+    }
+
+    #[test]
+    fn dyn_mixed_algorithms_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let md5_leaves: Vec<[u8; 16]> = (0..3_u8).map(|idx| [idx; 16]).collect();
+        let encoded = [
+            Sha256MerkleTree::new(&leaves)
+                .unwrap()
+                .build_proof(1)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            BlakeMerkleTree::new(&leaves)
+                .unwrap()
+                .build_proof(1)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            Md5MerkleTree::new(&md5_leaves)
+                .unwrap()
+                .build_proof(1)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+        ];
+
+        let proofs: Vec<DynMerkleProof> = encoded
+            .iter()
+            .map(|bytes| DynMerkleProof::from_bytes(bytes).unwrap())
+            .collect();
+        assert!(proofs[0].validate_proof(&leaves[1]));
+        assert!(proofs[1].validate_proof(&leaves[1]));
+        // MD5 proofs can be forged, so they are never valid.
+        assert!(!proofs[2].validate_proof(&md5_leaves[1]));
+        assert!(!proofs[2].validate_proof(&leaves[1]));
+    }
+
+    #[test]
+    fn dyn_conversion_checks_algorithm_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let dyn_tree = DynMerkleTree::new(HashAlgorithm::Sha256, &leaves).unwrap();
+        assert!(MerkleTree::<[u8; 32], Blake3MerkleTreeHasher>::try_from(&dyn_tree).is_err());
+        assert!(MerkleTree::<[u8; 32], Sha256MerkleTreeHasher>::try_from(&dyn_tree).is_ok());
+
+        let dyn_proof = dyn_tree.build_proof(2).unwrap();
+        assert!(MerkleProof::<[u8; 32], Blake3MerkleTreeHasher>::try_from(&dyn_proof).is_err());
+    }
+
+    #[test]
+    fn dyn_tree_rejects_bad_input_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let empty: [[u8; 32]; 0] = [];
+        assert!(DynMerkleTree::new(HashAlgorithm::Sha256, &empty).is_err());
+        assert!(DynMerkleTree::new(HashAlgorithm::Md5, &leaves).is_err());
+        assert!(DynMerkleTree::new(HashAlgorithm::Custom(0x300000), &leaves).is_err());
+
+        let dyn_tree = DynMerkleTree::new(HashAlgorithm::Sha256, &leaves).unwrap();
+        assert!(dyn_tree.build_proof(leaves.len()).is_err());
+
+        let mut bytes = dyn_tree.build_proof(0).unwrap().to_bytes().unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        assert!(DynMerkleProof::from_bytes(&bytes).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Md5MerkleTreeHasher};
+    use crate::{
+        BlakeMerkleTree, CryptographicHasher, HashAlgorithm, HasherRegistry, Keccak256MerkleTree,
+        Md5MerkleTree, MerkleTreeHasher, Sha256MerkleTree,
+    };
+
+    const SINGLE_CHAR_VALUES: [&str; 7] = ["a", "b", "c", "d", "e", "f", "g"];
+
+    #[derive(Clone, Copy, Debug, Default)]
+    struct XorMerkleTreeHasher {}
+
+    impl MerkleTreeHasher<[u8; 4]> for XorMerkleTreeHasher {
+        fn name(&self) -> String {
+            "XOR".to_string()
+        }
+        fn algorithm(&self) -> Option<HashAlgorithm> {
+            Some(HashAlgorithm::Custom(0x300000))
+        }
+        fn hash_leaf(leaf: &[u8; 4]) -> [u8; 4] {
+            leaf.map(|byte| byte ^ 0x5a)
+        }
+        fn hash_non_leaf_node(prefix: &[u8; 1], lhs: &[u8; 4], rhs: &[u8; 4]) -> [u8; 4] {
+            let mut hash = [prefix[0]; 4];
+            for idx in 0..4 {
+                hash[idx] ^= lhs[idx].rotate_left(1) ^ rhs[idx];
+            }
+            hash
+        }
+    }
+
+    // Stands in for a cryptographic hasher from outside this crate.
+    impl CryptographicHasher<[u8; 4]> for XorMerkleTreeHasher {}
+
+    #[test]
+    fn hash_algorithm_code_test() {
+        for algorithm in [
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Keccak256,
+            HashAlgorithm::Blake3,
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha512_256,
+            HashAlgorithm::Sha3_256,
+            HashAlgorithm::Blake2b256,
+            HashAlgorithm::Blake2s256,
+            HashAlgorithm::PoseidonBn254,
+            HashAlgorithm::Xxh3_128,
+            HashAlgorithm::Custom(0x300000),
+        ] {
+            assert_eq!(algorithm, HashAlgorithm::from_code(algorithm.code()));
+        }
+        assert_eq!(HashAlgorithm::Sha256, HashAlgorithm::from_code(0x12));
+        assert_eq!(
+            Some(HashAlgorithm::Keccak256),
+            HashAlgorithm::from_name("Keccak-256")
+        );
+    }
+
+    #[test]
+    fn keccak256_is_not_sha256_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let keccak_tree = Keccak256MerkleTree::new(&leaves).unwrap();
+        let sha256_tree = Sha256MerkleTree::new(&leaves).unwrap();
+        assert_ne!(keccak_tree.root(), sha256_tree.root());
+
+        let bytes = keccak_tree.to_bytes().unwrap();
+        assert!(Sha256MerkleTree::from_bytes(&bytes).is_err());
+        assert_eq!(
+            HashAlgorithm::Keccak256,
+            crate::binary_format::peek_algorithm(&bytes).unwrap()
+        );
+    }
+
+    #[test]
+    fn default_registry_test() {
+        let registry = HasherRegistry::default();
+        for algorithm in [
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Keccak256,
+            HashAlgorithm::Blake3,
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha512_256,
+            HashAlgorithm::Sha3_256,
+            HashAlgorithm::Blake2b256,
+            HashAlgorithm::Blake2s256,
+            HashAlgorithm::PoseidonBn254,
+            HashAlgorithm::Xxh3_128,
+        ] {
+            let digest_len = match algorithm {
+                HashAlgorithm::Md5 | HashAlgorithm::Xxh3_128 => 16,
+                _ => 32,
+            };
+            let hasher = registry.get(algorithm, digest_len).unwrap();
+            assert_eq!(algorithm, hasher.algorithm());
+            assert_eq!(algorithm.name(), hasher.name());
+        }
+        assert!(registry.get(HashAlgorithm::Md5, 32).is_err());
+        assert!(registry.get(HashAlgorithm::Custom(0x300000), 32).is_err());
+    }
+
+    #[test]
+    fn registry_validate_proof_bytes_test() {
+        let registry = HasherRegistry::default();
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let sha256_tree = Sha256MerkleTree::new(&leaves).unwrap();
+        let blake3_tree = BlakeMerkleTree::new(&leaves).unwrap();
+
+        for (idx, leaf) in leaves.iter().enumerate() {
+            let bytes = sha256_tree.build_proof(idx).unwrap().to_bytes().unwrap();
+            assert!(registry.validate_proof_bytes(&bytes, leaf).unwrap());
+            assert!(!registry
+                .validate_proof_bytes(&bytes, &leaves[(idx + 1) % leaves.len()])
+                .unwrap());
+
+            let bytes = blake3_tree.build_proof(idx).unwrap().to_bytes().unwrap();
+            assert!(registry.validate_proof_bytes(&bytes, leaf).unwrap());
+        }
+    }
+
+    #[test]
+    fn registry_refuses_md5_proof_bytes_test() {
+        let registry = HasherRegistry::default();
+        let leaves: Vec<[u8; 16]> = (0..5_u8).map(|idx| [idx; 16]).collect();
+        let tree = Md5MerkleTree::new(&leaves).unwrap();
+        let bytes = tree.build_proof(4).unwrap().to_bytes().unwrap();
+        assert!(!registry
+            .get(HashAlgorithm::Md5, 16)
+            .unwrap()
+            .is_cryptographic());
+        assert!(registry.validate_proof_bytes(&bytes, &leaves[4]).is_err());
+
+        let mut without_md5 = HasherRegistry::new();
+        without_md5
+            .register_non_cryptographic::<[u8; 16], Md5MerkleTreeHasher>()
+            .unwrap();
+        assert!(without_md5
+            .validate_proof_bytes(&bytes, &leaves[4])
+            .is_err());
+        assert!(HasherRegistry::new()
+            .validate_proof_bytes(&bytes, &leaves[4])
+            .is_err());
+    }
+
+    #[test]
+    fn registry_custom_hasher_test() {
+        let mut registry = HasherRegistry::new();
+        registry.register::<[u8; 4], XorMerkleTreeHasher>().unwrap();
+        assert!(registry
+            .get(HashAlgorithm::Custom(0x300000), 4)
+            .unwrap()
+            .is_cryptographic());
+
+        let leaves: Vec<[u8; 4]> = (0..6_u8).map(|idx| [idx, 1, 2, 3]).collect();
+        let tree = crate::MerkleTree::<[u8; 4], XorMerkleTreeHasher>::new(&leaves).unwrap();
+        let bytes = tree.build_proof(5).unwrap().to_bytes().unwrap();
+        assert!(registry.validate_proof_bytes(&bytes, &leaves[5]).unwrap());
+        assert!(!registry.validate_proof_bytes(&bytes, &leaves[4]).unwrap());
+    }
+}
@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::leaf_index::LeafIndex;
+    use crate::{BlakeMerkleTree, MerkleTreeHasher, Sha256MerkleTree};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+    const DUPLICATE_VALUES: [&str; 7] = ["a", "b", "a", "c", "b", "a", "d"];
+
+    #[test]
+    fn position_of_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        for (idx, leaf) in leaves.iter().enumerate() {
+            assert_eq!(Some(idx), tree.position_of(leaf));
+            assert_eq!(vec![idx], tree.positions_of(leaf));
+        }
+        assert_eq!(None, tree.position_of(&tree.root()));
+        assert!(tree.positions_of(&tree.root()).is_empty());
+    }
+
+    #[test]
+    fn positions_of_duplicates_test() {
+        let leaves = blake3_hash_leaf_values(&DUPLICATE_VALUES);
+        let tree = Sha256MerkleTree::new(&leaves).unwrap();
+        assert_eq!(Some(0), tree.position_of(&leaves[0]));
+        assert_eq!(vec![0, 2, 5], tree.positions_of(&leaves[0]));
+        assert_eq!(vec![1, 4], tree.positions_of(&leaves[1]));
+        assert_eq!(vec![6], tree.positions_of(&leaves[6]));
+    }
+
+    #[test]
+    fn build_proof_for_leaf_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap().with_leaf_index();
+        for (idx, leaf) in leaves.iter().enumerate() {
+            let proof = tree.build_proof_for_leaf(leaf).unwrap();
+            assert_eq!(tree.build_proof(idx).unwrap(), proof);
+            assert!(proof.validate_proof(leaf));
+        }
+        assert!(tree.build_proof_for_leaf(&tree.root()).is_err());
+    }
+
+    #[test]
+    fn leaf_index_survives_clone_and_serde_test() {
+        let leaves = blake3_hash_leaf_values(&DUPLICATE_VALUES);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap().with_leaf_index();
+        assert_eq!(vec![0, 2, 5], tree.clone().positions_of(&leaves[0]));
+
+        let json = serde_json::to_string(&tree).unwrap();
+        let tree_de: BlakeMerkleTree = serde_json::from_str(&json).unwrap();
+        assert!(tree_de.leaf_positions.get().is_none());
+        assert_eq!(vec![0, 2, 5], tree_de.positions_of(&leaves[0]));
+    }
+
+    /// The compact index searches sorted leaf hashes in place and finds the same positions as the map.
+    #[test]
+    fn compact_leaf_index_test() {
+        let mut leaves = blake3_hash_leaf_values(&DUPLICATE_VALUES);
+        leaves.sort_by_key(Blake3MerkleTreeHasher::hash_leaf);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        let compact = tree.clone().with_compact_leaf_index();
+        assert!(matches!(
+            compact.leaf_positions.get(),
+            Some(LeafIndex::Sorted)
+        ));
+        for leaf in &leaves {
+            assert_eq!(tree.positions_of(leaf), compact.positions_of(leaf));
+            assert_eq!(tree.position_of(leaf), compact.position_of(leaf));
+        }
+        let a = blake3_hash_leaf_values(&["a"])[0];
+        assert_eq!(3, compact.positions_of(&a).len());
+        assert!(matches!(tree.leaf_positions.get(), Some(LeafIndex::Map(_))));
+        assert_eq!(None, compact.position_of(&tree.root()));
+        assert!(compact.positions_of(&tree.root()).is_empty());
+        let proof = compact.build_proof_for_leaf(&leaves[3]).unwrap();
+        assert_eq!(tree.build_proof(3).unwrap(), proof);
+
+        // Unsorted leaf hashes fall back to the map.
+        let unsorted = blake3_hash_leaf_values(&DUPLICATE_VALUES);
+        let unsorted_tree = BlakeMerkleTree::new(&unsorted)
+            .unwrap()
+            .with_compact_leaf_index();
+        assert!(matches!(
+            unsorted_tree.leaf_positions.get(),
+            Some(LeafIndex::Map(_))
+        ));
+        assert_eq!(vec![0, 2, 5], unsorted_tree.positions_of(&unsorted[0]));
+
+        // An index already built is kept.
+        let map = tree.with_leaf_index().with_compact_leaf_index();
+        assert!(matches!(map.leaf_positions.get(), Some(LeafIndex::Map(_))));
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use std::fs::{File, OpenOptions};
+    use std::io::{Seek, SeekFrom, Write};
+
+    use anyhow::Result;
+
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher, Sha256MerkleTreeHasher};
+    use crate::mapped_merkle_tree::MAPPED_HEADER_LEN;
+    use crate::test_utils::TempPath;
+    use crate::{BlakeMerkleTree, MappedMerkleTree, MerkleTreeHasher};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    fn temp_path(name: &str) -> TempPath {
+        TempPath::new("mapped_merkle_tree", &format!("{}.tree", name))
+    }
+
+    fn write_tree(path: &TempPath, count: usize) -> (Vec<[u8; 32]>, BlakeMerkleTree) {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        tree.write_mapped(File::create(&path.0).unwrap()).unwrap();
+        (leaves, tree)
+    }
+
+    fn open<H: Default + MerkleTreeHasher<[u8; 32]>>(
+        path: &TempPath,
+    ) -> Result<MappedMerkleTree<[u8; 32], H>> {
+        // Safety: each test owns its file and only changes it while it is not mapped.
+        unsafe { MappedMerkleTree::open(&path.0) }
+    }
+
+    fn overwrite(path: &TempPath, offset: u64, bytes: &[u8]) {
+        let mut file = OpenOptions::new().write(true).open(&path.0).unwrap();
+        file.seek(SeekFrom::Start(offset)).unwrap();
+        file.write_all(bytes).unwrap();
+    }
+
+    #[test]
+    fn same_as_merkle_tree_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let path = temp_path(&format!("same_{}", count));
+            let (leaves, expected) = write_tree(&path, count);
+            let tree = open::<Blake3MerkleTreeHasher>(&path).unwrap();
+            assert_eq!(count, tree.num_leaves());
+            assert_eq!(expected.root(), tree.root().unwrap());
+            assert_eq!(Some(&expected.root()[..]), tree.node_bytes(tree.len() - 1));
+            assert_eq!(None, tree.node_bytes(tree.len()));
+            tree.verify_checksum().unwrap();
+            tree.verify_integrity().unwrap();
+            for (idx, leaf) in leaves.iter().enumerate() {
+                let proof = tree.build_proof(idx).unwrap();
+                assert_eq!(expected.build_proof(idx).unwrap(), proof);
+                assert!(proof.verify(leaf, &expected.root()));
+            }
+            assert!(tree.build_proof(count).is_err());
+        }
+    }
+
+    #[test]
+    fn wrong_algorithm_test() {
+        let path = temp_path("wrong_algorithm");
+        write_tree(&path, 5);
+        assert!(open::<Sha256MerkleTreeHasher>(&path).is_err());
+    }
+
+    #[test]
+    fn corrupt_header_test() {
+        let path = temp_path("corrupt_header");
+        write_tree(&path, 5);
+        // Doubling the number of leaves breaks the header checksum.
+        overwrite(&path, 16, &[10]);
+        assert!(open::<Blake3MerkleTreeHasher>(&path).is_err());
+
+        std::fs::write(&path.0, b"NDMM").unwrap();
+        assert!(open::<Blake3MerkleTreeHasher>(&path).is_err());
+    }
+
+    #[test]
+    fn truncated_file_test() {
+        let path = temp_path("truncated");
+        write_tree(&path, 5);
+        let len = std::fs::metadata(&path.0).unwrap().len();
+        let file = OpenOptions::new().write(true).open(&path.0).unwrap();
+        file.set_len(len - 1).unwrap();
+        drop(file);
+        assert!(open::<Blake3MerkleTreeHasher>(&path).is_err());
+    }
+
+    #[test]
+    fn corrupt_node_test() {
+        let path = temp_path("corrupt_node");
+        let (leaves, expected) = write_tree(&path, 5);
+        let mut leaf = expected.tree[2];
+        leaf[0] ^= 1;
+        overwrite(&path, (MAPPED_HEADER_LEN + 2 * 32) as u64, &leaf);
+
+        let tree = open::<Blake3MerkleTreeHasher>(&path).unwrap();
+        assert!(tree.verify_checksum().is_err());
+        assert!(tree.verify_integrity().is_err());
+        assert_eq!(expected.root(), tree.root().unwrap());
+        assert!(!tree
+            .build_proof(2)
+            .unwrap()
+            .verify(&leaves[2], &expected.root()));
+    }
+}
@@ -36,6 +36,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn blake3_merkle_proof_verify_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        let other_tree = BlakeMerkleTree::new(&leaves[1..]).unwrap();
+        for (idx, leaf) in leaves.iter().enumerate() {
+            let proof = tree.build_proof(idx).unwrap();
+            assert!(proof.verify(leaf, &tree.root()));
+            assert!(!proof.verify(leaf, &other_tree.root()));
+            assert!(!proof.verify(&tree.root(), &tree.root()));
+        }
+
+        // A proof without a root is refused rather than read out of bounds.
+        let mut rootless = tree.build_proof(2).unwrap();
+        rootless.sibling_hashes.clear();
+        assert!(!rootless.validate_proof(&leaves[2]));
+        assert!(!rootless.verify(&leaves[2], &tree.root()));
+    }
+
     #[test]
     fn blake3_merkle_proof_serde_test() {
         let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
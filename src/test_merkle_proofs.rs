@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use anyhow::bail;
+
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::{BlakeMerkleTree, MerkleProof};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    fn expected_proofs(
+        tree: &BlakeMerkleTree,
+    ) -> Vec<MerkleProof<[u8; 32], Blake3MerkleTreeHasher>> {
+        (0..tree.num_leaves())
+            .map(|idx| tree.build_proof(idx).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn same_as_build_proof_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+            let tree = BlakeMerkleTree::new(&leaves).unwrap();
+            let expected = expected_proofs(&tree);
+
+            assert_eq!(count, tree.proofs().len());
+            assert_eq!(expected, tree.build_all_proofs());
+            assert_eq!(expected, tree.par_build_all_proofs());
+            for (proof, leaf) in tree.build_all_proofs().iter().zip(&leaves) {
+                assert!(proof.verify(leaf, &tree.root()));
+            }
+        }
+    }
+
+    #[test]
+    fn iterator_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        let expected = expected_proofs(&tree);
+
+        let mut proofs = tree.proofs();
+        assert_eq!(Some(&expected[0]), proofs.next().as_ref());
+        assert_eq!(Some(&expected[8]), proofs.next_back().as_ref());
+        assert_eq!(Some(&expected[3]), proofs.nth(2).as_ref());
+        assert_eq!(4, proofs.len());
+        assert_eq!(expected[4..8].to_vec(), proofs.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stream_proofs_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+
+        let mut streamed = Vec::new();
+        tree.stream_proofs(|proof| {
+            streamed.push(proof);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(expected_proofs(&tree), streamed);
+
+        let mut count = 0;
+        let result = tree.stream_proofs(|proof| {
+            count += 1;
+            if proof.leaf_index() == 4 {
+                bail!("the sink is full");
+            }
+            Ok(())
+        });
+        assert!(result.is_err());
+        assert_eq!(5, count);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher, Sha256MerkleTreeHasher};
+    use crate::annotation::Sum;
+    use crate::{AnnotatedMerkleTree, MerkleSumTree};
+
+    type BlakeMerkleSumTree = MerkleSumTree<[u8; 32], Blake3MerkleTreeHasher>;
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    fn accounts(count: usize) -> Vec<([u8; 32], u128)> {
+        blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count])
+            .into_iter()
+            .zip((1..).map(|balance: u128| balance * 1_000))
+            .collect()
+    }
+
+    #[test]
+    fn total_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = accounts(count);
+            let tree = BlakeMerkleSumTree::new(&leaves).unwrap();
+            let total: u128 = leaves.iter().map(|(_, balance)| balance).sum();
+            assert_eq!(total, tree.total());
+            assert_eq!(count, tree.num_leaves());
+        }
+        assert!(BlakeMerkleSumTree::new(&[]).is_err());
+    }
+
+    #[test]
+    fn overflow_test() {
+        let mut leaves = accounts(3);
+        leaves[1].1 = u128::MAX;
+        assert!(BlakeMerkleSumTree::new(&leaves).is_err());
+
+        // The last node of an odd level is only counted once.
+        leaves.truncate(1);
+        leaves[0].1 = u128::MAX;
+        let tree = BlakeMerkleSumTree::new(&leaves).unwrap();
+        assert_eq!(u128::MAX, tree.total());
+    }
+
+    #[test]
+    fn annotated_sum_tree_test() {
+        // A sum tree is an annotated tree summing its balances, with the same roots and proofs.
+        let leaves = accounts(7);
+        let tree = BlakeMerkleSumTree::new(&leaves).unwrap();
+        let sums: Vec<([u8; 32], Sum)> = leaves
+            .iter()
+            .map(|(leaf, balance)| (*leaf, Sum(*balance)))
+            .collect();
+        let annotated: AnnotatedMerkleTree<[u8; 32], Blake3MerkleTreeHasher, Sum> =
+            AnnotatedMerkleTree::new(&sums).unwrap();
+        assert_eq!(annotated.root(), tree.root());
+        assert_eq!(*annotated.root_annotation(), Sum(tree.total()));
+        assert_eq!(
+            annotated.build_proof(4).unwrap(),
+            tree.build_proof(4).unwrap().proof
+        );
+    }
+
+    #[test]
+    fn proof_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = accounts(count);
+            let tree = MerkleSumTree::<[u8; 32], Sha256MerkleTreeHasher>::new(&leaves).unwrap();
+            for (idx, (leaf, balance)) in leaves.iter().enumerate() {
+                let proof = tree.build_proof(idx).unwrap();
+                assert_eq!(*balance, proof.balance());
+                assert!(proof.validate_proof(leaf, *balance));
+                assert!(proof.verify(leaf, *balance, &tree.root(), tree.total()));
+                assert!(!proof.validate_proof(leaf, balance + 1));
+                assert!(!proof.verify(leaf, *balance, &tree.root(), tree.total() - 1));
+            }
+            assert!(tree.build_proof(count).is_err());
+        }
+    }
+
+    #[test]
+    fn balance_failure_test() {
+        let leaves = accounts(7);
+        let tree = BlakeMerkleSumTree::new(&leaves).unwrap();
+        let proof = tree.build_proof(2).unwrap();
+        let (leaf, balance) = leaves[2];
+
+        // Moving balance between siblings keeps the total but changes the root.
+        let mut shifted = proof.clone();
+        shifted.proof.sibling_nodes[0].1 .0 -= 1;
+        shifted.proof.annotation.0 += 1;
+        assert!(!shifted.validate_proof(&leaf, balance + 1));
+
+        // A sibling cannot cancel out the other balances.
+        let mut overflowing = proof.clone();
+        overflowing.proof.sibling_nodes[1].1 = Sum(u128::MAX);
+        assert!(!overflowing.validate_proof(&leaf, balance));
+    }
+}
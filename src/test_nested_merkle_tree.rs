@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::{BlakeMerkleTree, MerkleTreeHasher, NestedMerkleTree, NestedMerkleTreeHasher};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    type Nested = NestedMerkleTree<[u8; 32], Blake3MerkleTreeHasher>;
+
+    /// Objects of bucket `bucket`, between one and nine of them.
+    fn objects(bucket: usize) -> Vec<[u8; 32]> {
+        blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[bucket % 3..bucket % 9 + 1])
+    }
+
+    fn buckets(tenant: usize) -> Nested {
+        let trees = (0..tenant + 1)
+            .map(|bucket| BlakeMerkleTree::new(&objects(tenant * 3 + bucket)).unwrap())
+            .collect();
+        NestedMerkleTree::new(trees).unwrap()
+    }
+
+    #[test]
+    fn two_layers_test() {
+        let trees: Vec<BlakeMerkleTree> = (0..5)
+            .map(|bucket| BlakeMerkleTree::new(&objects(bucket)).unwrap())
+            .collect();
+        let nested = NestedMerkleTree::new(trees.clone()).unwrap();
+        assert_eq!(2, nested.height());
+        let child_roots: Vec<[u8; 32]> = trees
+            .iter()
+            .map(|tree| Blake3MerkleTreeHasher::hash_child_root(&tree.root(), 1))
+            .collect();
+        assert_ne!(
+            BlakeMerkleTree::new(&child_roots).unwrap().root(),
+            nested.root()
+        );
+
+        for (bucket, tree) in trees.iter().enumerate() {
+            assert_eq!(
+                Some(tree.root()),
+                nested.child(bucket).map(|child| child.root())
+            );
+            for (object, leaf) in objects(bucket).iter().enumerate() {
+                let proof = nested.build_proof(&[bucket, object]).unwrap();
+                assert_eq!(2, proof.layers().len());
+                assert_eq!(tree.build_proof(object).unwrap(), proof.layers()[0]);
+                assert_eq!(Some(nested.root()), proof.root());
+                assert!(proof.validate_proof(leaf));
+                assert!(proof.verify(leaf, &nested.root()));
+                assert!(!proof.verify(leaf, &tree.root()));
+                assert!(!proof.verify(&tree.root(), &nested.root()));
+            }
+            assert!(nested.build_proof(&[bucket, tree.num_leaves()]).is_err());
+        }
+        assert!(nested.build_proof(&[5, 0]).is_err());
+        assert!(nested.build_proof(&[0]).is_err());
+        assert!(nested.build_proof(&[0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn three_layers_test() {
+        let tenants: Vec<Nested> = (0..3).map(buckets).collect();
+        let nested = NestedMerkleTree::nest(tenants.clone()).unwrap();
+        assert_eq!(3, nested.height());
+
+        for (tenant, buckets) in tenants.iter().enumerate() {
+            assert_eq!(
+                Some(buckets.root()),
+                nested.child(tenant).map(|child| child.root())
+            );
+            for bucket in 0..buckets.children().len() {
+                for (object, leaf) in objects(tenant * 3 + bucket).iter().enumerate() {
+                    let proof = nested.build_proof(&[tenant, bucket, object]).unwrap();
+                    assert_eq!(3, proof.layers().len());
+                    assert!(proof.verify(leaf, &nested.root()));
+
+                    let inner = buckets.build_proof(&[bucket, object]).unwrap();
+                    assert!(inner.verify(leaf, &buckets.root()));
+                    assert!(!inner.verify(leaf, &nested.root()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layers_are_separated_test() {
+        let tenants: Vec<Nested> = (0..2).map(buckets).collect();
+        let nested = NestedMerkleTree::nest(tenants.clone()).unwrap();
+        let bucket = tenants[1].child(1).unwrap().root();
+
+        // A bucket root is not an object of the tenant layer.
+        let proof = nested.build_proof(&[1, 1, 0]).unwrap();
+        let mut shortened = proof.clone();
+        shortened.layers.remove(0);
+        assert!(!shortened.verify(&bucket, &nested.root()));
+        assert_ne!(
+            Blake3MerkleTreeHasher::hash_leaf(&bucket),
+            Blake3MerkleTreeHasher::hash_child_root(&bucket, 1)
+        );
+        assert_ne!(
+            Blake3MerkleTreeHasher::hash_child_root(&bucket, 1),
+            Blake3MerkleTreeHasher::hash_child_root(&bucket, 2)
+        );
+
+        // Layers can not be swapped for those of another tenant.
+        let other = nested.build_proof(&[0, 0, 0]).unwrap();
+        let mut mixed = proof.clone();
+        mixed.layers[1] = other.layers[1].clone();
+        let leaf = objects(4)[0];
+        assert!(proof.verify(&leaf, &nested.root()));
+        assert!(!mixed.verify(&leaf, &nested.root()));
+
+        // A layer without its root is refused rather than read out of bounds.
+        for layer in 0..proof.layers.len() {
+            let mut rootless = proof.clone();
+            rootless.layers[layer].sibling_hashes.clear();
+            assert!(!rootless.validate_proof(&leaf));
+            assert!(!rootless.verify(&leaf, &nested.root()));
+        }
+
+        // Children of different heights can not be mixed.
+        let mut children: Vec<_> = tenants
+            .iter()
+            .map(|tenant| tenant.children()[0].clone())
+            .collect();
+        children.push(nested.children()[0].clone());
+        assert!(NestedMerkleTree::from_children(children).is_err());
+        assert!(NestedMerkleTree::<[u8; 32], Blake3MerkleTreeHasher>::new(Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests {
+    use std::fs::OpenOptions;
+    use std::io::{Seek, SeekFrom, Write};
+
+    use crate::node_store::{FileNodeStore, MemoryNodeStore, NodeStore, HEADER_SLOT_LEN};
+    use crate::test_utils::TempPath;
+
+    fn temp_path(name: &str) -> TempPath {
+        TempPath::new("node_store", &format!("{}.nodes", name))
+    }
+
+    fn node(value: u8) -> [u8; 4] {
+        [value; 4]
+    }
+
+    #[test]
+    fn memory_store_test() {
+        let mut store = MemoryNodeStore::new();
+        store.put(0, 0, node(1)).unwrap();
+        store.put(0, 1, node(2)).unwrap();
+        store.put(1, 0, node(3)).unwrap();
+        assert_eq!(node(2), store.get(0, 1).unwrap());
+        store.put(0, 1, node(4)).unwrap();
+        assert_eq!(node(4), store.get(0, 1).unwrap());
+
+        assert!(store.get(1, 1).is_err());
+        assert!(store.put(0, 3, node(5)).is_err());
+        assert!(store.put(3, 0, node(5)).is_err());
+    }
+
+    #[test]
+    fn file_store_append_only_test() {
+        let path = temp_path("append_only");
+        let mut store = FileNodeStore::create(&path.0, 3).unwrap();
+        assert!(store.put(0, 1, node(1)).is_err());
+        for idx in 0..3 {
+            store.put(0, idx, node(idx as u8)).unwrap();
+        }
+        assert!(store.put(0, 2, node(9)).is_err());
+        assert!(store.put(0, 3, node(9)).is_err());
+        assert!(store.put(1, 1, node(9)).is_err());
+        store.put(1, 0, node(3)).unwrap();
+        assert!(store.put(3, 0, node(9)).is_err());
+
+        // Pending nodes are readable before they are written.
+        assert_eq!(node(2), store.get(0, 2).unwrap());
+        assert!(store.get(1, 1).is_err());
+        assert!(!store.is_complete());
+        store.put(1, 1, node(4)).unwrap();
+        store.put(2, 0, node(5)).unwrap();
+        store.commit().unwrap();
+        assert!(store.is_complete());
+        assert_eq!(node(4), store.get(1, 1).unwrap());
+    }
+
+    #[test]
+    fn file_store_concurrent_get_test() {
+        let path = temp_path("concurrent_get");
+        let num_leaves = 64;
+        let mut store = FileNodeStore::create(&path.0, num_leaves).unwrap();
+        for idx in 0..num_leaves {
+            store.put(0, idx, node(idx as u8)).unwrap();
+        }
+        store.commit().unwrap();
+
+        // Readers share `&store`, so each read must not depend on a shared cursor.
+        std::thread::scope(|scope| {
+            for offset in 0..4 {
+                let store = &store;
+                scope.spawn(move || {
+                    for round in 0..200 {
+                        let idx = (round * 7 + offset * 13) % num_leaves;
+                        assert_eq!(node(idx as u8), store.get(0, idx).unwrap());
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn file_store_reopen_test() {
+        let path = temp_path("reopen");
+        let mut store = FileNodeStore::create(&path.0, 4).unwrap();
+        for idx in 0..4 {
+            store.put(0, idx, node(idx as u8)).unwrap();
+        }
+        store.commit().unwrap();
+        store.put(1, 0, node(4)).unwrap();
+        store.commit().unwrap();
+        // Never committed, as if the process crashed here.
+        store.put(1, 1, node(5)).unwrap();
+        drop(store);
+
+        let mut store = FileNodeStore::<[u8; 4]>::open(&path.0).unwrap();
+        assert_eq!(4, store.num_leaves());
+        assert_eq!(5, store.committed_nodes());
+        assert_eq!(node(4), store.get(1, 0).unwrap());
+        assert!(store.get(1, 1).is_err());
+        assert_eq!(
+            (2 * HEADER_SLOT_LEN + 5 * 4) as u64,
+            std::fs::metadata(&path.0).unwrap().len()
+        );
+
+        store.put(1, 1, node(6)).unwrap();
+        store.put(2, 0, node(7)).unwrap();
+        store.commit().unwrap();
+        let store = FileNodeStore::<[u8; 4]>::open(&path.0).unwrap();
+        assert!(store.is_complete());
+        assert_eq!(node(6), store.get(1, 1).unwrap());
+    }
+
+    /// A file shorter than its committed nodes is rejected instead of zero-extended.
+    #[test]
+    fn file_store_truncated_file_test() {
+        let path = temp_path("truncated_file");
+        let mut store = FileNodeStore::create(&path.0, 4).unwrap();
+        for idx in 0..4 {
+            store.put(0, idx, node(idx as u8)).unwrap();
+        }
+        store.commit().unwrap();
+        drop(store);
+
+        let file = OpenOptions::new().write(true).open(&path.0).unwrap();
+        file.set_len((2 * HEADER_SLOT_LEN + 3 * 4) as u64).unwrap();
+        drop(file);
+        assert!(FileNodeStore::<[u8; 4]>::open(&path.0).is_err());
+        assert_eq!(
+            (2 * HEADER_SLOT_LEN + 3 * 4) as u64,
+            std::fs::metadata(&path.0).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn file_store_torn_header_test() {
+        let path = temp_path("torn_header");
+        let mut store = FileNodeStore::create(&path.0, 2).unwrap();
+        store.put(0, 0, node(1)).unwrap();
+        store.commit().unwrap();
+        store.put(0, 1, node(2)).unwrap();
+        store.commit().unwrap();
+        drop(store);
+
+        // The second commit wrote slot 0, corrupt it as if the write was torn.
+        let mut file = OpenOptions::new().write(true).open(&path.0).unwrap();
+        file.seek(SeekFrom::Start(20)).unwrap();
+        file.write_all(&[0xff; 4]).unwrap();
+        drop(file);
+
+        let store = FileNodeStore::<[u8; 4]>::open(&path.0).unwrap();
+        assert_eq!(1, store.committed_nodes());
+        assert_eq!(node(1), store.get(0, 0).unwrap());
+        assert!(store.get(0, 1).is_err());
+
+        // Without any valid slot the file is rejected.
+        let mut file = OpenOptions::new().write(true).open(&path.0).unwrap();
+        file.write_all(&[0; 2 * HEADER_SLOT_LEN]).unwrap();
+        drop(file);
+        assert!(FileNodeStore::<[u8; 4]>::open(&path.0).is_err());
+    }
+}
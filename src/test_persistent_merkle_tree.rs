@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::{BlakeMerkleTree, PersistentMerkleTree, RetentionPolicy};
+
+    type PersistentBlakeMerkleTree = PersistentMerkleTree<[u8; 32], Blake3MerkleTreeHasher>;
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+    const UPDATED_VALUES: [&str; 9] = ["j", "k", "l", "m", "n", "o", "p", "q", "r"];
+
+    #[test]
+    fn same_root_as_merkle_tree_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let mut leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+            let updates = blake3_hash_leaf_values(&UPDATED_VALUES[..count]);
+            let mut tree =
+                PersistentBlakeMerkleTree::new(&leaves, RetentionPolicy::KeepAll).unwrap();
+            assert_eq!(BlakeMerkleTree::new(&leaves).unwrap().root(), tree.root());
+
+            for (idx, update) in updates.iter().enumerate() {
+                leaves[idx] = *update;
+                tree.update(idx, *update).unwrap();
+                let expected = BlakeMerkleTree::new(&leaves).unwrap();
+                assert_eq!(expected.root(), tree.root());
+                for proof_idx in 0..count {
+                    assert_eq!(
+                        expected.build_proof(proof_idx).unwrap(),
+                        tree.build_proof(proof_idx).unwrap()
+                    );
+                }
+            }
+            assert!(tree.update(count, updates[0]).is_err());
+        }
+    }
+
+    #[test]
+    fn old_versions_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let updates = blake3_hash_leaf_values(&UPDATED_VALUES);
+        let mut tree = PersistentBlakeMerkleTree::new(&leaves, RetentionPolicy::KeepAll).unwrap();
+        let first_root = tree.root();
+        assert_eq!(1, tree.update(4, updates[4]).unwrap());
+        assert_eq!(
+            2,
+            tree.update_leaves(&[(0, updates[0]), (8, updates[8])])
+                .unwrap()
+        );
+
+        let first = tree.version(0).unwrap();
+        assert_eq!(first_root, first.root());
+        let proof = first.build_proof(4).unwrap();
+        assert!(proof.verify(&leaves[4], &first_root));
+        assert!(!proof.verify(&updates[4], &first_root));
+
+        let latest = tree.latest();
+        assert_eq!(2, latest.version());
+        assert!(latest
+            .build_proof(8)
+            .unwrap()
+            .verify(&updates[8], &tree.root()));
+        assert!(latest
+            .build_proof(4)
+            .unwrap()
+            .verify(&updates[4], &tree.root()));
+        assert!(tree.version(3).is_none());
+        assert_eq!(vec![0, 1, 2], tree.versions().collect::<Vec<_>>());
+
+        assert!(tree
+            .update_leaves(&[(1, updates[1]), (9, updates[0])])
+            .is_err());
+        assert_eq!(2, tree.latest().version());
+    }
+
+    #[test]
+    fn structural_sharing_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..8]);
+        let mut tree = PersistentBlakeMerkleTree::new(&leaves, RetentionPolicy::KeepAll).unwrap();
+        tree.update(0, blake3_hash_leaf_values(&UPDATED_VALUES)[0])
+            .unwrap();
+
+        let old = &tree.version(0).unwrap().root;
+        let new = &tree.version(1).unwrap().root;
+        let (old_lhs, old_rhs) = old.children.as_ref().unwrap();
+        let (new_lhs, new_rhs) = new.children.as_ref().unwrap();
+        assert!(!Arc::ptr_eq(old_lhs, new_lhs));
+        assert!(Arc::ptr_eq(old_rhs, new_rhs));
+    }
+
+    #[test]
+    fn retention_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let updates = blake3_hash_leaf_values(&UPDATED_VALUES);
+        let mut tree =
+            PersistentBlakeMerkleTree::new(&leaves, RetentionPolicy::KeepLast(3)).unwrap();
+        let first = tree.latest().clone();
+        for (idx, update) in updates.iter().enumerate() {
+            tree.update(idx, *update).unwrap();
+        }
+        assert_eq!(vec![7, 8, 9], tree.versions().collect::<Vec<_>>());
+        assert!(tree.version(6).is_none());
+
+        // A version taken out of the tree outlives its retention.
+        assert!(first
+            .build_proof(0)
+            .unwrap()
+            .verify(&leaves[0], &first.root()));
+
+        tree.set_retention(RetentionPolicy::KeepLast(1)).unwrap();
+        assert_eq!(vec![9], tree.versions().collect::<Vec<_>>());
+        assert!(tree.set_retention(RetentionPolicy::KeepLast(0)).is_err());
+        assert!(PersistentBlakeMerkleTree::new(&leaves, RetentionPolicy::KeepLast(0)).is_err());
+        assert!(PersistentBlakeMerkleTree::new(&[], RetentionPolicy::KeepAll).is_err());
+    }
+
+    #[test]
+    fn rollback_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let updates = blake3_hash_leaf_values(&UPDATED_VALUES);
+        let mut tree = PersistentBlakeMerkleTree::new(&leaves, RetentionPolicy::KeepAll).unwrap();
+        for (idx, update) in updates.iter().enumerate().take(4) {
+            tree.update(idx, *update).unwrap();
+        }
+        let second_root = tree.version(2).unwrap().root();
+        tree.rollback(2).unwrap();
+        assert_eq!(second_root, tree.root());
+        assert_eq!(vec![0, 1, 2], tree.versions().collect::<Vec<_>>());
+        assert!(tree.rollback(3).is_err());
+
+        assert_eq!(3, tree.update(5, updates[5]).unwrap());
+        assert!(tree
+            .build_proof(5)
+            .unwrap()
+            .verify(&updates[5], &tree.root()));
+        assert!(tree
+            .build_proof(3)
+            .unwrap()
+            .verify(&leaves[3], &tree.root()));
+    }
+}
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::blake3_hash_leaf_values;
+    use crate::utils::count_tree_levels;
+    use crate::{count_tree_nodes, BlakeMerkleTree};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    #[test]
+    fn proofs_same_as_merkle_tree_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+            let tree = BlakeMerkleTree::new(&leaves).unwrap();
+            for level in 0..=count_tree_levels(count) {
+                let pruned = tree.clone().prune_below(level).unwrap();
+                assert_eq!(tree.root(), pruned.root());
+                assert_eq!(level, pruned.cap_level());
+                for (idx, leaf) in leaves.iter().enumerate() {
+                    let cap_idx = pruned.subtree_of(idx).unwrap();
+                    let range = pruned.subtree_range(cap_idx).unwrap();
+                    assert!(range.contains(&idx));
+                    let proof = pruned.build_proof(idx, &leaves[range]).unwrap();
+                    assert_eq!(tree.build_proof(idx).unwrap(), proof);
+                    assert!(proof.verify(leaf, &tree.root()));
+                }
+                assert!(pruned.subtree_of(count).is_err());
+                assert!(pruned
+                    .subtree_range(pruned.cap_count() - 1)
+                    .is_ok_and(|range| range.end == count));
+                assert!(pruned.subtree_range(pruned.cap_count()).is_err());
+            }
+            assert!(tree.prune_below(count_tree_levels(count) + 1).is_err());
+        }
+    }
+
+    #[test]
+    fn wrong_subtree_leaves_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let pruned = BlakeMerkleTree::new(&leaves)
+            .unwrap()
+            .prune_below(2)
+            .unwrap();
+        assert_eq!(4..8, pruned.subtree_range(1).unwrap());
+        assert!(pruned.build_proof(5, &leaves[4..8]).is_ok());
+        assert!(pruned.build_proof(5, &leaves[0..4]).is_err());
+        assert!(pruned.build_proof(5, &leaves[4..7]).is_err());
+        let mut changed = leaves.clone();
+        changed.swap(4, 6);
+        assert!(pruned.build_proof(5, &changed[4..8]).is_err());
+    }
+
+    #[test]
+    fn keeps_upper_levels_only_test() {
+        let leaves: Vec<[u8; 32]> = (0..1000_u32)
+            .map(|idx| blake3::hash(&idx.to_le_bytes()).into())
+            .collect();
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        let root = tree.root();
+        let pruned = tree.prune_below(6).unwrap();
+        assert_eq!(16, pruned.cap_count());
+        assert_eq!(count_tree_nodes(16), pruned.node_count());
+        assert_eq!(root, pruned.root());
+        assert_eq!(960..1000, pruned.subtree_range(15).unwrap());
+        let proof = pruned.build_proof(999, &leaves[960..]).unwrap();
+        assert!(proof.verify(&leaves[999], &root));
+    }
+}
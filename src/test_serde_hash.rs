@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::algos::blake3_hash_leaf_values;
+    use crate::algos::BlakeMerkleTree;
+    use crate::serde_hash::{Base64Encoded, HexEncoded};
+
+    const SINGLE_CHAR_VALUES: [&str; 6] = ["a", "b", "c", "d", "e", "f"];
+
+    #[derive(Debug, Deserialize, PartialEq, Serialize)]
+    struct Record {
+        #[serde(with = "crate::serde_hash::hex")]
+        root: [u8; 32],
+        #[serde(with = "crate::serde_hash::base64_seq")]
+        leaves: Vec<[u8; 32]>,
+    }
+
+    #[test]
+    fn hex_merkle_tree_serde_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let json = serde_json::to_string(&HexEncoded::new(tree.clone())).unwrap();
+        assert!(json.contains(&rustc_serialize::hex::ToHex::to_hex(&tree.root()[..])));
+        let tree_de: HexEncoded<BlakeMerkleTree> = serde_json::from_str(&json).unwrap();
+        assert_eq!(tree, tree_de.into_inner());
+    }
+
+    #[test]
+    fn base64_merkle_tree_serde_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let json = serde_json::to_string(&Base64Encoded::new(tree.clone())).unwrap();
+        let tree_de: Base64Encoded<BlakeMerkleTree> = serde_json::from_str(&json).unwrap();
+        assert_eq!(tree, tree_de.into_inner());
+    }
+
+    #[test]
+    fn encoded_reads_derived_json_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        let json = serde_json::to_string(&tree).unwrap();
+        let tree_de: HexEncoded<BlakeMerkleTree> = serde_json::from_str(&json).unwrap();
+        assert_eq!(tree, tree_de.into_inner());
+
+        let hex_json = serde_json::to_string(&HexEncoded::new(tree.clone())).unwrap();
+        let tree_de: Base64Encoded<BlakeMerkleTree> = serde_json::from_str(&hex_json).unwrap();
+        assert_eq!(tree, tree_de.into_inner());
+    }
+
+    #[test]
+    fn hex_merkle_proof_serde_test() {
+        let tree = BlakeMerkleTree::new(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap();
+        for idx in 0..SINGLE_CHAR_VALUES.len() {
+            let proof = tree.build_proof(idx).unwrap();
+            let json = serde_json::to_string(&HexEncoded::new(proof.clone())).unwrap();
+            let proof_de: Base64Encoded<_> = serde_json::from_str(&json).unwrap();
+            assert_eq!(proof, proof_de.into_inner());
+
+            let json = serde_json::to_string(&proof).unwrap();
+            let proof_de: HexEncoded<_> = serde_json::from_str(&json).unwrap();
+            assert_eq!(proof, proof_de.into_inner());
+        }
+    }
+
+    #[test]
+    fn serde_with_adapters_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        let record = Record {
+            root: tree.root(),
+            leaves,
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        let record_de: Record = serde_json::from_str(&json).unwrap();
+        assert_eq!(record, record_de);
+    }
+
+    #[test]
+    fn serde_hash_wrong_length_test() {
+        let json = "\"00ff\"";
+        assert!(serde_json::from_str::<HexEncoded<[u8; 32]>>(json).is_err());
+        let json = "[1, 2, 3]";
+        assert!(serde_json::from_str::<HexEncoded<[u8; 32]>>(json).is_err());
+        let json = "\"not a hash\"";
+        assert!(serde_json::from_str::<HexEncoded<[u8; 32]>>(json).is_err());
+    }
+
+    #[test]
+    fn serde_hash_hex_or_base64_test() {
+        // "abcd" is valid hex and valid base64 for two bytes, hex wins.
+        let hash: Base64Encoded<[u8; 2]> = serde_json::from_str("\"abcd\"").unwrap();
+        assert_eq!([0xab, 0xcd], hash.into_inner());
+        let hash: HexEncoded<[u8; 3]> = serde_json::from_str("\"abcd\"").unwrap();
+        assert_eq!([0x69, 0xb7, 0x1d], hash.into_inner());
+    }
+}
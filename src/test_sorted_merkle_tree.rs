@@ -0,0 +1,170 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::{NonMembershipProof, SortedMerkleTree};
+
+    type SortedBlakeMerkleTree = SortedMerkleTree<[u8; 32], Blake3MerkleTreeHasher>;
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+    const ABSENT_VALUES: [&str; 5] = ["j", "k", "l", "m", "n"];
+
+    fn sorted_tree() -> SortedBlakeMerkleTree {
+        SortedMerkleTree::from_unsorted(&blake3_hash_leaf_values(&SINGLE_CHAR_VALUES)).unwrap()
+    }
+
+    #[test]
+    fn new_rejects_unsorted_leaves_test() {
+        let mut leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        leaves.sort();
+        assert!(SortedBlakeMerkleTree::new(&leaves).is_ok());
+        assert!(SortedBlakeMerkleTree::new(&[]).is_err());
+
+        leaves.swap(2, 5);
+        assert!(SortedBlakeMerkleTree::new(&leaves).is_err());
+
+        leaves.swap(2, 5);
+        leaves[3] = leaves[4];
+        assert!(SortedBlakeMerkleTree::new(&leaves).is_err());
+    }
+
+    #[test]
+    fn from_unsorted_test() {
+        let mut leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        leaves.extend(blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..3]));
+        let tree = SortedBlakeMerkleTree::from_unsorted(&leaves).unwrap();
+        assert_eq!(SINGLE_CHAR_VALUES.len(), tree.num_leaves());
+        assert_eq!(sorted_tree().root(), tree.root());
+        assert!(tree.leaves().windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn inclusion_proof_test() {
+        let tree = sorted_tree();
+        for leaf in blake3_hash_leaf_values(&SINGLE_CHAR_VALUES) {
+            let idx = tree.position_of(&leaf).unwrap();
+            assert!(tree.contains(&leaf));
+            assert!(tree.build_proof(idx).unwrap().verify(&leaf, &tree.root()));
+            assert!(tree.build_non_membership_proof(&leaf).is_err());
+        }
+    }
+
+    #[test]
+    fn non_membership_proof_test() {
+        let tree = sorted_tree();
+        let mut values = blake3_hash_leaf_values(&ABSENT_VALUES);
+        values.push([0; 32]);
+        values.push([0xff; 32]);
+        for value in values {
+            assert!(!tree.contains(&value));
+            let proof = tree.build_non_membership_proof(&value).unwrap();
+            assert!(proof.validate_proof(&value, tree.num_leaves()));
+            assert!(proof.verify(&value, &tree.root(), tree.num_leaves()));
+            assert!(!proof.verify(&value, &value, tree.num_leaves()));
+        }
+
+        let below = tree.build_non_membership_proof(&[0; 32]).unwrap();
+        assert!(below.lower().is_none());
+        assert_eq!(0, below.upper().unwrap().1.leaf_index());
+        let above = tree.build_non_membership_proof(&[0xff; 32]).unwrap();
+        assert!(above.upper().is_none());
+        assert_eq!(tree.num_leaves() - 1, above.lower().unwrap().1.leaf_index());
+    }
+
+    #[test]
+    fn non_membership_proof_single_leaf_test() {
+        let leaf = [0x80; 32];
+        let tree = SortedBlakeMerkleTree::new(&[leaf]).unwrap();
+        for value in [[0; 32], [0xff; 32]] {
+            let proof = tree.build_non_membership_proof(&value).unwrap();
+            assert!(proof.verify(&value, &tree.root(), tree.num_leaves()));
+        }
+    }
+
+    #[test]
+    fn non_membership_proof_failure_test() {
+        let tree = sorted_tree();
+        let leaves = tree.leaves().to_vec();
+        let value = blake3_hash_leaf_values(&ABSENT_VALUES)[0];
+        let proof = tree.build_non_membership_proof(&value).unwrap();
+
+        // The proof does not cover values outside its bracket nor the leaves themselves.
+        for leaf in &leaves {
+            assert!(!proof.validate_proof(leaf, tree.num_leaves()));
+        }
+
+        // Leaves that are not adjacent do not prove anything.
+        let gapped = NonMembershipProof {
+            lower: Some((leaves[0], tree.build_proof(0).unwrap())),
+            upper: Some((leaves[2], tree.build_proof(2).unwrap())),
+        };
+        assert!(!gapped.validate_proof(&leaves[1], tree.num_leaves()));
+
+        // Dropping a side is only accepted at the ends of the tree.
+        let open_upper = NonMembershipProof {
+            lower: Some((leaves[3], tree.build_proof(3).unwrap())),
+            upper: None,
+        };
+        assert!(!open_upper.validate_proof(&[0xff; 32], tree.num_leaves()));
+        let open_lower = NonMembershipProof {
+            lower: None,
+            upper: Some((leaves[3], tree.build_proof(3).unwrap())),
+        };
+        assert!(!open_lower.validate_proof(&[0; 32], tree.num_leaves()));
+
+        // Leaves from another tree do not match the trusted root.
+        let other =
+            SortedBlakeMerkleTree::from_unsorted(&blake3_hash_leaf_values(&["x", "y"])).unwrap();
+        let foreign = other.build_non_membership_proof(&value).unwrap();
+        if foreign.validate_proof(&value, other.num_leaves()) {
+            assert!(!foreign.verify(&value, &tree.root(), tree.num_leaves()));
+        }
+
+        let empty: NonMembershipProof<[u8; 32], Blake3MerkleTreeHasher> = NonMembershipProof {
+            lower: None,
+            upper: None,
+        };
+        assert!(!empty.validate_proof(&value, tree.num_leaves()));
+
+        // A leaf proof without its root is refused rather than read out of bounds.
+        let mut rootless = proof.clone();
+        for (_, leaf_proof) in rootless.lower.iter_mut().chain(rootless.upper.iter_mut()) {
+            leaf_proof.sibling_hashes.clear();
+        }
+        assert_eq!(None, rootless.root());
+        assert!(!rootless.validate_proof(&value, tree.num_leaves()));
+        assert!(!rootless.verify(&value, &tree.root(), tree.num_leaves()));
+    }
+
+    #[test]
+    fn non_membership_proof_serde_test() {
+        let tree = sorted_tree();
+        let value = blake3_hash_leaf_values(&ABSENT_VALUES)[1];
+        let proof = tree.build_non_membership_proof(&value).unwrap();
+        let json = serde_json::to_string(&proof).unwrap();
+        let proof_de: NonMembershipProof<[u8; 32], Blake3MerkleTreeHasher> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(proof, proof_de);
+        assert!(proof_de.verify(&value, &tree.root(), tree.num_leaves()));
+    }
+
+    /// Lowering the number of leaves of a proof must not turn a middle leaf into the last one.
+    #[test]
+    fn non_membership_proof_forged_num_leaves_test() {
+        let tree = SortedBlakeMerkleTree::from_unsorted(&blake3_hash_leaf_values(
+            &SINGLE_CHAR_VALUES[..8],
+        ))
+        .unwrap();
+        let leaves = tree.leaves().to_vec();
+        let root = tree.root();
+
+        let mut forged = tree.build_proof(3).unwrap();
+        forged.num_leaves = 4;
+        let proof = NonMembershipProof {
+            lower: Some((leaves[3], forged)),
+            upper: None,
+        };
+        assert!(!proof.verify(&leaves[5], &root, tree.num_leaves()));
+        // Trusting the forged number of leaves still fails, the proof has one sibling too many.
+        assert!(!proof.verify(&leaves[5], &root, 4));
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use anyhow::Result;
+
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::node_store::{FileNodeStore, MemoryNodeStore, NodeStore};
+    use crate::test_utils::TempPath;
+    use crate::{BlakeMerkleTree, StoredMerkleTree};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    /// Counts the nodes read from a [MemoryNodeStore].
+    #[derive(Default)]
+    struct CountingNodeStore {
+        store: MemoryNodeStore<[u8; 32]>,
+        reads: Cell<usize>,
+    }
+
+    impl NodeStore<[u8; 32]> for CountingNodeStore {
+        fn get(&self, level: usize, idx: usize) -> Result<[u8; 32]> {
+            self.reads.set(self.reads.get() + 1);
+            self.store.get(level, idx)
+        }
+
+        fn put(&mut self, level: usize, idx: usize, node: [u8; 32]) -> Result<()> {
+            self.store.put(level, idx, node)
+        }
+    }
+
+    #[test]
+    fn memory_store_same_as_merkle_tree_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+            let expected = BlakeMerkleTree::new(&leaves).unwrap();
+            let tree: StoredMerkleTree<_, Blake3MerkleTreeHasher, _> =
+                StoredMerkleTree::new(MemoryNodeStore::new(), leaves.iter().copied()).unwrap();
+            assert_eq!(expected.root(), tree.root().unwrap());
+            for idx in 0..count {
+                assert_eq!(
+                    expected.build_proof(idx).unwrap(),
+                    tree.build_proof(idx).unwrap()
+                );
+            }
+            assert!(tree.build_proof(count).is_err());
+        }
+        let empty: Vec<[u8; 32]> = Vec::new();
+        assert!(StoredMerkleTree::<_, Blake3MerkleTreeHasher, _>::new(
+            MemoryNodeStore::new(),
+            empty
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn proof_reads_log_n_nodes_test() {
+        let leaves: Vec<[u8; 32]> = (0..1000_u32)
+            .map(|idx| blake3::hash(&idx.to_le_bytes()).into())
+            .collect();
+        let tree: StoredMerkleTree<_, Blake3MerkleTreeHasher, _> =
+            StoredMerkleTree::new(CountingNodeStore::default(), leaves.iter().copied()).unwrap();
+        let root = tree.root().unwrap();
+        tree.store().reads.set(0);
+        let proof = tree.build_proof(617).unwrap();
+        assert!(proof.verify(&leaves[617], &root));
+        // The leaf and one sibling for each of the 10 levels.
+        assert_eq!(11, tree.store().reads.get());
+    }
+
+    #[test]
+    fn file_store_test() {
+        let path = TempPath::new("stored_merkle_tree", "file_store.nodes");
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let expected = BlakeMerkleTree::new(&leaves).unwrap();
+
+        let store = FileNodeStore::create(&path.0, leaves.len()).unwrap();
+        let tree: StoredMerkleTree<_, Blake3MerkleTreeHasher, _> =
+            StoredMerkleTree::new(store, leaves.iter().copied()).unwrap();
+        assert_eq!(expected.root(), tree.root().unwrap());
+        assert!(tree.store().is_complete());
+        drop(tree);
+
+        let store = FileNodeStore::<[u8; 32]>::open(&path.0).unwrap();
+        let num_leaves = store.num_leaves();
+        let tree: StoredMerkleTree<_, Blake3MerkleTreeHasher, _> =
+            StoredMerkleTree::from_store(store, num_leaves).unwrap();
+        assert_eq!(expected.root(), tree.root().unwrap());
+        for (idx, leaf) in leaves.iter().enumerate() {
+            assert!(tree
+                .build_proof(idx)
+                .unwrap()
+                .verify(leaf, &expected.root()));
+        }
+
+        // A store which was not finished has no root.
+        let mut store = FileNodeStore::create(&path.0, leaves.len()).unwrap();
+        store.put(0, 0, leaves[0]).unwrap();
+        store.commit().unwrap();
+        assert!(StoredMerkleTree::<_, Blake3MerkleTreeHasher, _>::from_store(store, 9).is_err());
+    }
+}
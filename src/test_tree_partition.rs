@@ -0,0 +1,110 @@
+#[cfg(test)]
+mod tests {
+    use crate::algos::{blake3_hash_leaf_values, Blake3MerkleTreeHasher};
+    use crate::utils::count_tree_levels;
+    use crate::{BlakeMerkleTree, MerkleSubtree, PrunedMerkleTree, TreePartition};
+
+    const SINGLE_CHAR_VALUES: [&str; 9] = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+
+    type Subtree = MerkleSubtree<[u8; 32], Blake3MerkleTreeHasher>;
+
+    fn build_subtrees(partition: &TreePartition, leaves: &[[u8; 32]]) -> Vec<Subtree> {
+        (0..partition.len())
+            .map(|idx| {
+                let range = partition.subtree_range(idx).unwrap();
+                partition.build_subtree(idx, &leaves[range]).unwrap()
+            })
+            .collect()
+    }
+
+    fn combine(
+        partition: &TreePartition,
+        subtrees: &[Subtree],
+    ) -> PrunedMerkleTree<[u8; 32], Blake3MerkleTreeHasher> {
+        let roots: Vec<[u8; 32]> = subtrees.iter().map(|subtree| subtree.root()).collect();
+        partition.combine(&roots).unwrap()
+    }
+
+    #[test]
+    fn combined_same_as_merkle_tree_test() {
+        for count in 1..=SINGLE_CHAR_VALUES.len() {
+            let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES[..count]);
+            let tree = BlakeMerkleTree::new(&leaves).unwrap();
+            for level in 0..=count_tree_levels(count) {
+                let partition = TreePartition::new(count, level).unwrap();
+                let subtrees = build_subtrees(&partition, &leaves);
+                let cap = combine(&partition, &subtrees);
+                assert_eq!(tree.root(), cap.root());
+
+                for (idx, leaf) in leaves.iter().enumerate() {
+                    let subtree = &subtrees[partition.subtree_of(idx).unwrap()];
+                    assert!(subtree.leaf_range().contains(&idx));
+                    let proof = cap
+                        .stitch_proof(&subtree.build_proof(idx).unwrap())
+                        .unwrap();
+                    assert_eq!(tree.build_proof(idx).unwrap(), proof);
+                    assert!(proof.verify(leaf, &tree.root()));
+                }
+                assert!(subtrees[0].build_proof(count).is_err());
+            }
+            assert!(TreePartition::new(count, count_tree_levels(count) + 1).is_err());
+        }
+    }
+
+    #[test]
+    fn matches_pruned_tree_test() {
+        let leaves: Vec<[u8; 32]> = (0..1000_u32)
+            .map(|idx| blake3::hash(&idx.to_le_bytes()).into())
+            .collect();
+        let tree = BlakeMerkleTree::new(&leaves).unwrap();
+        let partition = TreePartition::with_max_subtrees(leaves.len(), 10).unwrap();
+        assert_eq!(7, partition.level());
+        assert_eq!(8, partition.len());
+        assert_eq!(896..1000, partition.subtree_range(7).unwrap());
+
+        let subtrees = build_subtrees(&partition, &leaves);
+        let cap = combine(&partition, &subtrees);
+        let pruned = tree.clone().prune_below(partition.level()).unwrap();
+        assert_eq!(pruned.tree, cap.tree);
+        assert_eq!(partition, pruned.partition());
+
+        let proof = pruned
+            .stitch_proof(&subtrees[7].build_proof(999).unwrap())
+            .unwrap();
+        assert_eq!(tree.build_proof(999).unwrap(), proof);
+    }
+
+    #[test]
+    fn mismatched_parts_test() {
+        let leaves = blake3_hash_leaf_values(&SINGLE_CHAR_VALUES);
+        let partition = TreePartition::new(leaves.len(), 2).unwrap();
+        assert!(partition
+            .build_subtree::<_, Blake3MerkleTreeHasher>(1, &leaves[4..7])
+            .is_err());
+        assert!(partition
+            .build_subtree::<_, Blake3MerkleTreeHasher>(3, &leaves[8..])
+            .is_err());
+
+        let subtrees = build_subtrees(&partition, &leaves);
+        let roots: Vec<[u8; 32]> = subtrees.iter().map(|subtree| subtree.root()).collect();
+        assert!(partition
+            .combine::<_, Blake3MerkleTreeHasher>(&roots[..2])
+            .is_err());
+        let cap = combine(&partition, &subtrees);
+
+        // A subtree built from other leaves does not lead to the combined root.
+        let mut changed = leaves.clone();
+        changed.swap(4, 6);
+        let changed_subtree: Subtree = partition.build_subtree(1, &changed[4..8]).unwrap();
+        assert!(cap
+            .stitch_proof(&changed_subtree.build_proof(5).unwrap())
+            .is_err());
+
+        // Proofs from subtrees of another level have the wrong number of siblings.
+        let other = TreePartition::new(leaves.len(), 1).unwrap();
+        let other_subtree: Subtree = other.build_subtree(2, &leaves[4..6]).unwrap();
+        assert!(cap
+            .stitch_proof(&other_subtree.build_proof(5).unwrap())
+            .is_err());
+    }
+}
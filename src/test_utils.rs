@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+/// A file in the temporary directory, removed when dropped.
+///
+/// The file name starts with `prefix` and the process id, so concurrent test runs do not collide.
+pub(crate) struct TempPath(pub(crate) PathBuf);
+
+impl TempPath {
+    pub(crate) fn new(prefix: &str, name: &str) -> TempPath {
+        TempPath(std::env::temp_dir().join(format!("{}_{}_{}", prefix, std::process::id(), name)))
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
@@ -0,0 +1,298 @@
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+use anyhow::{ensure, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{build_levels, count_tree_levels, next_level_prefix, node_prefix};
+use crate::{add_1_if_odd, is_odd, MerkleTreeHasher, PrunedMerkleTree};
+
+/// Splits the leaves of a Merkle Tree into aligned subtrees of `2^level` leaves, to build them
+/// on different workers.
+///
+/// Subtree `idx` holds the leaves `idx * 2^level..(idx + 1) * 2^level`, the last one fewer.
+/// Every subtree is the part of the full tree below one node of `level`, so its nodes use the
+/// level prefixes and odd node duplication of the full tree. That is why subtrees are built with
+/// [`build_subtree`](TreePartition::build_subtree) rather than [MerkleTree::new](crate::MerkleTree::new),
+/// whose prefixes only match when no level below `level` has an odd number of nodes.
+/// [`combine`](TreePartition::combine) turns the subtree roots into the upper levels of the tree.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct TreePartition {
+    pub(crate) num_leaves: usize,
+    pub(crate) level: usize,
+}
+
+impl TreePartition {
+    /// Splits `num_leaves` leaves into subtrees of `2^level` leaves.
+    ///
+    /// Fails if there are no leaves or `level` is above the root.
+    pub fn new(num_leaves: usize, level: usize) -> Result<TreePartition> {
+        ensure!(num_leaves > 0, "a Merkle Tree needs at least one leaf");
+        let levels = count_tree_levels(num_leaves);
+        ensure!(
+            level <= levels,
+            "cannot split below level {} of a tree with {} levels above the leaves",
+            level,
+            levels
+        );
+        Ok(TreePartition { num_leaves, level })
+    }
+
+    /// Splits `num_leaves` leaves into the smallest subtrees giving at most `max_subtrees` of them.
+    pub fn with_max_subtrees(num_leaves: usize, max_subtrees: usize) -> Result<TreePartition> {
+        ensure!(max_subtrees > 0, "at least one subtree is needed");
+        let mut partition = TreePartition::new(num_leaves, 0)?;
+        while partition.len() > max_subtrees {
+            partition.level += 1;
+        }
+        Ok(partition)
+    }
+
+    /// Returns the range of leaf indices of the subtree at `idx`.
+    pub fn subtree_range(&self, idx: usize) -> Result<Range<usize>> {
+        ensure!(
+            idx < self.len(),
+            "subtree {} is out of range for {} subtrees",
+            idx,
+            self.len()
+        );
+        let start = idx << self.level;
+        let end = ((idx + 1) << self.level).min(self.num_leaves);
+        Ok(start..end)
+    }
+
+    /// Returns the index of the subtree holding the leaf at `leaf_index`.
+    pub fn subtree_of(&self, leaf_index: usize) -> Result<usize> {
+        ensure!(
+            leaf_index < self.num_leaves,
+            "leaf index {} is out of range for {} leaves",
+            leaf_index,
+            self.num_leaves
+        );
+        Ok(leaf_index >> self.level)
+    }
+
+    /// Builds the subtree at `idx` from its `leaves`, the leaves in [`subtree_range(idx)`](TreePartition::subtree_range).
+    pub fn build_subtree<T, H>(&self, idx: usize, leaves: &[T]) -> Result<MerkleSubtree<T, H>>
+    where
+        T: AsRef<[u8]> + Copy,
+        H: Default + MerkleTreeHasher<T>,
+    {
+        let range = self.subtree_range(idx)?;
+        ensure!(
+            leaves.len() == range.len(),
+            "subtree {} has {} leaves, found {}",
+            idx,
+            range.len(),
+            leaves.len()
+        );
+
+        let wrap_to_value = H::wrap_to_value();
+        let mut level_prefix = H::non_leaf_node_starting_prefix()[0];
+        let mut level_count = self.num_leaves;
+        let mut first_idx = range.start;
+        let mut nodes: Vec<T> = leaves.iter().map(H::hash_leaf).collect();
+        let mut level_start = 0;
+        let mut subtree_count = leaves.len();
+
+        for _ in 0..self.level {
+            for local_idx in (0..subtree_count).step_by(2) {
+                // The last node of an odd level is hashed with itself.
+                let rhs_idx = (local_idx + 1).min(subtree_count - 1);
+                let prefix = [node_prefix(
+                    level_prefix,
+                    level_count,
+                    first_idx + local_idx,
+                    wrap_to_value,
+                )];
+                nodes.push(H::hash_non_leaf_node(
+                    &prefix,
+                    &nodes[level_start + local_idx],
+                    &nodes[level_start + rhs_idx],
+                ));
+            }
+
+            level_prefix = next_level_prefix(level_prefix, level_count, wrap_to_value);
+            level_start += subtree_count;
+            first_idx /= 2;
+            subtree_count = add_1_if_odd(subtree_count) / 2;
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        Ok(MerkleSubtree {
+            partition: *self,
+            idx,
+            nodes,
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Hashes the roots of every subtree, in order, into the levels above them.
+    ///
+    /// The root of the result is the root [MerkleTree::new](crate::MerkleTree::new) builds from all leaves.
+    pub fn combine<T, H>(&self, roots: &[T]) -> Result<PrunedMerkleTree<T, H>>
+    where
+        T: AsRef<[u8]> + Copy,
+        H: Default + MerkleTreeHasher<T>,
+    {
+        ensure!(
+            roots.len() == self.len(),
+            "expected {} subtree roots, found {}",
+            self.len(),
+            roots.len()
+        );
+
+        let wrap_to_value = H::wrap_to_value();
+        let mut level_prefix = H::non_leaf_node_starting_prefix()[0];
+        let mut level_count = self.num_leaves;
+        for _ in 0..self.level {
+            level_prefix = next_level_prefix(level_prefix, level_count, wrap_to_value);
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+
+        let mut tree = roots.to_vec();
+        build_levels(
+            &mut tree,
+            level_count,
+            level_prefix,
+            wrap_to_value,
+            |prefix, lhs, rhs| Ok::<T, Infallible>(H::hash_non_leaf_node(prefix, lhs, rhs)),
+        )
+        .unwrap_or_else(|never| match never {});
+
+        Ok(PrunedMerkleTree {
+            num_leaves: self.num_leaves,
+            cap_level: self.level,
+            tree,
+            hash_name: H::default().name(),
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Returns the number of subtrees.
+    pub fn len(&self) -> usize {
+        let mut level_count = self.num_leaves;
+        for _ in 0..self.level {
+            level_count = add_1_if_odd(level_count) / 2;
+        }
+        level_count
+    }
+
+    /// Always false, as there is at least one subtree.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Returns the level of the subtree roots, 0 being the leaves.
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Returns the number of leaves of the whole tree.
+    pub fn num_leaves(&self) -> usize {
+        self.num_leaves
+    }
+}
+
+/// The nodes of one subtree of a [TreePartition], in level order from its leaves to its root.
+///
+/// Created by [`TreePartition::build_subtree`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct MerkleSubtree<T: Copy + Sized, H: MerkleTreeHasher<T>> {
+    partition: TreePartition,
+    idx: usize,
+    nodes: Vec<T>,
+    _dummy: PhantomData<H>,
+}
+
+impl<T, H> MerkleSubtree<T, H>
+where
+    T: AsRef<[u8]> + Copy,
+    H: Default + MerkleTreeHasher<T>,
+{
+    /// Builds the [SubtreeProof] for the leaf at `leaf_index` of the whole tree.
+    ///
+    /// [`PrunedMerkleTree::stitch_proof`] extends it into a [MerkleProof](crate::MerkleProof).
+    pub fn build_proof(&self, leaf_index: usize) -> Result<SubtreeProof<T, H>> {
+        let range = self.leaf_range();
+        ensure!(
+            range.contains(&leaf_index),
+            "leaf index {} is not in subtree {}",
+            leaf_index,
+            self.idx
+        );
+
+        let mut siblings = Vec::with_capacity(self.partition.level);
+        let mut level_start = 0;
+        let mut subtree_count = range.len();
+        let mut current_idx = leaf_index - range.start;
+
+        for _ in 0..self.partition.level {
+            let sibling_idx = if is_odd(current_idx) {
+                current_idx - 1
+            } else {
+                (current_idx + 1).min(subtree_count - 1)
+            };
+            siblings.push(self.nodes[level_start + sibling_idx]);
+
+            current_idx /= 2;
+            level_start += subtree_count;
+            subtree_count = add_1_if_odd(subtree_count) / 2;
+        }
+
+        Ok(SubtreeProof {
+            leaf_index,
+            leaf_hash: self.nodes[leaf_index - range.start],
+            siblings,
+            _dummy: Default::default(),
+        })
+    }
+
+    /// Returns the range of leaf indices of this subtree.
+    pub fn leaf_range(&self) -> Range<usize> {
+        let start = self.idx << self.partition.level;
+        let end = ((self.idx + 1) << self.partition.level).min(self.partition.num_leaves);
+        start..end
+    }
+
+    /// Returns the index of this subtree within its [TreePartition].
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Returns the [TreePartition] this subtree belongs to.
+    pub fn partition(&self) -> &TreePartition {
+        &self.partition
+    }
+
+    /// Returns the root of this subtree, a node of the whole tree.
+    pub fn root(&self) -> T {
+        self.nodes[self.nodes.len() - 1]
+    }
+}
+
+/// The part of a [MerkleProof](crate::MerkleProof) below the root of a [MerkleSubtree].
+///
+/// Created by [`MerkleSubtree::build_proof`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
+pub struct SubtreeProof<T: Copy + Sized, H: MerkleTreeHasher<T>> {
+    pub(crate) leaf_index: usize,
+    pub(crate) leaf_hash: T,
+    pub(crate) siblings: Vec<T>,
+    pub(crate) _dummy: PhantomData<H>,
+}
+
+impl<T: Copy, H: MerkleTreeHasher<T>> SubtreeProof<T, H> {
+    /// Returns the index of the proven leaf within the whole tree.
+    pub fn leaf_index(&self) -> usize {
+        self.leaf_index
+    }
+
+    /// Returns the sibling hashes from the leaf level up to below the subtree root.
+    pub fn siblings(&self) -> &[T] {
+        &self.siblings
+    }
+}
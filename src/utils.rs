@@ -23,16 +23,224 @@ pub(crate) fn count_tree_nodes(num_leaves: usize) -> usize {
     let mut count = num_leaves;
     let mut level_count = num_leaves;
     while level_count > 1 {
-        let prev_level_count = level_count;
-        level_count /= 2;
-        if prev_level_count != 2 {
-            level_count = add_1_if_odd(level_count);
-        }
+        level_count = add_1_if_odd(level_count) / 2;
         count += level_count;
     }
     count
 }
 
+/// Given the number of leaves count the number of non-leaf levels in the Merkle Tree.
+#[inline]
+#[doc(hidden)]
+pub(crate) fn count_tree_levels(num_leaves: usize) -> usize {
+    let mut levels = 0;
+    let mut level_count = num_leaves;
+    while level_count > 1 {
+        level_count = add_1_if_odd(level_count) / 2;
+        levels += 1;
+    }
+    levels
+}
+
+/// The prefix used to hash the parent of the node at `idx` on a level holding `level_count` nodes.
+///
+/// The last node of an odd level is hashed with itself using an incremented prefix.
+#[inline]
+#[doc(hidden)]
+pub(crate) fn node_prefix(
+    level_prefix: u8,
+    level_count: usize,
+    idx: usize,
+    wrap_to_value: u8,
+) -> u8 {
+    if is_odd(level_count) && idx == level_count - 1 {
+        increment_or_wrap_around(level_prefix, wrap_to_value)
+    } else {
+        level_prefix
+    }
+}
+
+/// The prefix used for the level above a level holding `level_count` nodes.
+#[inline]
+#[doc(hidden)]
+pub(crate) fn next_level_prefix(level_prefix: u8, level_count: usize, wrap_to_value: u8) -> u8 {
+    let prefix = if is_odd(level_count) {
+        increment_or_wrap_around(level_prefix, wrap_to_value)
+    } else {
+        level_prefix
+    };
+    increment_or_wrap_around(prefix, 1)
+}
+
+/// A non-leaf node of a tree, as visited by [for_each_parent].
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct ParentNode {
+    /// The level of the children, 0 being the first level of the walk.
+    pub(crate) level: usize,
+    /// The level-ordered index of the first node of the children's level.
+    pub(crate) level_start: usize,
+    /// The index of the left-hand child on its level.
+    pub(crate) lhs_idx: usize,
+    /// The index of the right-hand child on its level, `lhs_idx` for the last node of an odd level.
+    pub(crate) rhs_idx: usize,
+    /// The level-ordered index of the parent itself.
+    pub(crate) parent: usize,
+    /// The prefix the parent is hashed with.
+    pub(crate) prefix: [u8; 1],
+}
+
+impl ParentNode {
+    /// The level-ordered index of the left-hand child.
+    pub(crate) fn lhs(&self) -> usize {
+        self.level_start + self.lhs_idx
+    }
+
+    /// The level-ordered index of the right-hand child.
+    pub(crate) fn rhs(&self) -> usize {
+        self.level_start + self.rhs_idx
+    }
+
+    /// The index of the parent on its level.
+    pub(crate) fn idx(&self) -> usize {
+        self.lhs_idx / 2
+    }
+
+    /// True for the parent of the last node of an odd level, which is hashed with itself.
+    pub(crate) fn is_odd_last(&self) -> bool {
+        self.lhs_idx == self.rhs_idx
+    }
+}
+
+/// Visits every non-leaf node above a level of `level_count` nodes, level by level, in the
+/// level order of [MerkleTree](crate::MerkleTree), so the children of a node are always visited first.
+///
+/// `starting_prefix` is the prefix of the first level, usually the hasher's non-leaf starting
+/// prefix with the leaves as the first level. Stops at the first error returned by `visit`.
+#[doc(hidden)]
+pub(crate) fn for_each_parent<E, F>(
+    level_count: usize,
+    starting_prefix: u8,
+    wrap_to_value: u8,
+    mut visit: F,
+) -> Result<(), E>
+where
+    F: FnMut(&ParentNode) -> Result<(), E>,
+{
+    let mut level_prefix = starting_prefix;
+    let mut level_count = level_count;
+    let mut level_start = 0;
+    let mut level = 0;
+
+    while level_count > 1 {
+        let next_level_start = level_start + level_count;
+        for idx in (0..level_count).step_by(2) {
+            visit(&ParentNode {
+                level,
+                level_start,
+                lhs_idx: idx,
+                rhs_idx: (idx + 1).min(level_count - 1),
+                parent: next_level_start + idx / 2,
+                prefix: [node_prefix(level_prefix, level_count, idx, wrap_to_value)],
+            })?;
+        }
+
+        level_prefix = next_level_prefix(level_prefix, level_count, wrap_to_value);
+        level_start = next_level_start;
+        level += 1;
+        level_count = add_1_if_odd(level_count) / 2;
+    }
+    Ok(())
+}
+
+/// Appends the non-leaf nodes above the `level_count` nodes `tree` starts with, see [for_each_parent].
+///
+/// `hash_node` is called with the level prefix, the left-hand and the right-hand node.
+#[doc(hidden)]
+pub(crate) fn build_levels<T, E, F>(
+    tree: &mut Vec<T>,
+    level_count: usize,
+    starting_prefix: u8,
+    wrap_to_value: u8,
+    mut hash_node: F,
+) -> Result<(), E>
+where
+    F: FnMut(&[u8; 1], &T, &T) -> Result<T, E>,
+{
+    for_each_parent(level_count, starting_prefix, wrap_to_value, |node| {
+        let hash = hash_node(&node.prefix, &tree[node.lhs()], &tree[node.rhs()])?;
+        tree.push(hash);
+        Ok(())
+    })
+}
+
+/// Hashes from a leaf up to the root, returning the hash computed on each level.
+///
+/// `hash_node` is called with the level prefix, the left-hand and the right-hand node.
+#[doc(hidden)]
+pub(crate) fn fold_proof_path<T, E, F>(
+    leaf_index: usize,
+    num_leaves: usize,
+    leaf_hash: &T,
+    siblings: &[T],
+    starting_prefix: u8,
+    wrap_to_value: u8,
+    mut hash_node: F,
+) -> Result<Vec<T>, E>
+where
+    T: Clone,
+    F: FnMut(&[u8; 1], &T, &T) -> Result<T, E>,
+{
+    let mut level_prefix = starting_prefix;
+    let mut level_count = num_leaves;
+    let mut current_idx = leaf_index;
+    let mut hash = leaf_hash.clone();
+    let mut result_hashes = Vec::with_capacity(siblings.len());
+
+    for sibling_hash in siblings {
+        let prefix = [node_prefix(
+            level_prefix,
+            level_count,
+            current_idx,
+            wrap_to_value,
+        )];
+        hash = if is_odd(current_idx) {
+            hash_node(&prefix, sibling_hash, &hash)?
+        } else {
+            hash_node(&prefix, &hash, sibling_hash)?
+        };
+        result_hashes.push(hash.clone());
+
+        level_prefix = next_level_prefix(level_prefix, level_count, wrap_to_value);
+        current_idx /= 2;
+        level_count = add_1_if_odd(level_count) / 2;
+    }
+    Ok(result_hashes)
+}
+
+/// CRC-32 as used by zlib and PNG.
+#[doc(hidden)]
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    crc32_update(0, bytes)
+}
+
+/// Continues the CRC-32 `crc` of earlier bytes with `bytes`, like zlib's `crc32`.
+#[doc(hidden)]
+pub(crate) fn crc32_update(crc: u32, bytes: &[u8]) -> u32 {
+    let mut crc = !crc;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
 /// if there are more than u8::MAX levels we wrap around to the wrap_to_value parameter value.
 #[inline]
 #[doc(hidden)]